@@ -0,0 +1,27 @@
+use super::role_binding::SubjectType;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Grants one permission on one resource to a user or team, for sharing
+/// below the granularity of role bindings. Resources are identified by
+/// their path, e.g. `workspace:<id>/folder:<id>/file:<id>`.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "acl_entry")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub resource: String,
+    pub principal_type: SubjectType,
+    /// A user or team id, depending on `principal_type`. Not a foreign key.
+    pub principal_id: Uuid,
+    pub permission: String,
+    /// Whether the entry also applies to everything beneath `resource`.
+    pub inherited: bool,
+    pub granted_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
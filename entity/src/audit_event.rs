@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Append-only record of a security-relevant action. Ids are not foreign
+/// keys, so the trail outlives the users and resources it mentions.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_event")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// Who did it. `None` for the admin key and the system itself.
+    pub actor_id: Option<Uuid>,
+    /// Dotted verb, e.g. `team.deleted`.
+    pub action: String,
+    /// Kind of thing acted on, e.g. `team`.
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    /// Free-form context, such as names that may later be deleted.
+    pub detail: Json,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
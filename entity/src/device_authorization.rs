@@ -0,0 +1,59 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "device_authorization")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub device_code_hash: String,
+    /// Short code the user types on the verification page, without the dash.
+    pub user_code: String,
+    pub client_id: String,
+    /// Space-separated scopes requested by the device.
+    pub scopes: String,
+    /// Becomes the name of the API key, e.g. the CLI host name.
+    pub device_name: String,
+    pub status: DeviceAuthorizationStatus,
+    /// The approving (or denying) user.
+    pub user_id: Option<Uuid>,
+    /// Minimum seconds between polls. Grows on `slow_down`.
+    pub interval_secs: i32,
+    pub last_polled_at: Option<DateTimeUtc>,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceAuthorizationStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "denied")]
+    Denied,
+    /// The device collected its API key.
+    #[sea_orm(string_value = "consumed")]
+    Consumed,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,64 @@
+use super::user::UserRole;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A request for a temporary role, and the grant once it is active. The
+/// role applies while `status` is active and `expires_at` is in the future.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "elevation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub role: UserRole,
+    pub reason: String,
+    pub duration_minutes: i32,
+    pub status: ElevationStatus,
+    /// The admin who approved or denied the request. `None` when it needed
+    /// no approval, or the static admin key decided.
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTimeUtc>,
+    /// Set when the elevation becomes active.
+    pub expires_at: Option<DateTimeUtc>,
+    /// When it was revoked or marked expired.
+    pub ended_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum ElevationStatus {
+    /// Waiting for another admin.
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "active")]
+    Active,
+    #[sea_orm(string_value = "denied")]
+    Denied,
+    /// Ended early by the user or an admin.
+    #[sea_orm(string_value = "revoked")]
+    Revoked,
+    /// Ran out, or was never decided on in time.
+    #[sea_orm(string_value = "expired")]
+    Expired,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,27 @@
+use super::role_binding::SubjectType;
+use super::user::UserRole;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Lets a user, or every member of a team, ask for `role` for up to
+/// `max_minutes` at a time.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "elevation_eligibility")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub subject_type: SubjectType,
+    /// A user or team id, depending on `subject_type`. Not a foreign key.
+    pub subject_id: Uuid,
+    pub role: UserRole,
+    pub max_minutes: i32,
+    /// Whether another admin has to approve each request.
+    pub requires_approval: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
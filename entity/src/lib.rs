@@ -1,6 +1,42 @@
+pub mod acl_entry;
+pub mod api_key;
+pub mod audit_event;
+pub mod device_authorization;
+pub mod elevation;
+pub mod elevation_eligibility;
+pub mod email_verification;
+pub mod federated_identity;
+pub mod federated_login;
+pub mod oauth_access_token;
+pub mod oauth_authorization_code;
+pub mod oauth_client;
+pub mod oauth_consent;
+pub mod passkey_credential;
+pub mod password_token;
+pub mod permission;
+pub mod plan;
+pub mod plan_assignment;
+pub mod quota_usage;
+pub mod rbac_role;
+pub mod rbac_role_permission;
+pub mod recovery_token;
+pub mod role_binding;
+pub mod session;
+pub mod signup_invite;
+pub mod team;
+pub mod team_deletion;
+pub mod team_invite;
+pub mod team_membership;
+pub mod totp_recovery_code;
 pub mod user;
+pub mod user_totp;
+pub mod webauthn_ceremony;
+pub mod workspace_key;
 
 /*
- Simplified, self-hostable model: users have a name, email, and hashed auth key.
- Teams/invites were removed, so owning a valid token is the only gate to access.
+ Self-hostable model: users have a name, email, and hashed auth key, and can
+ share workspaces through teams. Team memberships travel with gRPC validation
+ so services can scope storage per workspace, and role bindings and per-file
+ ACL entries answer finer questions through gRPC authorization. Plans cap
+ what a user or team may store, with usage counted here for every service.
  */
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A relying party allowed to sign users in through our OpenID provider.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_client")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// The public identifier clients send as `client_id`.
+    pub client_id: String,
+    pub name: String,
+    /// `None` for public clients, which must rely on PKCE alone.
+    pub secret_hash: Option<String>,
+    /// JSON array of exact redirect URIs.
+    pub redirect_uris: Json,
+    pub created_at: DateTimeUtc,
+    pub revoked_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An action a role can grant, named like `file.delete`. A trailing `.*`
+/// covers every action under that prefix, and `*` covers everything.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "permission")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::rbac_role_permission::Entity")]
+    RbacRolePermission,
+}
+
+impl Related<super::rbac_role_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RbacRolePermission.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Limits and feature flags assigned to users or teams. A missing limit
+/// means unlimited.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "plan")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub name: String,
+    pub max_bytes: Option<i64>,
+    pub max_files: Option<i64>,
+    pub max_share_links: Option<i64>,
+    /// Names of the features the plan turns on, as a JSON array of strings.
+    pub features: Json,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::plan_assignment::Entity")]
+    PlanAssignment,
+}
+
+impl Related<super::plan_assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PlanAssignment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
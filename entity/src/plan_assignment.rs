@@ -0,0 +1,36 @@
+use super::role_binding::SubjectType;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The plan a user or team is on. Each subject has at most one.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "plan_assignment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub subject_type: SubjectType,
+    /// A user or team id, depending on `subject_type`. Not a foreign key.
+    pub subject_id: Uuid,
+    pub plan_id: Uuid,
+    pub assigned_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plan::Entity",
+        from = "Column::PlanId",
+        to = "super::plan::Column::Id",
+        on_delete = "Restrict"
+    )]
+    Plan,
+}
+
+impl Related<super::plan::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Plan.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
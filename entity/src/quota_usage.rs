@@ -0,0 +1,24 @@
+use super::role_binding::SubjectType;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What a user or team currently uses against its plan's limits, as
+/// reserved and released by the services that store the data.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "quota_usage")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub subject_type: SubjectType,
+    /// A user or team id, depending on `subject_type`. Not a foreign key.
+    pub subject_id: Uuid,
+    pub bytes: i64,
+    pub files: i64,
+    pub share_links: i64,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A named set of permissions, bound to subjects through role bindings.
+/// Separate from the account role on `user`, which only gates this service.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "rbac_role")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::rbac_role_permission::Entity")]
+    RbacRolePermission,
+    #[sea_orm(has_many = "super::role_binding::Entity")]
+    RoleBinding,
+}
+
+impl Related<super::rbac_role_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RbacRolePermission.def()
+    }
+}
+
+impl Related<super::role_binding::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RoleBinding.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,43 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "rbac_role_permission")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub role_id: Uuid,
+    pub permission: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::rbac_role::Entity",
+        from = "Column::RoleId",
+        to = "super::rbac_role::Column::Id",
+        on_delete = "Cascade"
+    )]
+    RbacRole,
+    #[sea_orm(
+        belongs_to = "super::permission::Entity",
+        from = "Column::Permission",
+        to = "super::permission::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Permission,
+}
+
+impl Related<super::rbac_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RbacRole.def()
+    }
+}
+
+impl Related<super::permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Permission.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Grants a role to a subject on a resource scope. Scopes are `/`-separated
+/// paths such as `workspace:<id>` and cover everything beneath them; `*`
+/// covers every resource.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "role_binding")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub subject_type: SubjectType,
+    /// A user or team id, depending on `subject_type`. Not a foreign key.
+    pub subject_id: Uuid,
+    pub role_id: Uuid,
+    pub scope: String,
+    /// Requirements on the request context, all of which must hold. See
+    /// `BindingCondition` in the service crate for the shape.
+    pub conditions: Json,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectType {
+    #[sea_orm(string_value = "user")]
+    User,
+    /// Applies to every member of the team.
+    #[sea_orm(string_value = "team")]
+    Team,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::rbac_role::Entity",
+        from = "Column::RoleId",
+        to = "super::rbac_role::Column::Id",
+        on_delete = "Cascade"
+    )]
+    RbacRole,
+}
+
+impl Related<super::rbac_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RbacRole.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
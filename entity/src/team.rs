@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A shared workspace. Files are scoped to it by the file service.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "team")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::team_membership::Entity")]
+    TeamMembership,
+}
+
+impl Related<super::team_membership::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TeamMembership.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
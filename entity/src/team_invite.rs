@@ -0,0 +1,55 @@
+use super::team_membership::TeamRole;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An emailed invitation to join a team. Single use; resending rotates the secret.
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "team_invite")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub team_id: Uuid,
+    /// Lowercased address the invite was sent to.
+    pub email: String,
+    /// Role given on acceptance.
+    pub role: TeamRole,
+    pub secret_hash: String,
+    pub invited_by: Uuid,
+    pub expires_at: DateTimeUtc,
+    pub sent_at: DateTimeUtc,
+    pub accepted_at: Option<DateTimeUtc>,
+    pub cancelled_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::team::Entity",
+        from = "Column::TeamId",
+        to = "super::team::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Team,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::InvitedBy",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::team::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Team.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
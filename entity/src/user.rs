@@ -8,11 +8,50 @@ pub struct Model {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    /// Address the user asked to move to. It replaces `email` once verified.
+    pub pending_email: Option<String>,
     pub auth_hash: String,
+    /// Argon2 hash of an optional login password. `None` means API token only.
+    pub password_hash: Option<String>,
+    pub password_changed_at: Option<DateTimeUtc>,
+    pub email_verified_at: Option<DateTimeUtc>,
+    pub role: UserRole,
+    pub status: UserStatus,
+    pub status_reason: Option<String>,
+    pub status_changed_at: Option<DateTimeUtc>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum UserRole {
+    #[sea_orm(string_value = "admin")]
+    Admin,
+    #[sea_orm(string_value = "member")]
+    Member,
+    #[sea_orm(string_value = "read_only")]
+    ReadOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    #[sea_orm(string_value = "active")]
+    Active,
+    /// Cut off by an admin.
+    #[sea_orm(string_value = "suspended")]
+    Suspended,
+    /// Cut off for security reasons, e.g. a suspected token leak.
+    #[sea_orm(string_value = "locked")]
+    Locked,
+    /// Waiting on email verification.
+    #[sea_orm(string_value = "pending")]
+    Pending,
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
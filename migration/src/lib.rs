@@ -1,13 +1,59 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_user_table;
+mod m20261018_000002_add_email_verification;
+mod m20261018_000003_add_user_role_and_signup_invites;
+mod m20261018_000004_add_user_status;
+mod m20261018_000005_create_recovery_token;
+mod m20261018_000006_create_totp;
+mod m20261018_000007_create_session_and_passkey;
+mod m20261018_000008_add_password;
+mod m20261018_000009_add_session_csrf;
+mod m20261018_000010_create_api_key_and_device_grant;
+mod m20261018_000011_create_oidc_provider;
+mod m20261018_000012_create_federated_identity;
+mod m20261018_000013_create_team;
+mod m20261018_000014_create_team_invite;
+mod m20261018_000015_create_audit_event_and_team_deletion;
+mod m20261018_000016_create_rbac;
+mod m20261018_000017_add_role_binding_conditions;
+mod m20261018_000018_create_acl_entry;
+mod m20261018_000019_create_workspace_key;
+mod m20261018_000020_create_plans;
+mod m20261018_000021_create_elevation;
+mod m20261018_000022_add_user_pending_email;
+mod m20261019_000023_add_totp_failed_attempts;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_user_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_user_table::Migration),
+            Box::new(m20261018_000002_add_email_verification::Migration),
+            Box::new(m20261018_000003_add_user_role_and_signup_invites::Migration),
+            Box::new(m20261018_000004_add_user_status::Migration),
+            Box::new(m20261018_000005_create_recovery_token::Migration),
+            Box::new(m20261018_000006_create_totp::Migration),
+            Box::new(m20261018_000007_create_session_and_passkey::Migration),
+            Box::new(m20261018_000008_add_password::Migration),
+            Box::new(m20261018_000009_add_session_csrf::Migration),
+            Box::new(m20261018_000010_create_api_key_and_device_grant::Migration),
+            Box::new(m20261018_000011_create_oidc_provider::Migration),
+            Box::new(m20261018_000012_create_federated_identity::Migration),
+            Box::new(m20261018_000013_create_team::Migration),
+            Box::new(m20261018_000014_create_team_invite::Migration),
+            Box::new(m20261018_000015_create_audit_event_and_team_deletion::Migration),
+            Box::new(m20261018_000016_create_rbac::Migration),
+            Box::new(m20261018_000017_add_role_binding_conditions::Migration),
+            Box::new(m20261018_000018_create_acl_entry::Migration),
+            Box::new(m20261018_000019_create_workspace_key::Migration),
+            Box::new(m20261018_000020_create_plans::Migration),
+            Box::new(m20261018_000021_create_elevation::Migration),
+            Box::new(m20261018_000022_add_user_pending_email::Migration),
+            Box::new(m20261019_000023_add_totp_failed_attempts::Migration),
+        ]
     }
 
     fn migration_table_name() -> DynIden {
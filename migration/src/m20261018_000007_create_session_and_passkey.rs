@@ -0,0 +1,210 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Session::Table)
+                    .col(ColumnDef::new(Session::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Session::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Session::SecretHash).string().not_null())
+                    .col(ColumnDef::new(Session::AuthMethod).string().not_null())
+                    .col(ColumnDef::new(Session::IpAddress).string().null())
+                    .col(ColumnDef::new(Session::UserAgent).string().null())
+                    .col(
+                        ColumnDef::new(Session::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Session::LastSeenAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Session::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Session::RevokedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_session_user")
+                            .from(Session::Table, Session::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_session_user")
+                    .table(Session::Table)
+                    .col(Session::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasskeyCredential::Table)
+                    .col(
+                        ColumnDef::new(PasskeyCredential::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PasskeyCredential::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(PasskeyCredential::CredentialId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PasskeyCredential::Name).string().not_null())
+                    .col(ColumnDef::new(PasskeyCredential::Passkey).json().not_null())
+                    .col(
+                        ColumnDef::new(PasskeyCredential::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PasskeyCredential::LastUsedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_passkey_credential_user")
+                            .from(PasskeyCredential::Table, PasskeyCredential::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_passkey_credential_id")
+                    .table(PasskeyCredential::Table)
+                    .col(PasskeyCredential::CredentialId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebauthnCeremony::Table)
+                    .col(
+                        ColumnDef::new(WebauthnCeremony::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebauthnCeremony::UserId).uuid().not_null())
+                    .col(ColumnDef::new(WebauthnCeremony::Kind).string().not_null())
+                    .col(ColumnDef::new(WebauthnCeremony::State).json().not_null())
+                    .col(
+                        ColumnDef::new(WebauthnCeremony::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webauthn_ceremony_user")
+                            .from(WebauthnCeremony::Table, WebauthnCeremony::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(WebauthnCeremony::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(PasskeyCredential::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Session::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Session {
+    Table,
+    Id,
+    UserId,
+    SecretHash,
+    AuthMethod,
+    IpAddress,
+    UserAgent,
+    CreatedAt,
+    LastSeenAt,
+    ExpiresAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum PasskeyCredential {
+    Table,
+    Id,
+    UserId,
+    CredentialId,
+    Name,
+    Passkey,
+    CreatedAt,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum WebauthnCeremony {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    State,
+    ExpiresAt,
+}
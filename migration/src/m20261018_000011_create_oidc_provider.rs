@@ -0,0 +1,343 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthClient::Table)
+                    .col(
+                        ColumnDef::new(OauthClient::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OauthClient::ClientId).string().not_null())
+                    .col(ColumnDef::new(OauthClient::Name).string().not_null())
+                    .col(ColumnDef::new(OauthClient::SecretHash).string().null())
+                    .col(ColumnDef::new(OauthClient::RedirectUris).json().not_null())
+                    .col(
+                        ColumnDef::new(OauthClient::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OauthClient::RevokedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_oauth_client_client_id")
+                    .table(OauthClient::Table)
+                    .col(OauthClient::ClientId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthConsent::Table)
+                    .col(
+                        ColumnDef::new(OauthConsent::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OauthConsent::UserId).uuid().not_null())
+                    .col(ColumnDef::new(OauthConsent::ClientId).uuid().not_null())
+                    .col(ColumnDef::new(OauthConsent::Scopes).string().not_null())
+                    .col(
+                        ColumnDef::new(OauthConsent::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OauthConsent::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_consent_user")
+                            .from(OauthConsent::Table, OauthConsent::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_consent_client")
+                            .from(OauthConsent::Table, OauthConsent::ClientId)
+                            .to(OauthClient::Table, OauthClient::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_oauth_consent_user_client")
+                    .table(OauthConsent::Table)
+                    .col(OauthConsent::UserId)
+                    .col(OauthConsent::ClientId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthAuthorizationCode::Table)
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::SecretHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::ClientId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::RedirectUri)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::Scopes)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::Nonce)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::CodeChallenge)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::AuthTime)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::ConsumedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAuthorizationCode::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_authorization_code_user")
+                            .from(
+                                OauthAuthorizationCode::Table,
+                                OauthAuthorizationCode::UserId,
+                            )
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_authorization_code_client")
+                            .from(
+                                OauthAuthorizationCode::Table,
+                                OauthAuthorizationCode::ClientId,
+                            )
+                            .to(OauthClient::Table, OauthClient::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthAccessToken::Table)
+                    .col(
+                        ColumnDef::new(OauthAccessToken::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAccessToken::SecretHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OauthAccessToken::ClientId).uuid().not_null())
+                    .col(ColumnDef::new(OauthAccessToken::UserId).uuid().not_null())
+                    .col(ColumnDef::new(OauthAccessToken::Scopes).string().not_null())
+                    .col(
+                        ColumnDef::new(OauthAccessToken::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAccessToken::RevokedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthAccessToken::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_access_token_user")
+                            .from(OauthAccessToken::Table, OauthAccessToken::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_access_token_client")
+                            .from(OauthAccessToken::Table, OauthAccessToken::ClientId)
+                            .to(OauthClient::Table, OauthClient::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(OauthAccessToken::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(OauthAuthorizationCode::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(OauthConsent::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(OauthClient::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum OauthClient {
+    Table,
+    Id,
+    ClientId,
+    Name,
+    SecretHash,
+    RedirectUris,
+    CreatedAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum OauthConsent {
+    Table,
+    Id,
+    UserId,
+    ClientId,
+    Scopes,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OauthAuthorizationCode {
+    Table,
+    Id,
+    SecretHash,
+    ClientId,
+    UserId,
+    RedirectUri,
+    Scopes,
+    Nonce,
+    CodeChallenge,
+    AuthTime,
+    ExpiresAt,
+    ConsumedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OauthAccessToken {
+    Table,
+    Id,
+    SecretHash,
+    ClientId,
+    UserId,
+    Scopes,
+    ExpiresAt,
+    RevokedAt,
+    CreatedAt,
+}
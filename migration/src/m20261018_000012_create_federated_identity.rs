@@ -0,0 +1,141 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FederatedIdentity::Table)
+                    .col(
+                        ColumnDef::new(FederatedIdentity::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(FederatedIdentity::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(FederatedIdentity::Provider)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(FederatedIdentity::Subject)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(FederatedIdentity::Email).string().not_null())
+                    .col(
+                        ColumnDef::new(FederatedIdentity::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(FederatedIdentity::LastLoginAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_federated_identity_user")
+                            .from(FederatedIdentity::Table, FederatedIdentity::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_federated_identity_provider_subject")
+                    .table(FederatedIdentity::Table)
+                    .col(FederatedIdentity::Provider)
+                    .col(FederatedIdentity::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(FederatedLogin::Table)
+                    .col(
+                        ColumnDef::new(FederatedLogin::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(FederatedLogin::Provider).string().not_null())
+                    .col(ColumnDef::new(FederatedLogin::Nonce).string().not_null())
+                    .col(
+                        ColumnDef::new(FederatedLogin::CodeVerifier)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(FederatedLogin::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FederatedLogin::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FederatedIdentity::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum FederatedIdentity {
+    Table,
+    Id,
+    UserId,
+    Provider,
+    Subject,
+    Email,
+    CreatedAt,
+    LastLoginAt,
+}
+
+#[derive(DeriveIden)]
+enum FederatedLogin {
+    Table,
+    Id,
+    Provider,
+    Nonce,
+    CodeVerifier,
+    ExpiresAt,
+}
@@ -0,0 +1,125 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TeamInvite::Table)
+                    .col(
+                        ColumnDef::new(TeamInvite::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TeamInvite::TeamId).uuid().not_null())
+                    .col(ColumnDef::new(TeamInvite::Email).string().not_null())
+                    .col(
+                        ColumnDef::new(TeamInvite::Role)
+                            .string()
+                            .not_null()
+                            .default("member"),
+                    )
+                    .col(ColumnDef::new(TeamInvite::SecretHash).string().not_null())
+                    .col(ColumnDef::new(TeamInvite::InvitedBy).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TeamInvite::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TeamInvite::SentAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TeamInvite::AcceptedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TeamInvite::CancelledAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TeamInvite::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_team_invite_team")
+                            .from(TeamInvite::Table, TeamInvite::TeamId)
+                            .to(Team::Table, Team::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_team_invite_invited_by")
+                            .from(TeamInvite::Table, TeamInvite::InvitedBy)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_team_invite_team")
+                    .table(TeamInvite::Table)
+                    .col(TeamInvite::TeamId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(TeamInvite::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Team {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum TeamInvite {
+    Table,
+    Id,
+    TeamId,
+    Email,
+    Role,
+    SecretHash,
+    InvitedBy,
+    ExpiresAt,
+    SentAt,
+    AcceptedAt,
+    CancelledAt,
+    CreatedAt,
+}
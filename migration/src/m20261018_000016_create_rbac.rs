@@ -0,0 +1,214 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Permission::Table)
+                    .col(
+                        ColumnDef::new(Permission::Name)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Permission::Description).string().null())
+                    .col(
+                        ColumnDef::new(Permission::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RbacRole::Table)
+                    .col(ColumnDef::new(RbacRole::Id).uuid().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(RbacRole::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(RbacRole::Description).string().null())
+                    .col(
+                        ColumnDef::new(RbacRole::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RbacRolePermission::Table)
+                    .col(
+                        ColumnDef::new(RbacRolePermission::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RbacRolePermission::RoleId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(RbacRolePermission::Permission)
+                            .string()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rbac_role_permission_role")
+                            .from(RbacRolePermission::Table, RbacRolePermission::RoleId)
+                            .to(RbacRole::Table, RbacRole::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rbac_role_permission_permission")
+                            .from(RbacRolePermission::Table, RbacRolePermission::Permission)
+                            .to(Permission::Table, Permission::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_rbac_role_permission_role_permission")
+                    .table(RbacRolePermission::Table)
+                    .col(RbacRolePermission::RoleId)
+                    .col(RbacRolePermission::Permission)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoleBinding::Table)
+                    .col(
+                        ColumnDef::new(RoleBinding::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RoleBinding::SubjectType).string().not_null())
+                    .col(ColumnDef::new(RoleBinding::SubjectId).uuid().not_null())
+                    .col(ColumnDef::new(RoleBinding::RoleId).uuid().not_null())
+                    .col(ColumnDef::new(RoleBinding::Scope).string().not_null())
+                    .col(ColumnDef::new(RoleBinding::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(RoleBinding::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_binding_role")
+                            .from(RoleBinding::Table, RoleBinding::RoleId)
+                            .to(RbacRole::Table, RbacRole::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_role_binding_subject_role_scope")
+                    .table(RoleBinding::Table)
+                    .col(RoleBinding::SubjectType)
+                    .col(RoleBinding::SubjectId)
+                    .col(RoleBinding::RoleId)
+                    .col(RoleBinding::Scope)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(RoleBinding::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(RbacRolePermission::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(RbacRole::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Permission::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Permission {
+    Table,
+    Name,
+    Description,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum RbacRole {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum RbacRolePermission {
+    Table,
+    Id,
+    RoleId,
+    Permission,
+}
+
+#[derive(DeriveIden)]
+enum RoleBinding {
+    Table,
+    Id,
+    SubjectType,
+    SubjectId,
+    RoleId,
+    Scope,
+    CreatedBy,
+    CreatedAt,
+}
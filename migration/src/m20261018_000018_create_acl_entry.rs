@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AclEntry::Table)
+                    .col(ColumnDef::new(AclEntry::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(AclEntry::Resource).string().not_null())
+                    .col(ColumnDef::new(AclEntry::PrincipalType).string().not_null())
+                    .col(ColumnDef::new(AclEntry::PrincipalId).uuid().not_null())
+                    .col(ColumnDef::new(AclEntry::Permission).string().not_null())
+                    .col(
+                        ColumnDef::new(AclEntry::Inherited)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(AclEntry::GrantedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(AclEntry::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_acl_entry_resource_principal_permission")
+                    .table(AclEntry::Table)
+                    .col(AclEntry::Resource)
+                    .col(AclEntry::PrincipalType)
+                    .col(AclEntry::PrincipalId)
+                    .col(AclEntry::Permission)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_acl_entry_principal")
+                    .table(AclEntry::Table)
+                    .col(AclEntry::PrincipalType)
+                    .col(AclEntry::PrincipalId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AclEntry::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AclEntry {
+    Table,
+    Id,
+    Resource,
+    PrincipalType,
+    PrincipalId,
+    Permission,
+    Inherited,
+    GrantedBy,
+    CreatedAt,
+}
@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkspaceKey::Table)
+                    .col(
+                        ColumnDef::new(WorkspaceKey::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WorkspaceKey::TeamId)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WorkspaceKey::MasterKeyId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WorkspaceKey::WrappedKey).string().not_null())
+                    .col(
+                        ColumnDef::new(WorkspaceKey::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WorkspaceKey::RotatedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workspace_key_team")
+                            .from(WorkspaceKey::Table, WorkspaceKey::TeamId)
+                            .to(Team::Table, Team::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workspace_key_master_key_id")
+                    .table(WorkspaceKey::Table)
+                    .col(WorkspaceKey::MasterKeyId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(WorkspaceKey::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Team {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum WorkspaceKey {
+    Table,
+    Id,
+    TeamId,
+    MasterKeyId,
+    WrappedKey,
+    CreatedAt,
+    RotatedAt,
+}
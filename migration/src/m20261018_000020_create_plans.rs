@@ -0,0 +1,200 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Plan::Table)
+                    .col(ColumnDef::new(Plan::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Plan::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Plan::MaxBytes).big_integer().null())
+                    .col(ColumnDef::new(Plan::MaxFiles).big_integer().null())
+                    .col(ColumnDef::new(Plan::MaxShareLinks).big_integer().null())
+                    .col(
+                        ColumnDef::new(Plan::Features)
+                            .json()
+                            .not_null()
+                            .default(Expr::cust("'[]'")),
+                    )
+                    .col(
+                        ColumnDef::new(Plan::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Plan::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlanAssignment::Table)
+                    .col(
+                        ColumnDef::new(PlanAssignment::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PlanAssignment::SubjectType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PlanAssignment::SubjectId).uuid().not_null())
+                    .col(ColumnDef::new(PlanAssignment::PlanId).uuid().not_null())
+                    .col(ColumnDef::new(PlanAssignment::AssignedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(PlanAssignment::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_plan_assignment_plan")
+                            .from(PlanAssignment::Table, PlanAssignment::PlanId)
+                            .to(Plan::Table, Plan::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_plan_assignment_subject")
+                    .table(PlanAssignment::Table)
+                    .col(PlanAssignment::SubjectType)
+                    .col(PlanAssignment::SubjectId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuotaUsage::Table)
+                    .col(
+                        ColumnDef::new(QuotaUsage::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(QuotaUsage::SubjectType).string().not_null())
+                    .col(ColumnDef::new(QuotaUsage::SubjectId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(QuotaUsage::Bytes)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(QuotaUsage::Files)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(QuotaUsage::ShareLinks)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(QuotaUsage::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_quota_usage_subject")
+                    .table(QuotaUsage::Table)
+                    .col(QuotaUsage::SubjectType)
+                    .col(QuotaUsage::SubjectId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(QuotaUsage::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(PlanAssignment::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Plan::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Plan {
+    Table,
+    Id,
+    Name,
+    MaxBytes,
+    MaxFiles,
+    MaxShareLinks,
+    Features,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PlanAssignment {
+    Table,
+    Id,
+    SubjectType,
+    SubjectId,
+    PlanId,
+    AssignedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum QuotaUsage {
+    Table,
+    Id,
+    SubjectType,
+    SubjectId,
+    Bytes,
+    Files,
+    ShareLinks,
+    UpdatedAt,
+}
@@ -0,0 +1,188 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ElevationEligibility::Table)
+                    .col(
+                        ColumnDef::new(ElevationEligibility::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::SubjectType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::SubjectId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::Role)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::MaxMinutes)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::RequiresApproval)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::CreatedBy)
+                            .uuid()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ElevationEligibility::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_elevation_eligibility_subject_role")
+                    .table(ElevationEligibility::Table)
+                    .col(ElevationEligibility::SubjectType)
+                    .col(ElevationEligibility::SubjectId)
+                    .col(ElevationEligibility::Role)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Elevation::Table)
+                    .col(
+                        ColumnDef::new(Elevation::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Elevation::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Elevation::Role).string().not_null())
+                    .col(ColumnDef::new(Elevation::Reason).string().not_null())
+                    .col(
+                        ColumnDef::new(Elevation::DurationMinutes)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Elevation::Status).string().not_null())
+                    .col(ColumnDef::new(Elevation::DecidedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(Elevation::DecidedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Elevation::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Elevation::EndedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Elevation::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_elevation_user")
+                            .from(Elevation::Table, Elevation::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_elevation_user_status")
+                    .table(Elevation::Table)
+                    .col(Elevation::UserId)
+                    .col(Elevation::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Elevation::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ElevationEligibility::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ElevationEligibility {
+    Table,
+    Id,
+    SubjectType,
+    SubjectId,
+    Role,
+    MaxMinutes,
+    RequiresApproval,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Elevation {
+    Table,
+    Id,
+    UserId,
+    Role,
+    Reason,
+    DurationMinutes,
+    Status,
+    DecidedBy,
+    DecidedAt,
+    ExpiresAt,
+    EndedAt,
+    CreatedAt,
+}
@@ -1,4 +1,6 @@
+use crate::utils::kms::{Keyring, MasterKey};
 use std::env;
+use std::str::FromStr;
 use std::sync::OnceLock;
 
 #[derive(Clone, Debug)]
@@ -7,7 +9,50 @@ pub struct EnvConfig {
     pub db_url: String,
     pub admin_key: String,
     pub resend_key: String,
+    /// Externally reachable base URL, used to build links in outgoing mail.
+    pub public_url: String,
+    pub registration: RegistrationConfig,
+    pub webauthn: WebauthnConfig,
+    pub oidc: OidcConfig,
+    /// External identity providers users can sign in with.
+    pub federation: Vec<FederatedProviderConfig>,
+    /// Bearer token the HR system's SCIM client presents. SCIM is off when unset.
+    pub scim_token: Option<String>,
     pub grpc: GrpcConfig,
+    /// Master keys for workspace data keys. Key management is off when unset.
+    pub kms: Option<Keyring>,
+}
+
+/// Settings for acting as an OpenID Connect provider. The issuer is `public_url`.
+#[derive(Clone, Debug)]
+pub struct OidcConfig {
+    /// PKCS#8 PEM RSA key used to sign ID tokens. When unset, a key is
+    /// generated at startup and tokens stop verifying after a restart.
+    pub signing_key_pem: Option<String>,
+}
+
+/// An external OpenID provider, e.g. the company IdP.
+#[derive(Clone, Debug)]
+pub struct FederatedProviderConfig {
+    /// Short slug used in URLs, e.g. `corp`.
+    pub id: String,
+    /// Shown on the login screen.
+    pub name: String,
+    /// Issuer URL; metadata is discovered from it.
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Create accounts for people who don't have one yet.
+    pub auto_create: bool,
+}
+
+/// Relying party settings for passkeys.
+#[derive(Clone, Debug)]
+pub struct WebauthnConfig {
+    /// Effective domain passkeys are bound to, e.g. `ledger.example.com`.
+    pub rp_id: String,
+    /// Origin the browser reports during ceremonies, e.g. `https://ledger.example.com`.
+    pub rp_origin: String,
 }
 
 #[derive(Clone, Debug)]
@@ -16,26 +61,162 @@ pub struct GrpcConfig {
     pub auth_key: String,
 }
 
+/// Who may create an account through the public `/signup` route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistrationMode {
+    /// Only admins create accounts.
+    Closed,
+    /// Signup needs an admin-issued invite code.
+    InviteOnly,
+    /// Signup is open to addresses on `allowed_domains`, with or without an invite.
+    DomainRestricted,
+    /// Anybody may sign up.
+    Open,
+}
+
+impl FromStr for RegistrationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "closed" => Ok(Self::Closed),
+            "invite_only" => Ok(Self::InviteOnly),
+            "domain_restricted" => Ok(Self::DomainRestricted),
+            "open" => Ok(Self::Open),
+            other => Err(format!("unknown registration mode: {other}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegistrationConfig {
+    pub mode: RegistrationMode,
+    /// Lowercased domains. When non-empty, public signups must match one.
+    pub allowed_domains: Vec<String>,
+    /// Lowercased domains that can never register, even through an admin.
+    pub denied_domains: Vec<String>,
+    /// Refuse the bundled list of throwaway mail providers.
+    pub block_disposable: bool,
+}
+
 impl EnvConfig {
     fn get_env(key: &str) -> String {
         env::var(key).unwrap_or_else(|_| panic!("Environment variable {} not set", key))
     }
 
+    fn get_env_or(key: &str, default: String) -> String {
+        env::var(key).unwrap_or(default)
+    }
+
+    fn get_env_list(key: &str) -> Vec<String> {
+        env::var(key)
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Providers listed in `FEDERATION_PROVIDERS`, each configured through
+    /// `FEDERATION_<ID>_ISSUER`, `_CLIENT_ID`, `_CLIENT_SECRET`, and optionally
+    /// `_NAME` and `_AUTO_CREATE`.
+    fn get_federation() -> Vec<FederatedProviderConfig> {
+        Self::get_env_list("FEDERATION_PROVIDERS")
+            .into_iter()
+            .map(|id| {
+                let key = |suffix: &str| format!("FEDERATION_{}_{suffix}", id.to_ascii_uppercase());
+                FederatedProviderConfig {
+                    name: Self::get_env_or(&key("NAME"), id.clone()),
+                    issuer: Self::get_env(&key("ISSUER")),
+                    client_id: Self::get_env(&key("CLIENT_ID")),
+                    client_secret: Self::get_env(&key("CLIENT_SECRET")),
+                    auto_create: Self::get_env_or(&key("AUTO_CREATE"), "true".into())
+                        .parse()
+                        .unwrap_or(true),
+                    id,
+                }
+            })
+            .collect()
+    }
+
+    /// Reads `<KEY>`, or the contents of the file named by `<KEY>_FILE`.
+    fn get_env_or_file(key: &str) -> Option<String> {
+        env::var(key).ok().or_else(|| {
+            env::var(format!("{key}_FILE")).ok().map(|path| {
+                std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{key}_FILE {path}: {e}"))
+            })
+        })
+    }
+
+    /// The master key from `KMS_MASTER_KEY`, and those being rotated away
+    /// from in `KMS_RETIRED_MASTER_KEYS`, comma or newline separated. Both
+    /// also read from a `_FILE`.
+    fn get_kms() -> Option<Keyring> {
+        let parse = |name: &str, encoded: &str| {
+            MasterKey::from_base64(encoded).unwrap_or_else(|e| panic!("{name}: {e}"))
+        };
+        let current = Self::get_env_or_file("KMS_MASTER_KEY").filter(|k| !k.trim().is_empty())?;
+        let retired = Self::get_env_or_file("KMS_RETIRED_MASTER_KEYS")
+            .unwrap_or_default()
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(|k| parse("KMS_RETIRED_MASTER_KEYS", k))
+            .collect();
+        Some(Keyring {
+            current: parse("KMS_MASTER_KEY", &current),
+            retired,
+        })
+    }
+
     pub fn from_env() -> Self {
         dotenv::dotenv().ok();
 
         let db_url: String = Self::get_env("POSTGRES_URI");
         let resend_key: String = Self::get_env("RESEND_KEY");
+        let port: i32 = Self::get_env("PORT").parse().unwrap_or(8081);
+        let public_url = Self::get_env_or("PUBLIC_URL", format!("http://localhost:{port}"));
+        let rp_origin = Self::get_env_or("WEBAUTHN_RP_ORIGIN", public_url.clone());
+        let default_rp_id = rp_origin
+            .split("://")
+            .nth(1)
+            .and_then(|rest| rest.split(['/', ':']).next())
+            .unwrap_or("localhost")
+            .to_string();
 
         EnvConfig {
-            port: Self::get_env("PORT").parse().unwrap_or(8081),
+            port,
             db_url,
             admin_key: Self::get_env("ADMIN_KEY"),
             resend_key,
+            public_url,
+            registration: RegistrationConfig {
+                mode: Self::get_env_or("REGISTRATION_MODE", "invite_only".into())
+                    .parse()
+                    .unwrap_or_else(|e| panic!("REGISTRATION_MODE: {e}")),
+                allowed_domains: Self::get_env_list("REGISTRATION_ALLOWED_DOMAINS"),
+                denied_domains: Self::get_env_list("REGISTRATION_DENIED_DOMAINS"),
+                block_disposable: Self::get_env_or("REGISTRATION_BLOCK_DISPOSABLE", "true".into())
+                    .parse()
+                    .unwrap_or(true),
+            },
+            webauthn: WebauthnConfig {
+                rp_id: Self::get_env_or("WEBAUTHN_RP_ID", default_rp_id),
+                rp_origin,
+            },
+            oidc: OidcConfig {
+                signing_key_pem: env::var("OIDC_SIGNING_KEY_FILE").ok().map(|path| {
+                    std::fs::read_to_string(&path)
+                        .unwrap_or_else(|e| panic!("OIDC_SIGNING_KEY_FILE {path}: {e}"))
+                }),
+            },
+            federation: Self::get_federation(),
+            scim_token: env::var("SCIM_TOKEN").ok().filter(|t| !t.is_empty()),
             grpc: GrpcConfig {
                 port: Self::get_env("GRPC_PORT").parse().unwrap_or(50051),
                 auth_key: Self::get_env("GRPC_AUTH_KEY"),
             },
+            kms: Self::get_kms(),
         }
     }
 }
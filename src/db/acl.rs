@@ -0,0 +1,188 @@
+use crate::db::{audit::record_audit, postgres_service::PostgresService};
+use crate::{
+    types::error::AppError,
+    utils::{
+        policy::{resource_ancestors, AclGrant},
+        token::new_id,
+    },
+};
+use chrono::Utc;
+use entity::acl_entry::{
+    ActiveModel as AclActive, Column as AclColumn, Entity as AclEntry, Model as AclModel,
+};
+use entity::role_binding::SubjectType;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Entries on `resource` itself, or inherited from one of its ancestors.
+fn effective_on(resource: &str) -> Condition {
+    let mut ancestors = resource_ancestors(resource);
+    ancestors.pop();
+    Condition::any().add(AclColumn::Resource.eq(resource)).add(
+        Condition::all()
+            .add(AclColumn::Resource.is_in(ancestors))
+            .add(AclColumn::Inherited.eq(true)),
+    )
+}
+
+impl PostgresService {
+    /// Grants `permission` on `resource` to a principal. Granting an entry
+    /// that already exists only updates whether it is inherited.
+    pub async fn grant_acl(
+        &self,
+        resource: &str,
+        principal_type: SubjectType,
+        principal_id: &Uuid,
+        permission: &str,
+        inherited: bool,
+        actor_id: Option<Uuid>,
+    ) -> Result<AclModel, AppError> {
+        if !self.subject_exists(principal_type, principal_id).await? {
+            return Err(AppError::Validation("The principal does not exist.".into()));
+        }
+        if !self.permission_exists(permission).await? {
+            return Err(AppError::Validation(
+                "The permission does not exist.".into(),
+            ));
+        }
+
+        let txn = self.database_connection.begin().await?;
+        let existing = AclEntry::find()
+            .filter(AclColumn::Resource.eq(resource))
+            .filter(AclColumn::PrincipalType.eq(principal_type))
+            .filter(AclColumn::PrincipalId.eq(*principal_id))
+            .filter(AclColumn::Permission.eq(permission))
+            .one(&txn)
+            .await?;
+
+        let entry = match existing {
+            Some(entry) if entry.inherited == inherited => return Ok(entry),
+            Some(entry) => {
+                let mut am: AclActive = entry.into();
+                am.inherited = Set(inherited);
+                am.update(&txn).await?
+            }
+            None => {
+                let entry = AclModel {
+                    id: new_id(),
+                    resource: resource.to_string(),
+                    principal_type,
+                    principal_id: *principal_id,
+                    permission: permission.to_string(),
+                    inherited,
+                    granted_by: actor_id,
+                    created_at: Utc::now(),
+                };
+                AclEntry::insert(AclActive::from(entry.clone()))
+                    .exec(&txn)
+                    .await?;
+                entry
+            }
+        };
+        record_audit(
+            &txn,
+            actor_id,
+            "acl.granted",
+            "acl_entry",
+            Some(entry.id),
+            json!({
+                "resource": entry.resource,
+                "principal_type": entry.principal_type,
+                "principal_id": entry.principal_id,
+                "permission": entry.permission,
+                "inherited": entry.inherited,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(entry)
+    }
+
+    pub async fn get_acl_entry(&self, entry_id: &Uuid) -> Result<AclModel, AppError> {
+        AclEntry::find_by_id(*entry_id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn revoke_acl(
+        &self,
+        entry: &AclModel,
+        actor_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        let res = AclEntry::delete_by_id(entry.id).exec(&txn).await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        record_audit(
+            &txn,
+            actor_id,
+            "acl.revoked",
+            "acl_entry",
+            Some(entry.id),
+            json!({
+                "resource": entry.resource,
+                "principal_type": entry.principal_type,
+                "principal_id": entry.principal_id,
+                "permission": entry.permission,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Entries that apply to `resource`: its own, then those inherited from
+    /// its ancestors, outermost first.
+    pub async fn list_acl(&self, resource: &str) -> Result<Vec<AclModel>, AppError> {
+        let mut entries = AclEntry::find()
+            .filter(effective_on(resource))
+            .order_by_asc(AclColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?;
+        entries.sort_by_key(|e| (e.resource == resource, e.resource.len()));
+        Ok(entries)
+    }
+
+    /// Entries that apply to `resource` and name the user or one of their teams.
+    pub async fn load_user_acl(
+        &self,
+        user_id: &Uuid,
+        resource: &str,
+    ) -> Result<Vec<AclGrant>, AppError> {
+        let team_ids = self.list_user_team_ids(user_id).await?;
+        let entries = AclEntry::find()
+            .filter(effective_on(resource))
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(AclColumn::PrincipalType.eq(SubjectType::User))
+                            .add(AclColumn::PrincipalId.eq(*user_id)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(AclColumn::PrincipalType.eq(SubjectType::Team))
+                            .add(AclColumn::PrincipalId.is_in(team_ids)),
+                    ),
+            )
+            .all(&self.database_connection)
+            .await?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| AclGrant {
+                entry_id: entry.id,
+                resource: entry.resource,
+                permission: entry.permission,
+                inherited: entry.inherited,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    db::session::LAST_SEEN_GRANULARITY_SECS,
+    types::{error::AppError, token::TokenType},
+    utils::{
+        scope::join_scopes,
+        token::{construct_token, encrypt, new_id, new_token},
+    },
+};
+use chrono::Utc;
+use entity::api_key::{
+    ActiveModel as ApiKeyActive, Column as ApiKeyColumn, Entity as ApiKey, Model as ApiKeyModel,
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+/// Inserts a named API key on any connection, so grants can create one
+/// inside their own transaction. Returns the key and its bearer token.
+pub(crate) async fn insert_api_key<C: ConnectionTrait>(
+    conn: &C,
+    user_id: &Uuid,
+    name: &str,
+    scopes: &[String],
+) -> Result<(ApiKeyModel, String), AppError> {
+    let id = new_id();
+    let secret = new_token(TokenType::ApiKey);
+    let secret_hash = encrypt(&secret).map_err(|_| {
+        AppError::Internal("There was an issue while encrypting the API key.".into())
+    })?;
+
+    let model = ApiKeyModel {
+        id,
+        user_id: *user_id,
+        name: name.to_string(),
+        scopes: join_scopes(scopes),
+        secret_hash,
+        created_at: Utc::now(),
+        last_used_at: None,
+        revoked_at: None,
+    };
+    ApiKey::insert(ApiKeyActive::from(model.clone()))
+        .exec(conn)
+        .await?;
+
+    Ok((model, construct_token(&id, &secret)))
+}
+
+impl PostgresService {
+    pub async fn create_api_key(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        scopes: &[String],
+    ) -> Result<(ApiKeyModel, String), AppError> {
+        insert_api_key(&self.database_connection, user_id, name, scopes).await
+    }
+
+    /// Looks up an API key that has not been revoked.
+    pub async fn get_live_api_key(&self, id: &Uuid) -> Result<ApiKeyModel, AppError> {
+        ApiKey::find_by_id(*id)
+            .filter(ApiKeyColumn::RevokedAt.is_null())
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn list_api_keys(&self, user_id: &Uuid) -> Result<Vec<ApiKeyModel>, AppError> {
+        Ok(ApiKey::find()
+            .filter(ApiKeyColumn::UserId.eq(*user_id))
+            .filter(ApiKeyColumn::RevokedAt.is_null())
+            .order_by_desc(ApiKeyColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    pub async fn revoke_api_key(&self, user_id: &Uuid, id: &Uuid) -> Result<(), AppError> {
+        let res = ApiKey::update_many()
+            .col_expr(ApiKeyColumn::RevokedAt, Expr::value(Utc::now()))
+            .filter(ApiKeyColumn::Id.eq(*id))
+            .filter(ApiKeyColumn::UserId.eq(*user_id))
+            .filter(ApiKeyColumn::RevokedAt.is_null())
+            .exec(&self.database_connection)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Stamps `last_used_at`, at most once per [`LAST_SEEN_GRANULARITY_SECS`].
+    pub async fn touch_api_key(&self, key: &ApiKeyModel) -> Result<(), AppError> {
+        let now = Utc::now();
+        if key
+            .last_used_at
+            .is_some_and(|t| (now - t).num_seconds() < LAST_SEEN_GRANULARITY_SECS)
+        {
+            return Ok(());
+        }
+        ApiKey::update_many()
+            .col_expr(ApiKeyColumn::LastUsedAt, Expr::value(now))
+            .filter(ApiKeyColumn::Id.eq(key.id))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{audit::RAuditList, error::AppError},
+    utils::{
+        pagination::{decode_cursor, encode_cursor, page_size},
+        token::new_id,
+    },
+};
+use chrono::Utc;
+use entity::audit_event::{
+    ActiveModel as AuditActive, Column as AuditColumn, Entity as AuditEvent, Model as AuditModel,
+};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Appends an audit event. Takes any connection so the event commits or rolls
+/// back together with the change it describes.
+pub async fn record_audit<C: ConnectionTrait>(
+    conn: &C,
+    actor_id: Option<Uuid>,
+    action: &str,
+    target_type: &str,
+    target_id: Option<Uuid>,
+    detail: Value,
+) -> Result<AuditModel, AppError> {
+    let event = AuditModel {
+        id: new_id(),
+        actor_id,
+        action: action.to_string(),
+        target_type: target_type.to_string(),
+        target_id,
+        detail,
+        created_at: Utc::now(),
+    };
+    AuditEvent::insert(AuditActive::from(event.clone()))
+        .exec(conn)
+        .await?;
+    Ok(event)
+}
+
+impl PostgresService {
+    pub async fn record_audit(
+        &self,
+        actor_id: Option<Uuid>,
+        action: &str,
+        target_type: &str,
+        target_id: Option<Uuid>,
+        detail: Value,
+    ) -> Result<AuditModel, AppError> {
+        record_audit(
+            &self.database_connection,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            detail,
+        )
+        .await
+    }
+
+    /// One page of the audit trail, newest first.
+    pub async fn list_audit_events(
+        &self,
+        filter: &RAuditList,
+    ) -> Result<(Vec<AuditModel>, Option<String>), AppError> {
+        let limit = page_size(filter.limit);
+        let mut query = AuditEvent::find();
+
+        if let Some(actor_id) = filter.actor_id {
+            query = query.filter(AuditColumn::ActorId.eq(actor_id));
+        }
+        if let Some(action) = &filter.action {
+            query = query.filter(AuditColumn::Action.eq(action.clone()));
+        }
+        if let Some(target_type) = &filter.target_type {
+            query = query.filter(AuditColumn::TargetType.eq(target_type.clone()));
+        }
+        if let Some(target_id) = filter.target_id {
+            query = query.filter(AuditColumn::TargetId.eq(target_id));
+        }
+        if let Some(cursor) = &filter.cursor {
+            let (ts, id) = decode_cursor(cursor)
+                .ok_or_else(|| AppError::BadRequest("Invalid cursor.".into()))?;
+            query = query.filter(
+                Condition::any().add(AuditColumn::CreatedAt.lt(ts)).add(
+                    Condition::all()
+                        .add(AuditColumn::CreatedAt.eq(ts))
+                        .add(AuditColumn::Id.lt(id)),
+                ),
+            );
+        }
+
+        let mut events = query
+            .order_by_desc(AuditColumn::CreatedAt)
+            .order_by_desc(AuditColumn::Id)
+            .limit(limit + 1)
+            .all(&self.database_connection)
+            .await?;
+
+        let next_cursor = if events.len() as u64 > limit {
+            events.truncate(limit as usize);
+            events.last().map(|e| encode_cursor(&e.created_at, &e.id))
+        } else {
+            None
+        };
+
+        Ok((events, next_cursor))
+    }
+}
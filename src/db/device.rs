@@ -0,0 +1,197 @@
+use crate::db::api_key::insert_api_key;
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, oauth::OAuthError, token::TokenType},
+    utils::{
+        scope::{join_scopes, split_scopes},
+        token::{construct_token, encrypt, extract_token_parts, new_id, new_token, verify},
+    },
+};
+use chrono::{Duration, Utc};
+use entity::api_key::Model as ApiKeyModel;
+use entity::device_authorization::{
+    ActiveModel as DeviceActive, Column as DeviceColumn, DeviceAuthorizationStatus,
+    Entity as DeviceAuthorization, Model as DeviceModel,
+};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use uuid::Uuid;
+
+pub const DEVICE_CODE_TTL_MINUTES: i64 = 10;
+pub const DEVICE_POLL_INTERVAL_SECS: i32 = 5;
+/// RFC 8628 section 3.5: each `slow_down` adds five seconds to the interval.
+const SLOW_DOWN_STEP_SECS: i32 = 5;
+const USER_CODE_LEN: usize = 8;
+/// Consonants only (RFC 8628 section 6.1): no vowels means no accidental words,
+/// and nothing that looks like a digit.
+const USER_CODE_ALPHABET: [char; 20] = [
+    'B', 'C', 'D', 'F', 'G', 'H', 'J', 'K', 'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'V', 'W', 'X',
+    'Z',
+];
+
+/// Uppercases a typed user code and drops the dash and any spaces.
+pub fn normalize_user_code(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// `BCDFGHJK` -> `BCDF-GHJK`, the form shown to users.
+pub fn format_user_code(code: &str) -> String {
+    let (a, b) = code.split_at(code.len() / 2);
+    format!("{a}-{b}")
+}
+
+impl PostgresService {
+    /// Starts a device grant. Returns the record and the secret device code.
+    pub async fn create_device_authorization(
+        &self,
+        client_id: &str,
+        scopes: &[String],
+        device_name: &str,
+    ) -> Result<(DeviceModel, String), AppError> {
+        let id = new_id();
+        let secret = new_token(TokenType::Device);
+        let device_code_hash = encrypt(&secret).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the device code.".into())
+        })?;
+        let now = Utc::now();
+
+        let model = DeviceModel {
+            id,
+            device_code_hash,
+            user_code: nanoid::nanoid!(USER_CODE_LEN, &USER_CODE_ALPHABET),
+            client_id: client_id.to_string(),
+            scopes: join_scopes(scopes),
+            device_name: device_name.to_string(),
+            status: DeviceAuthorizationStatus::Pending,
+            user_id: None,
+            interval_secs: DEVICE_POLL_INTERVAL_SECS,
+            last_polled_at: None,
+            expires_at: now + Duration::minutes(DEVICE_CODE_TTL_MINUTES),
+            created_at: now,
+        };
+        DeviceAuthorization::insert(DeviceActive::from(model.clone()))
+            .exec(&self.database_connection)
+            .await?;
+
+        Ok((model, construct_token(&id, &secret)))
+    }
+
+    /// Finds the pending grant behind a user code, for the approval screen.
+    pub async fn get_pending_device_authorization(
+        &self,
+        user_code: &str,
+    ) -> Result<DeviceModel, AppError> {
+        DeviceAuthorization::find()
+            .filter(DeviceColumn::UserCode.eq(normalize_user_code(user_code)))
+            .filter(DeviceColumn::Status.eq(DeviceAuthorizationStatus::Pending))
+            .filter(DeviceColumn::ExpiresAt.gt(Utc::now()))
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Unknown or expired code.".into()))
+    }
+
+    /// Approves or denies a pending grant on behalf of the signed-in user.
+    pub async fn decide_device_authorization(
+        &self,
+        user_code: &str,
+        user_id: &Uuid,
+        approve: bool,
+    ) -> Result<(), AppError> {
+        let status = if approve {
+            DeviceAuthorizationStatus::Approved
+        } else {
+            DeviceAuthorizationStatus::Denied
+        };
+        let res = DeviceAuthorization::update_many()
+            .col_expr(DeviceColumn::Status, Expr::value(status))
+            .col_expr(DeviceColumn::UserId, Expr::value(*user_id))
+            .filter(DeviceColumn::UserCode.eq(normalize_user_code(user_code)))
+            .filter(DeviceColumn::Status.eq(DeviceAuthorizationStatus::Pending))
+            .filter(DeviceColumn::ExpiresAt.gt(Utc::now()))
+            .exec(&self.database_connection)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::BadRequest("Unknown or expired code.".into()));
+        }
+        Ok(())
+    }
+
+    /// Handles one poll of the token endpoint (RFC 8628 section 3.4).
+    ///
+    /// Once approved, the first poll creates the API key and spends the grant,
+    /// in one transaction; later polls get `invalid_grant`.
+    pub async fn redeem_device_code(
+        &self,
+        device_code: &str,
+        client_id: &str,
+    ) -> Result<(ApiKeyModel, String), OAuthError> {
+        let (id, secret) = extract_token_parts(device_code).ok_or(OAuthError::InvalidGrant)?;
+        let grant = DeviceAuthorization::find_by_id(id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or(OAuthError::InvalidGrant)?;
+
+        if !verify(&secret, &grant.device_code_hash).unwrap_or(false)
+            || grant.client_id != client_id
+        {
+            return Err(OAuthError::InvalidGrant);
+        }
+
+        let now = Utc::now();
+        if grant.expires_at <= now {
+            return Err(OAuthError::ExpiredToken);
+        }
+
+        let too_soon = grant
+            .last_polled_at
+            .is_some_and(|t| (now - t).num_seconds() < i64::from(grant.interval_secs));
+        let mut poll = DeviceAuthorization::update_many()
+            .col_expr(DeviceColumn::LastPolledAt, Expr::value(now))
+            .filter(DeviceColumn::Id.eq(id));
+        if too_soon {
+            poll = poll.col_expr(
+                DeviceColumn::IntervalSecs,
+                Expr::col(DeviceColumn::IntervalSecs).add(SLOW_DOWN_STEP_SECS),
+            );
+        }
+        poll.exec(&self.database_connection).await?;
+        if too_soon {
+            return Err(OAuthError::SlowDown);
+        }
+
+        match grant.status {
+            DeviceAuthorizationStatus::Pending => return Err(OAuthError::AuthorizationPending),
+            DeviceAuthorizationStatus::Denied => return Err(OAuthError::AccessDenied),
+            DeviceAuthorizationStatus::Consumed => return Err(OAuthError::InvalidGrant),
+            DeviceAuthorizationStatus::Approved => {}
+        }
+        let user_id = grant.user_id.ok_or(OAuthError::InvalidGrant)?;
+
+        let txn = self.database_connection.begin().await?;
+        let claimed = DeviceAuthorization::update_many()
+            .col_expr(
+                DeviceColumn::Status,
+                Expr::value(DeviceAuthorizationStatus::Consumed),
+            )
+            .filter(DeviceColumn::Id.eq(id))
+            .filter(DeviceColumn::Status.eq(DeviceAuthorizationStatus::Approved))
+            .exec(&txn)
+            .await?;
+        if claimed.rows_affected == 0 {
+            return Err(OAuthError::InvalidGrant);
+        }
+
+        let key = insert_api_key(
+            &txn,
+            &user_id,
+            &grant.device_name,
+            &split_scopes(&grant.scopes),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(key)
+    }
+}
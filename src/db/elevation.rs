@@ -0,0 +1,482 @@
+use crate::db::{audit::record_audit, postgres_service::PostgresService};
+use crate::{
+    types::{elevation::RElevationList, error::AppError},
+    utils::{
+        elevation::{resolve_eligibility, Eligibility, PENDING_ELEVATION_TTL_HOURS},
+        role::{role_name, role_satisfies},
+        token::new_id,
+    },
+};
+use chrono::{Duration, Utc};
+use entity::elevation::{
+    ActiveModel as ElevationActive, Column as ElevationColumn, ElevationStatus,
+    Entity as Elevation, Model as ElevationModel,
+};
+use entity::elevation_eligibility::{
+    ActiveModel as EligibilityActive, Column as EligibilityColumn, Entity as ElevationEligibility,
+    Model as EligibilityModel,
+};
+use entity::role_binding::SubjectType;
+use entity::user::UserRole;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Requests and grants that still count: pending ones within their TTL and
+/// active ones that haven't run out.
+fn open_elevations(now: chrono::DateTime<Utc>) -> Condition {
+    Condition::any()
+        .add(
+            Condition::all()
+                .add(ElevationColumn::Status.eq(ElevationStatus::Pending))
+                .add(
+                    ElevationColumn::CreatedAt
+                        .gt(now - Duration::hours(PENDING_ELEVATION_TTL_HOURS)),
+                ),
+        )
+        .add(
+            Condition::all()
+                .add(ElevationColumn::Status.eq(ElevationStatus::Active))
+                .add(ElevationColumn::ExpiresAt.gt(now)),
+        )
+}
+
+impl PostgresService {
+    pub async fn create_elevation_eligibility(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+        role: UserRole,
+        max_minutes: i32,
+        requires_approval: bool,
+        actor_id: Option<Uuid>,
+    ) -> Result<EligibilityModel, AppError> {
+        if !self.subject_exists(subject_type, subject_id).await? {
+            return Err(AppError::Validation("The subject does not exist.".into()));
+        }
+        if ElevationEligibility::find()
+            .filter(EligibilityColumn::SubjectType.eq(subject_type))
+            .filter(EligibilityColumn::SubjectId.eq(*subject_id))
+            .filter(EligibilityColumn::Role.eq(role))
+            .count(&self.database_connection)
+            .await?
+            > 0
+        {
+            return Err(AppError::AlreadyExists);
+        }
+
+        let eligibility = EligibilityModel {
+            id: new_id(),
+            subject_type,
+            subject_id: *subject_id,
+            role,
+            max_minutes,
+            requires_approval,
+            created_by: actor_id,
+            created_at: Utc::now(),
+        };
+
+        let txn = self.database_connection.begin().await?;
+        ElevationEligibility::insert(EligibilityActive::from(eligibility.clone()))
+            .exec(&txn)
+            .await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "elevation.eligibility_granted",
+            "elevation_eligibility",
+            Some(eligibility.id),
+            json!({
+                "subject_type": eligibility.subject_type,
+                "subject_id": eligibility.subject_id,
+                "role": role_name(role),
+                "max_minutes": max_minutes,
+                "requires_approval": requires_approval,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(eligibility)
+    }
+
+    pub async fn list_elevation_eligibilities(&self) -> Result<Vec<EligibilityModel>, AppError> {
+        Ok(ElevationEligibility::find()
+            .order_by_asc(EligibilityColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Stops a subject from asking. Elevations already granted run their course.
+    pub async fn delete_elevation_eligibility(
+        &self,
+        eligibility_id: &Uuid,
+        actor_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        let eligibility = ElevationEligibility::find_by_id(*eligibility_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        ElevationEligibility::delete_by_id(eligibility.id)
+            .exec(&txn)
+            .await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "elevation.eligibility_revoked",
+            "elevation_eligibility",
+            Some(eligibility.id),
+            json!({
+                "subject_type": eligibility.subject_type,
+                "subject_id": eligibility.subject_id,
+                "role": role_name(eligibility.role),
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// What the user may ask for `role` under, directly or through a team.
+    pub async fn user_elevation_eligibility(
+        &self,
+        user_id: &Uuid,
+        role: UserRole,
+    ) -> Result<Option<Eligibility>, AppError> {
+        let team_ids = self.list_user_team_ids(user_id).await?;
+        let entries: Vec<Eligibility> = ElevationEligibility::find()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(EligibilityColumn::SubjectType.eq(SubjectType::User))
+                            .add(EligibilityColumn::SubjectId.eq(*user_id)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(EligibilityColumn::SubjectType.eq(SubjectType::Team))
+                            .add(EligibilityColumn::SubjectId.is_in(team_ids)),
+                    ),
+            )
+            .all(&self.database_connection)
+            .await?
+            .into_iter()
+            .map(|e| Eligibility {
+                role: e.role,
+                max_minutes: e.max_minutes,
+                requires_approval: e.requires_approval,
+            })
+            .collect();
+        Ok(resolve_eligibility(&entries, role))
+    }
+
+    /// Asks for `role` for `minutes`. Starts right away unless the user's
+    /// eligibility wants another admin to approve it first.
+    pub async fn request_elevation(
+        &self,
+        user_id: &Uuid,
+        role: UserRole,
+        reason: &str,
+        minutes: i32,
+    ) -> Result<ElevationModel, AppError> {
+        let user = self.get_user_by_id(user_id).await?;
+        if role_satisfies(user.role, role) {
+            return Err(AppError::Validation(
+                "Your account already has this role.".into(),
+            ));
+        }
+        let eligibility = self
+            .user_elevation_eligibility(user_id, role)
+            .await?
+            .ok_or(AppError::Forbidden)?;
+        if minutes < 1 || minutes > eligibility.max_minutes {
+            return Err(AppError::Validation(format!(
+                "Elevations to this role last between 1 and {} minutes.",
+                eligibility.max_minutes
+            )));
+        }
+
+        let now = Utc::now();
+        if Elevation::find()
+            .filter(ElevationColumn::UserId.eq(*user_id))
+            .filter(open_elevations(now))
+            .count(&self.database_connection)
+            .await?
+            > 0
+        {
+            return Err(AppError::Conflict(
+                "You already have an open elevation request or grant.".into(),
+            ));
+        }
+
+        let immediate = !eligibility.requires_approval;
+        let elevation = ElevationModel {
+            id: new_id(),
+            user_id: *user_id,
+            role,
+            reason: reason.to_string(),
+            duration_minutes: minutes,
+            status: if immediate {
+                ElevationStatus::Active
+            } else {
+                ElevationStatus::Pending
+            },
+            decided_by: None,
+            decided_at: immediate.then_some(now),
+            expires_at: immediate.then(|| now + Duration::minutes(minutes.into())),
+            ended_at: None,
+            created_at: now,
+        };
+
+        let txn = self.database_connection.begin().await?;
+        Elevation::insert(ElevationActive::from(elevation.clone()))
+            .exec(&txn)
+            .await?;
+        record_audit(
+            &txn,
+            Some(*user_id),
+            "elevation.requested",
+            "elevation",
+            Some(elevation.id),
+            json!({
+                "role": role_name(role),
+                "reason": reason,
+                "duration_minutes": minutes,
+                "requires_approval": eligibility.requires_approval,
+            }),
+        )
+        .await?;
+        if immediate {
+            record_audit(
+                &txn,
+                Some(*user_id),
+                "elevation.activated",
+                "elevation",
+                Some(elevation.id),
+                json!({ "role": role_name(role), "expires_at": elevation.expires_at }),
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(elevation)
+    }
+
+    pub async fn get_elevation(&self, elevation_id: &Uuid) -> Result<ElevationModel, AppError> {
+        Elevation::find_by_id(*elevation_id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Elevations, newest first. Lapsed ones are marked expired first so the
+    /// listing shows where they really stand.
+    pub async fn list_elevations(
+        &self,
+        filter: &RElevationList,
+    ) -> Result<Vec<ElevationModel>, AppError> {
+        self.expire_elevations().await?;
+        let mut query = Elevation::find();
+        if let Some(user_id) = filter.user_id {
+            query = query.filter(ElevationColumn::UserId.eq(user_id));
+        }
+        if let Some(status) = filter.status {
+            query = query.filter(ElevationColumn::Status.eq(status));
+        }
+        Ok(query
+            .order_by_desc(ElevationColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// The elevation the user acts under right now, if any.
+    pub async fn active_elevation(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Option<ElevationModel>, AppError> {
+        Ok(Elevation::find()
+            .filter(ElevationColumn::UserId.eq(*user_id))
+            .filter(ElevationColumn::Status.eq(ElevationStatus::Active))
+            .filter(ElevationColumn::ExpiresAt.gt(Utc::now()))
+            .order_by_desc(ElevationColumn::ExpiresAt)
+            .one(&self.database_connection)
+            .await?)
+    }
+
+    /// Approves or denies a pending request. `approver_id` is `None` for
+    /// the static admin key; an admin can't decide on their own request.
+    pub async fn decide_elevation(
+        &self,
+        elevation_id: &Uuid,
+        approver_id: Option<Uuid>,
+        approve: bool,
+    ) -> Result<ElevationModel, AppError> {
+        let elevation = self.get_elevation(elevation_id).await?;
+        if approver_id == Some(elevation.user_id) {
+            return Err(AppError::Forbidden);
+        }
+
+        let now = Utc::now();
+        let lapsed = elevation.created_at <= now - Duration::hours(PENDING_ELEVATION_TTL_HOURS);
+        if elevation.status != ElevationStatus::Pending || lapsed {
+            return Err(AppError::Conflict(
+                "The request is no longer pending.".into(),
+            ));
+        }
+
+        let (status, expires_at, action) = if approve {
+            (
+                ElevationStatus::Active,
+                Some(now + Duration::minutes(elevation.duration_minutes.into())),
+                "elevation.approved",
+            )
+        } else {
+            (ElevationStatus::Denied, None, "elevation.denied")
+        };
+
+        let txn = self.database_connection.begin().await?;
+        // Only one decision wins if two admins answer at once.
+        let decided = Elevation::update_many()
+            .col_expr(ElevationColumn::Status, Expr::value(status))
+            .col_expr(ElevationColumn::DecidedBy, Expr::value(approver_id))
+            .col_expr(ElevationColumn::DecidedAt, Expr::value(now))
+            .col_expr(ElevationColumn::ExpiresAt, Expr::value(expires_at))
+            .filter(ElevationColumn::Id.eq(elevation.id))
+            .filter(ElevationColumn::Status.eq(ElevationStatus::Pending))
+            .exec(&txn)
+            .await?;
+        if decided.rows_affected == 0 {
+            return Err(AppError::Conflict(
+                "The request is no longer pending.".into(),
+            ));
+        }
+        record_audit(
+            &txn,
+            approver_id,
+            action,
+            "elevation",
+            Some(elevation.id),
+            json!({
+                "user_id": elevation.user_id,
+                "role": role_name(elevation.role),
+                "expires_at": expires_at,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        self.get_elevation(elevation_id).await
+    }
+
+    /// Ends a pending or active elevation early. With `owner_id`, only that
+    /// user's elevations are found, so users can end their own.
+    pub async fn revoke_elevation(
+        &self,
+        elevation_id: &Uuid,
+        owner_id: Option<Uuid>,
+        actor_id: Option<Uuid>,
+    ) -> Result<ElevationModel, AppError> {
+        let elevation = self.get_elevation(elevation_id).await?;
+        if owner_id.is_some_and(|owner| owner != elevation.user_id) {
+            return Err(AppError::NotFound);
+        }
+
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+        let revoked = Elevation::update_many()
+            .col_expr(
+                ElevationColumn::Status,
+                Expr::value(ElevationStatus::Revoked),
+            )
+            .col_expr(ElevationColumn::EndedAt, Expr::value(now))
+            .filter(ElevationColumn::Id.eq(elevation.id))
+            .filter(open_elevations(now))
+            .exec(&txn)
+            .await?;
+        if revoked.rows_affected == 0 {
+            return Err(AppError::Conflict(
+                "The elevation has already ended.".into(),
+            ));
+        }
+        record_audit(
+            &txn,
+            actor_id,
+            "elevation.revoked",
+            "elevation",
+            Some(elevation.id),
+            json!({
+                "user_id": elevation.user_id,
+                "role": role_name(elevation.role),
+                "was": elevation.status,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        self.get_elevation(elevation_id).await
+    }
+
+    /// Marks elevations that ran out, and requests nobody decided on in
+    /// time, as expired, with an audit event each. Token checks already
+    /// ignore them; this only keeps the records and the trail honest.
+    pub async fn expire_elevations(&self) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let lapsed = Condition::any()
+            .add(
+                Condition::all()
+                    .add(ElevationColumn::Status.eq(ElevationStatus::Active))
+                    .add(ElevationColumn::ExpiresAt.lte(now)),
+            )
+            .add(
+                Condition::all()
+                    .add(ElevationColumn::Status.eq(ElevationStatus::Pending))
+                    .add(
+                        ElevationColumn::CreatedAt
+                            .lte(now - Duration::hours(PENDING_ELEVATION_TTL_HOURS)),
+                    ),
+            );
+
+        let txn = self.database_connection.begin().await?;
+        // Locked so overlapping sweeps don't audit the same expiry twice.
+        let expired = Elevation::find()
+            .filter(lapsed.clone())
+            .lock_exclusive()
+            .all(&txn)
+            .await?;
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        Elevation::update_many()
+            .col_expr(
+                ElevationColumn::Status,
+                Expr::value(ElevationStatus::Expired),
+            )
+            .col_expr(ElevationColumn::EndedAt, Expr::value(now))
+            .filter(ElevationColumn::Id.is_in(expired.iter().map(|e| e.id)))
+            .filter(lapsed)
+            .exec(&txn)
+            .await?;
+        for elevation in &expired {
+            record_audit(
+                &txn,
+                None,
+                "elevation.expired",
+                "elevation",
+                Some(elevation.id),
+                json!({
+                    "user_id": elevation.user_id,
+                    "role": role_name(elevation.role),
+                    "was": elevation.status,
+                }),
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(expired.len() as u64)
+    }
+}
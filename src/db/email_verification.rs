@@ -0,0 +1,147 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::error::AppError,
+    utils::token::{encrypt, new_id, new_numeric_code, verify},
+};
+use chrono::{Duration, Utc};
+use entity::email_verification::{
+    ActiveModel as VerificationActive, Column as VerificationColumn, Entity as EmailVerification,
+};
+use entity::user::{
+    ActiveModel as UserActive, Column as UserColumn, Entity as User, Model as UserModel, UserStatus,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+const VERIFICATION_CODE_LEN: usize = 8;
+const VERIFICATION_TTL_HOURS: i64 = 24;
+const MAX_VERIFICATION_ATTEMPTS: i32 = 5;
+
+impl PostgresService {
+    /// Issues a fresh verification code for a user. Any earlier codes stop working.
+    pub async fn create_email_verification(&self, user_id: &Uuid) -> Result<String, AppError> {
+        let code = new_numeric_code(VERIFICATION_CODE_LEN);
+        let code_hash = encrypt(&code).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the verification code.".into())
+        })?;
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+
+        EmailVerification::update_many()
+            .col_expr(VerificationColumn::ConsumedAt, Expr::value(now))
+            .filter(VerificationColumn::UserId.eq(*user_id))
+            .filter(VerificationColumn::ConsumedAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        EmailVerification::insert(VerificationActive {
+            id: Set(new_id()),
+            user_id: Set(*user_id),
+            code_hash: Set(code_hash),
+            attempts: Set(0),
+            expires_at: Set(now + Duration::hours(VERIFICATION_TTL_HOURS)),
+            consumed_at: Set(None),
+            created_at: Set(now),
+        })
+        .exec(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(code)
+    }
+
+    /// The account a code mailed to `email` would be for: one moving to that
+    /// address, or one that signed up with it and hasn't verified it yet.
+    pub async fn user_awaiting_verification(
+        &self,
+        email: &str,
+    ) -> Result<Option<UserModel>, AppError> {
+        if let Some(user) = User::find()
+            .filter(UserColumn::PendingEmail.eq(email))
+            .one(&self.database_connection)
+            .await?
+        {
+            return Ok(Some(user));
+        }
+        Ok(User::find()
+            .filter(UserColumn::Email.eq(email))
+            .filter(UserColumn::EmailVerifiedAt.is_null())
+            .filter(UserColumn::PendingEmail.is_null())
+            .one(&self.database_connection)
+            .await?)
+    }
+
+    /// Confirms a user's email with the code that was mailed to them. For an
+    /// email change, this is when the new address replaces the old one.
+    ///
+    /// Unknown emails, already verified emails, wrong codes and expired codes all
+    /// fail the same way so the endpoint can't be used to probe for accounts.
+    pub async fn verify_email(&self, email: &str, code: &str) -> Result<Uuid, AppError> {
+        let invalid = || AppError::BadRequest("Invalid or expired verification code.".into());
+
+        let user = self
+            .user_awaiting_verification(email)
+            .await?
+            .ok_or_else(invalid)?;
+
+        let now = Utc::now();
+        let pending = EmailVerification::find()
+            .filter(VerificationColumn::UserId.eq(user.id))
+            .filter(VerificationColumn::ConsumedAt.is_null())
+            .filter(VerificationColumn::ExpiresAt.gt(now))
+            .order_by_desc(VerificationColumn::CreatedAt)
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        // Every guess claims an attempt before the code is checked, in one
+        // conditional update, so concurrent guesses can't go over the limit.
+        let claimed = EmailVerification::update_many()
+            .col_expr(
+                VerificationColumn::Attempts,
+                Expr::col(VerificationColumn::Attempts).add(1),
+            )
+            .filter(VerificationColumn::Id.eq(pending.id))
+            .filter(VerificationColumn::Attempts.lt(MAX_VERIFICATION_ATTEMPTS))
+            .exec(&self.database_connection)
+            .await?;
+        if claimed.rows_affected == 0 {
+            return Err(invalid());
+        }
+
+        if !verify(code, &pending.code_hash).unwrap_or(false) {
+            return Err(invalid());
+        }
+
+        let txn = self.database_connection.begin().await?;
+
+        let mut verification: VerificationActive = pending.into();
+        verification.consumed_at = Set(Some(now));
+        verification.update(&txn).await?;
+
+        let user_id = user.id;
+        let was_pending = user.status == UserStatus::Pending;
+        let new_email = user.pending_email.clone();
+        let mut am: UserActive = user.into();
+        if let Some(new_email) = new_email {
+            // Someone may have signed up with the address since the request.
+            if self.user_exists_by_email(&new_email).await? {
+                return Err(AppError::Conflict("The email is already in use.".into()));
+            }
+            am.email = Set(new_email);
+            am.pending_email = Set(None);
+        }
+        am.email_verified_at = Set(Some(now));
+        if was_pending {
+            am.status = Set(UserStatus::Active);
+        }
+        am.updated_at = Set(now);
+        am.update(&txn).await?;
+
+        txn.commit().await?;
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{types::error::AppError, utils::token::new_id};
+use chrono::{Duration, Utc};
+use entity::federated_identity::{
+    ActiveModel as IdentityActive, Column as IdentityColumn, Entity as FederatedIdentity,
+    Model as IdentityModel,
+};
+use entity::federated_login::{
+    ActiveModel as LoginActive, Column as LoginColumn, Entity as FederatedLogin,
+    Model as LoginModel,
+};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+pub const FEDERATED_LOGIN_TTL_MINUTES: i64 = 10;
+
+impl PostgresService {
+    /// Parks a login while the browser is at the provider. The id is the `state`.
+    pub async fn create_federated_login(
+        &self,
+        provider: &str,
+        nonce: &str,
+        code_verifier: &str,
+    ) -> Result<Uuid, AppError> {
+        let id = new_id();
+        FederatedLogin::insert(LoginActive {
+            id: Set(id),
+            provider: Set(provider.to_string()),
+            nonce: Set(nonce.to_string()),
+            code_verifier: Set(code_verifier.to_string()),
+            expires_at: Set(Utc::now() + Duration::minutes(FEDERATED_LOGIN_TTL_MINUTES)),
+        })
+        .exec(&self.database_connection)
+        .await?;
+        Ok(id)
+    }
+
+    /// Removes and returns a pending login. Each `state` can come back once.
+    pub async fn take_federated_login(
+        &self,
+        id: &Uuid,
+        provider: &str,
+    ) -> Result<LoginModel, AppError> {
+        let invalid = || AppError::BadRequest("Unknown or expired login attempt.".into());
+
+        let login = FederatedLogin::find_by_id(*id)
+            .filter(LoginColumn::Provider.eq(provider))
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        let removed = FederatedLogin::delete_many()
+            .filter(LoginColumn::Id.eq(*id))
+            .exec(&self.database_connection)
+            .await?;
+
+        if removed.rows_affected == 0 || login.expires_at <= Utc::now() {
+            return Err(invalid());
+        }
+        Ok(login)
+    }
+
+    pub async fn find_federated_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<IdentityModel>, AppError> {
+        Ok(FederatedIdentity::find()
+            .filter(IdentityColumn::Provider.eq(provider))
+            .filter(IdentityColumn::Subject.eq(subject))
+            .one(&self.database_connection)
+            .await?)
+    }
+
+    pub async fn link_federated_identity(
+        &self,
+        user_id: &Uuid,
+        provider: &str,
+        subject: &str,
+        email: &str,
+    ) -> Result<IdentityModel, AppError> {
+        let now = Utc::now();
+        let model = IdentityModel {
+            id: new_id(),
+            user_id: *user_id,
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+            email: email.to_string(),
+            created_at: now,
+            last_login_at: Some(now),
+        };
+        FederatedIdentity::insert(IdentityActive::from(model.clone()))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(model)
+    }
+
+    pub async fn touch_federated_identity(&self, id: &Uuid) -> Result<(), AppError> {
+        FederatedIdentity::update_many()
+            .col_expr(IdentityColumn::LastLoginAt, Expr::value(Utc::now()))
+            .filter(IdentityColumn::Id.eq(*id))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::db::{audit::record_audit, postgres_service::PostgresService};
+use crate::{
+    types::error::AppError,
+    utils::{
+        kms::{new_data_key, Keyring},
+        token::new_id,
+    },
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use entity::workspace_key::{
+    ActiveModel as WorkspaceKeyActive, Column as WorkspaceKeyColumn, Entity as WorkspaceKey,
+    Model as WorkspaceKeyModel,
+};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde_json::json;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Outcome of rewrapping every workspace key under the current master key.
+pub struct KeyRotation {
+    pub rewrapped: u64,
+    /// Workspaces whose key is wrapped under a master key that is no longer
+    /// configured. Their data cannot be decrypted until it is restored.
+    pub failed: Vec<Uuid>,
+}
+
+fn unwrap_key(keyring: &Keyring, row: &WorkspaceKeyModel) -> Option<Vec<u8>> {
+    let wrapped = STANDARD.decode(&row.wrapped_key).ok()?;
+    keyring
+        .get(&row.master_key_id)?
+        .unwrap(&row.team_id, &wrapped)
+}
+
+impl PostgresService {
+    /// Returns the plaintext data key of a team's workspace, generating one
+    /// on first use.
+    pub async fn workspace_data_key(
+        &self,
+        keyring: &Keyring,
+        team_id: &Uuid,
+    ) -> Result<Vec<u8>, AppError> {
+        let row = match WorkspaceKey::find()
+            .filter(WorkspaceKeyColumn::TeamId.eq(*team_id))
+            .one(&self.database_connection)
+            .await?
+        {
+            Some(row) => row,
+            None => self.create_workspace_key(keyring, team_id).await?,
+        };
+
+        unwrap_key(keyring, &row).ok_or_else(|| {
+            warn!(
+                "Workspace key of team {} does not unwrap under master key {}",
+                row.team_id, row.master_key_id
+            );
+            AppError::Internal("The workspace key could not be unwrapped.".into())
+        })
+    }
+
+    /// Stores a fresh wrapped key for the team. If another request got there
+    /// first, theirs is kept and returned.
+    async fn create_workspace_key(
+        &self,
+        keyring: &Keyring,
+        team_id: &Uuid,
+    ) -> Result<WorkspaceKeyModel, AppError> {
+        let master = &keyring.current;
+        let row = WorkspaceKeyModel {
+            id: new_id(),
+            team_id: *team_id,
+            master_key_id: master.id().to_string(),
+            wrapped_key: STANDARD.encode(master.wrap(team_id, &new_data_key())),
+            created_at: Utc::now(),
+            rotated_at: None,
+        };
+
+        let txn = self.database_connection.begin().await?;
+        let inserted = WorkspaceKey::insert(WorkspaceKeyActive::from(row.clone()))
+            .on_conflict(
+                OnConflict::column(WorkspaceKeyColumn::TeamId)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        if inserted > 0 {
+            record_audit(
+                &txn,
+                None,
+                "kms.key_created",
+                "team",
+                Some(*team_id),
+                json!({ "master_key_id": row.master_key_id }),
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
+        WorkspaceKey::find()
+            .filter(WorkspaceKeyColumn::TeamId.eq(*team_id))
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Rewraps every workspace key that isn't under the current master key
+    /// yet. The data keys themselves don't change, so nothing stored with
+    /// them needs re-encrypting.
+    pub async fn rotate_workspace_keys(
+        &self,
+        keyring: &Keyring,
+        actor_id: Option<Uuid>,
+    ) -> Result<KeyRotation, AppError> {
+        let master = &keyring.current;
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+
+        let stale = WorkspaceKey::find()
+            .filter(WorkspaceKeyColumn::MasterKeyId.ne(master.id()))
+            .all(&txn)
+            .await?;
+        let mut rotation = KeyRotation {
+            rewrapped: 0,
+            failed: vec![],
+        };
+        for row in stale {
+            let Some(data_key) = unwrap_key(keyring, &row) else {
+                warn!(
+                    "Cannot rewrap the workspace key of team {}: master key {} is not configured",
+                    row.team_id, row.master_key_id
+                );
+                rotation.failed.push(row.team_id);
+                continue;
+            };
+            let wrapped = STANDARD.encode(master.wrap(&row.team_id, &data_key));
+            let mut am: WorkspaceKeyActive = row.into();
+            am.master_key_id = Set(master.id().to_string());
+            am.wrapped_key = Set(wrapped);
+            am.rotated_at = Set(Some(now));
+            am.update(&txn).await?;
+            rotation.rewrapped += 1;
+        }
+
+        record_audit(
+            &txn,
+            actor_id,
+            "kms.master_key_rotated",
+            "kms",
+            None,
+            json!({
+                "master_key_id": master.id(),
+                "rewrapped": rotation.rewrapped,
+                "failed": rotation.failed,
+            }),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Ok(rotation)
+    }
+}
@@ -1,2 +1,23 @@
+pub mod acl;
+pub mod api_key;
+pub mod audit;
+pub mod device;
+pub mod elevation;
+pub mod email_verification;
+pub mod federation;
+pub mod kms;
+pub mod oidc;
+pub mod passkey;
+pub mod password;
+pub mod plan;
 pub mod postgres_service;
+pub mod rbac;
+pub mod recovery;
+pub mod scim;
+pub mod session;
+pub mod signup_invite;
+pub mod team;
+pub mod team_deletion;
+pub mod team_invite;
+pub mod totp;
 pub mod user;
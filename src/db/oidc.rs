@@ -0,0 +1,358 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, oauth::OAuthError, token::TokenType},
+    utils::{
+        oidc::pkce_matches,
+        scope::join_scopes,
+        token::{
+            construct_token, encrypt, extract_token_parts, new_id, new_nanoid, new_token, verify,
+        },
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use entity::oauth_access_token::{
+    ActiveModel as AccessTokenActive, Column as AccessTokenColumn, Entity as AccessToken,
+    Model as AccessTokenModel,
+};
+use entity::oauth_authorization_code::{
+    ActiveModel as CodeActive, Column as CodeColumn, Entity as AuthorizationCode,
+    Model as CodeModel,
+};
+use entity::oauth_client::{
+    ActiveModel as ClientActive, Column as ClientColumn, Entity as OauthClient,
+    Model as ClientModel,
+};
+use entity::oauth_consent::{
+    ActiveModel as ConsentActive, Column as ConsentColumn, Entity as OauthConsent,
+    Model as ConsentModel,
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+/// Codes are exchanged by the client's backend right after the redirect.
+pub const AUTHORIZATION_CODE_TTL_SECS: i64 = 60;
+pub const OIDC_ACCESS_TOKEN_TTL_SECS: i64 = 3600;
+const CLIENT_ID_LEN: usize = 24;
+
+/// Everything an authorization code remembers about the request that made it.
+pub struct CodeGrant {
+    pub client_id: Uuid,
+    pub user_id: Uuid,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub nonce: Option<String>,
+    pub code_challenge: String,
+    pub auth_time: DateTime<Utc>,
+}
+
+/// Revokes every live access token a client holds for a user.
+async fn revoke_client_tokens<C: ConnectionTrait>(
+    conn: &C,
+    user_id: &Uuid,
+    client_id: &Uuid,
+) -> Result<(), AppError> {
+    AccessToken::update_many()
+        .col_expr(AccessTokenColumn::RevokedAt, Expr::value(Utc::now()))
+        .filter(AccessTokenColumn::UserId.eq(*user_id))
+        .filter(AccessTokenColumn::ClientId.eq(*client_id))
+        .filter(AccessTokenColumn::RevokedAt.is_null())
+        .exec(conn)
+        .await?;
+    Ok(())
+}
+
+impl PostgresService {
+    /// Registers a client. Confidential clients get a secret, returned only here.
+    pub async fn create_oauth_client(
+        &self,
+        name: &str,
+        redirect_uris: &[String],
+        confidential: bool,
+    ) -> Result<(ClientModel, Option<String>), AppError> {
+        let secret = confidential.then(|| new_token(TokenType::ClientSecret));
+        let secret_hash = match &secret {
+            Some(secret) => Some(encrypt(secret).map_err(|_| {
+                AppError::Internal("There was an issue while encrypting the client secret.".into())
+            })?),
+            None => None,
+        };
+
+        let model = ClientModel {
+            id: new_id(),
+            client_id: new_nanoid(CLIENT_ID_LEN),
+            name: name.to_string(),
+            secret_hash,
+            redirect_uris: serde_json::json!(redirect_uris),
+            created_at: Utc::now(),
+            revoked_at: None,
+        };
+        OauthClient::insert(ClientActive::from(model.clone()))
+            .exec(&self.database_connection)
+            .await?;
+
+        Ok((model, secret))
+    }
+
+    pub async fn list_oauth_clients(&self) -> Result<Vec<ClientModel>, AppError> {
+        Ok(OauthClient::find()
+            .order_by_desc(ClientColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Stops a client from starting new logins and kills its access tokens.
+    pub async fn revoke_oauth_client(&self, id: &Uuid) -> Result<(), AppError> {
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+        let res = OauthClient::update_many()
+            .col_expr(ClientColumn::RevokedAt, Expr::value(now))
+            .filter(ClientColumn::Id.eq(*id))
+            .filter(ClientColumn::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        AccessToken::update_many()
+            .col_expr(AccessTokenColumn::RevokedAt, Expr::value(now))
+            .filter(AccessTokenColumn::ClientId.eq(*id))
+            .filter(AccessTokenColumn::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Looks up a client by its public `client_id`, ignoring revoked ones.
+    pub async fn get_live_oauth_client(&self, client_id: &str) -> Result<ClientModel, AppError> {
+        OauthClient::find()
+            .filter(ClientColumn::ClientId.eq(client_id))
+            .filter(ClientColumn::RevokedAt.is_null())
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Client authentication at the token endpoint (RFC 6749 section 2.3).
+    ///
+    /// Confidential clients must present their secret; public clients must not
+    /// claim one.
+    pub async fn authenticate_oauth_client(
+        &self,
+        client_id: &str,
+        secret: Option<&str>,
+    ) -> Result<ClientModel, OAuthError> {
+        let client = self
+            .get_live_oauth_client(client_id)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound => OAuthError::InvalidClient,
+                e => e.into(),
+            })?;
+
+        match (&client.secret_hash, secret) {
+            (Some(hash), Some(secret)) if verify(secret, hash).unwrap_or(false) => Ok(client),
+            (None, None) => Ok(client),
+            _ => Err(OAuthError::InvalidClient),
+        }
+    }
+
+    pub async fn get_consent(
+        &self,
+        user_id: &Uuid,
+        client_id: &Uuid,
+    ) -> Result<Option<ConsentModel>, AppError> {
+        Ok(OauthConsent::find()
+            .filter(ConsentColumn::UserId.eq(*user_id))
+            .filter(ConsentColumn::ClientId.eq(*client_id))
+            .one(&self.database_connection)
+            .await?)
+    }
+
+    /// Records that a user shares `scopes` with a client, on top of anything
+    /// they already agreed to.
+    pub async fn grant_consent(
+        &self,
+        user_id: &Uuid,
+        client_id: &Uuid,
+        scopes: &[String],
+    ) -> Result<(), AppError> {
+        let now = Utc::now();
+        match self.get_consent(user_id, client_id).await? {
+            Some(existing) => {
+                let mut merged: Vec<String> = existing
+                    .scopes
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect();
+                for scope in scopes {
+                    if !merged.contains(scope) {
+                        merged.push(scope.clone());
+                    }
+                }
+                OauthConsent::update_many()
+                    .col_expr(ConsentColumn::Scopes, Expr::value(join_scopes(&merged)))
+                    .col_expr(ConsentColumn::UpdatedAt, Expr::value(now))
+                    .filter(ConsentColumn::Id.eq(existing.id))
+                    .exec(&self.database_connection)
+                    .await?;
+            }
+            None => {
+                OauthConsent::insert(ConsentActive {
+                    id: Set(new_id()),
+                    user_id: Set(*user_id),
+                    client_id: Set(*client_id),
+                    scopes: Set(join_scopes(scopes)),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                })
+                .exec(&self.database_connection)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn list_consents(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<(ConsentModel, Option<ClientModel>)>, AppError> {
+        Ok(OauthConsent::find()
+            .find_also_related(OauthClient)
+            .filter(ConsentColumn::UserId.eq(*user_id))
+            .order_by_desc(ConsentColumn::UpdatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Withdraws consent. The client's access tokens for this user stop working.
+    pub async fn revoke_consent(&self, user_id: &Uuid, id: &Uuid) -> Result<(), AppError> {
+        let consent = OauthConsent::find_by_id(*id)
+            .filter(ConsentColumn::UserId.eq(*user_id))
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        let txn = self.database_connection.begin().await?;
+        OauthConsent::delete_by_id(consent.id).exec(&txn).await?;
+        revoke_client_tokens(&txn, user_id, &consent.client_id).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    pub async fn create_authorization_code(&self, grant: CodeGrant) -> Result<String, AppError> {
+        let id = new_id();
+        let secret = new_token(TokenType::AuthorizationCode);
+        let secret_hash = encrypt(&secret).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the authorization code.".into())
+        })?;
+        let now = Utc::now();
+
+        AuthorizationCode::insert(CodeActive {
+            id: Set(id),
+            secret_hash: Set(secret_hash),
+            client_id: Set(grant.client_id),
+            user_id: Set(grant.user_id),
+            redirect_uri: Set(grant.redirect_uri),
+            scopes: Set(join_scopes(&grant.scopes)),
+            nonce: Set(grant.nonce),
+            code_challenge: Set(grant.code_challenge),
+            auth_time: Set(grant.auth_time),
+            expires_at: Set(now + Duration::seconds(AUTHORIZATION_CODE_TTL_SECS)),
+            consumed_at: Set(None),
+            created_at: Set(now),
+        })
+        .exec(&self.database_connection)
+        .await?;
+
+        Ok(construct_token(&id, &secret))
+    }
+
+    /// Exchanges an authorization code for an access token (RFC 6749 section
+    /// 4.1.3, RFC 7636 section 4.6).
+    ///
+    /// The code is spent and the token created in one transaction. A code that
+    /// is presented twice revokes whatever was issued from it (section 10.5).
+    pub async fn redeem_authorization_code(
+        &self,
+        code: &str,
+        client: &ClientModel,
+        redirect_uri: &str,
+        code_verifier: &str,
+    ) -> Result<(CodeModel, String), OAuthError> {
+        let (id, secret) = extract_token_parts(code).ok_or(OAuthError::InvalidGrant)?;
+        let grant = AuthorizationCode::find_by_id(id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or(OAuthError::InvalidGrant)?;
+
+        if !verify(&secret, &grant.secret_hash).unwrap_or(false) || grant.client_id != client.id {
+            return Err(OAuthError::InvalidGrant);
+        }
+
+        if grant.consumed_at.is_some() {
+            revoke_client_tokens(&self.database_connection, &grant.user_id, &client.id).await?;
+            return Err(OAuthError::InvalidGrant);
+        }
+
+        let now = Utc::now();
+        if grant.expires_at <= now
+            || grant.redirect_uri != redirect_uri
+            || !pkce_matches(code_verifier, &grant.code_challenge)
+        {
+            return Err(OAuthError::InvalidGrant);
+        }
+
+        let txn = self.database_connection.begin().await?;
+        let claimed = AuthorizationCode::update_many()
+            .col_expr(CodeColumn::ConsumedAt, Expr::value(now))
+            .filter(CodeColumn::Id.eq(id))
+            .filter(CodeColumn::ConsumedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if claimed.rows_affected == 0 {
+            return Err(OAuthError::InvalidGrant);
+        }
+
+        let token_id = new_id();
+        let token_secret = new_token(TokenType::OidcAccess);
+        let secret_hash = encrypt(&token_secret).map_err(|_| OAuthError::ServerError)?;
+        AccessToken::insert(AccessTokenActive::from(AccessTokenModel {
+            id: token_id,
+            secret_hash,
+            client_id: client.id,
+            user_id: grant.user_id,
+            scopes: grant.scopes.clone(),
+            expires_at: now + Duration::seconds(OIDC_ACCESS_TOKEN_TTL_SECS),
+            revoked_at: None,
+            created_at: now,
+        }))
+        .exec(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok((grant, construct_token(&token_id, &token_secret)))
+    }
+
+    /// Checks an access token presented at the userinfo endpoint.
+    pub async fn get_live_oidc_access_token(
+        &self,
+        token: &str,
+    ) -> Result<AccessTokenModel, AppError> {
+        let (id, secret) = extract_token_parts(token).ok_or(AppError::Unauthorized)?;
+        let record = AccessToken::find_by_id(id)
+            .filter(AccessTokenColumn::RevokedAt.is_null())
+            .filter(AccessTokenColumn::ExpiresAt.gt(Utc::now()))
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if !verify(&secret, &record.secret_hash).unwrap_or(false) {
+            return Err(AppError::Unauthorized);
+        }
+        Ok(record)
+    }
+}
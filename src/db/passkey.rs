@@ -0,0 +1,133 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{types::error::AppError, utils::token::new_id};
+use chrono::{Duration, Utc};
+use entity::passkey_credential::{
+    ActiveModel as PasskeyActive, Column as PasskeyColumn, Entity as PasskeyCredential,
+    Model as PasskeyModel,
+};
+use entity::webauthn_ceremony::{
+    ActiveModel as CeremonyActive, Column as CeremonyColumn, Entity as WebauthnCeremony,
+    Model as CeremonyModel,
+};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+const CEREMONY_TTL_MINUTES: i64 = 5;
+
+pub const CEREMONY_REGISTRATION: &str = "registration";
+pub const CEREMONY_AUTHENTICATION: &str = "authentication";
+
+impl PostgresService {
+    /// Parks WebAuthn ceremony state between the start and finish calls.
+    pub async fn create_webauthn_ceremony(
+        &self,
+        user_id: &Uuid,
+        kind: &str,
+        state: serde_json::Value,
+    ) -> Result<Uuid, AppError> {
+        let id = new_id();
+        WebauthnCeremony::insert(CeremonyActive {
+            id: Set(id),
+            user_id: Set(*user_id),
+            kind: Set(kind.to_string()),
+            state: Set(state),
+            expires_at: Set(Utc::now() + Duration::minutes(CEREMONY_TTL_MINUTES)),
+        })
+        .exec(&self.database_connection)
+        .await?;
+        Ok(id)
+    }
+
+    /// Removes and returns a pending ceremony. Each challenge can be answered once.
+    pub async fn take_webauthn_ceremony(
+        &self,
+        id: &Uuid,
+        kind: &str,
+    ) -> Result<CeremonyModel, AppError> {
+        let invalid = || AppError::BadRequest("Unknown or expired passkey challenge.".into());
+
+        let ceremony = WebauthnCeremony::find_by_id(*id)
+            .filter(CeremonyColumn::Kind.eq(kind))
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        let removed = WebauthnCeremony::delete_many()
+            .filter(CeremonyColumn::Id.eq(*id))
+            .exec(&self.database_connection)
+            .await?;
+
+        if removed.rows_affected == 0 || ceremony.expires_at <= Utc::now() {
+            return Err(invalid());
+        }
+
+        Ok(ceremony)
+    }
+
+    pub async fn add_passkey(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        credential_id: &str,
+        passkey: serde_json::Value,
+    ) -> Result<PasskeyModel, AppError> {
+        let exists = PasskeyCredential::find()
+            .filter(PasskeyColumn::CredentialId.eq(credential_id))
+            .one(&self.database_connection)
+            .await?;
+        if exists.is_some() {
+            return Err(AppError::AlreadyExists);
+        }
+
+        let model = PasskeyModel {
+            id: new_id(),
+            user_id: *user_id,
+            credential_id: credential_id.to_string(),
+            name: name.to_string(),
+            passkey,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        PasskeyCredential::insert(PasskeyActive::from(model.clone()))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(model)
+    }
+
+    pub async fn list_passkeys(&self, user_id: &Uuid) -> Result<Vec<PasskeyModel>, AppError> {
+        Ok(PasskeyCredential::find()
+            .filter(PasskeyColumn::UserId.eq(*user_id))
+            .order_by_asc(PasskeyColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Stores the credential after a successful login (the signature counter
+    /// may have moved) and stamps when it was last used.
+    pub async fn record_passkey_use(
+        &self,
+        id: &Uuid,
+        passkey: Option<serde_json::Value>,
+    ) -> Result<(), AppError> {
+        let mut update = PasskeyCredential::update_many()
+            .col_expr(PasskeyColumn::LastUsedAt, Expr::value(Utc::now()))
+            .filter(PasskeyColumn::Id.eq(*id));
+        if let Some(passkey) = passkey {
+            update = update.col_expr(PasskeyColumn::Passkey, Expr::value(passkey));
+        }
+        update.exec(&self.database_connection).await?;
+        Ok(())
+    }
+
+    pub async fn delete_passkey(&self, user_id: &Uuid, id: &Uuid) -> Result<(), AppError> {
+        let res = PasskeyCredential::delete_many()
+            .filter(PasskeyColumn::Id.eq(*id))
+            .filter(PasskeyColumn::UserId.eq(*user_id))
+            .exec(&self.database_connection)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+}
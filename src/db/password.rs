@@ -0,0 +1,103 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, token::TokenType},
+    utils::{
+        password::{check_password_policy, hash_password},
+        token::{construct_token, encrypt, extract_token_parts, new_id, new_token, verify},
+    },
+};
+use chrono::{Duration, Utc};
+use entity::password_token::{
+    ActiveModel as PasswordTokenActive, Column as PasswordTokenColumn, Entity as PasswordToken,
+};
+use entity::user::{Column as UserColumn, Entity as User};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use uuid::Uuid;
+
+const PASSWORD_TOKEN_TTL_MINUTES: i64 = 30;
+
+impl PostgresService {
+    /// Issues a single-use token for setting a password. Earlier unused ones stop working.
+    pub async fn create_password_token(&self, user_id: &Uuid) -> Result<String, AppError> {
+        let id = new_id();
+        let secret = new_token(TokenType::Password);
+        let secret_hash = encrypt(&secret).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the password token.".into())
+        })?;
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+
+        PasswordToken::update_many()
+            .col_expr(PasswordTokenColumn::ConsumedAt, Expr::value(now))
+            .filter(PasswordTokenColumn::UserId.eq(*user_id))
+            .filter(PasswordTokenColumn::ConsumedAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        PasswordToken::insert(PasswordTokenActive {
+            id: Set(id),
+            user_id: Set(*user_id),
+            secret_hash: Set(secret_hash),
+            expires_at: Set(now + Duration::minutes(PASSWORD_TOKEN_TTL_MINUTES)),
+            consumed_at: Set(None),
+            created_at: Set(now),
+        })
+        .exec(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(construct_token(&id, &secret))
+    }
+
+    /// Sets (or replaces) a user's password with a mailed token.
+    ///
+    /// The policy is checked before the token is spent, so a rejected password
+    /// can be retried with the same link.
+    pub async fn set_password_with_token(
+        &self,
+        token: &str,
+        password: &str,
+    ) -> Result<Uuid, AppError> {
+        let invalid = || AppError::BadRequest("Invalid or expired password link.".into());
+
+        let (id, secret) = extract_token_parts(token).ok_or_else(invalid)?;
+        let record = PasswordToken::find_by_id(id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if !verify(&secret, &record.secret_hash).unwrap_or(false) {
+            return Err(invalid());
+        }
+
+        let user = self.get_user_by_id(&record.user_id).await?;
+        check_password_policy(password, &user.email)?;
+        let password_hash = hash_password(password)?;
+
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+
+        let consumed = PasswordToken::update_many()
+            .col_expr(PasswordTokenColumn::ConsumedAt, Expr::value(now))
+            .filter(PasswordTokenColumn::Id.eq(id))
+            .filter(PasswordTokenColumn::ConsumedAt.is_null())
+            .filter(PasswordTokenColumn::ExpiresAt.gt(now))
+            .exec(&txn)
+            .await?;
+
+        if consumed.rows_affected == 0 {
+            return Err(invalid());
+        }
+
+        User::update_many()
+            .col_expr(UserColumn::PasswordHash, Expr::value(password_hash))
+            .col_expr(UserColumn::PasswordChangedAt, Expr::value(now))
+            .col_expr(UserColumn::UpdatedAt, Expr::value(now))
+            .filter(UserColumn::Id.eq(user.id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(user.id)
+    }
+}
@@ -0,0 +1,382 @@
+use crate::db::{audit::record_audit, postgres_service::PostgresService};
+use crate::{
+    types::{error::AppError, plan::RPlan},
+    utils::{
+        quota::{Exceeded, Limits, Usage},
+        token::new_id,
+    },
+};
+use chrono::Utc;
+use entity::plan::{
+    ActiveModel as PlanActive, Column as PlanColumn, Entity as Plan, Model as PlanModel,
+};
+use entity::plan_assignment::{
+    ActiveModel as AssignmentActive, Column as AssignmentColumn, Entity as PlanAssignment,
+    Model as AssignmentModel,
+};
+use entity::quota_usage::{
+    ActiveModel as UsageActive, Column as UsageColumn, Entity as QuotaUsage, Model as UsageModel,
+};
+use entity::role_binding::SubjectType;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+pub enum Reservation {
+    Granted(Usage),
+    /// Nothing was reserved; `Usage` is what is in use now.
+    Denied(Exceeded, Usage),
+}
+
+pub fn plan_limits(plan: &PlanModel) -> Limits {
+    Limits {
+        max_bytes: plan.max_bytes,
+        max_files: plan.max_files,
+        max_share_links: plan.max_share_links,
+    }
+}
+
+fn usage_of(row: &UsageModel) -> Usage {
+    Usage {
+        bytes: row.bytes,
+        files: row.files,
+        share_links: row.share_links,
+    }
+}
+
+/// What the audit trail records about a plan.
+fn settings(plan: &PlanModel) -> serde_json::Value {
+    json!({
+        "name": plan.name,
+        "max_bytes": plan.max_bytes,
+        "max_files": plan.max_files,
+        "max_share_links": plan.max_share_links,
+        "features": plan.features,
+    })
+}
+
+fn sorted_features(features: &[String]) -> Vec<String> {
+    let mut features = features.to_vec();
+    features.sort();
+    features.dedup();
+    features
+}
+
+async fn subject_plan<C: ConnectionTrait>(
+    conn: &C,
+    subject_type: SubjectType,
+    subject_id: &Uuid,
+) -> Result<Option<PlanModel>, AppError> {
+    Ok(PlanAssignment::find()
+        .filter(AssignmentColumn::SubjectType.eq(subject_type))
+        .filter(AssignmentColumn::SubjectId.eq(*subject_id))
+        .find_also_related(Plan)
+        .one(conn)
+        .await?
+        .and_then(|(_, plan)| plan))
+}
+
+/// Loads the subject's usage row for update, creating an empty one first.
+/// Holding the row lock until commit is what keeps concurrent reservations
+/// from both squeezing under the limit.
+async fn lock_usage<C: ConnectionTrait>(
+    conn: &C,
+    subject_type: SubjectType,
+    subject_id: &Uuid,
+) -> Result<UsageModel, AppError> {
+    let empty = UsageModel {
+        id: new_id(),
+        subject_type,
+        subject_id: *subject_id,
+        bytes: 0,
+        files: 0,
+        share_links: 0,
+        updated_at: Utc::now(),
+    };
+    QuotaUsage::insert(UsageActive::from(empty))
+        .on_conflict(
+            OnConflict::columns([UsageColumn::SubjectType, UsageColumn::SubjectId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await?;
+    QuotaUsage::find()
+        .filter(UsageColumn::SubjectType.eq(subject_type))
+        .filter(UsageColumn::SubjectId.eq(*subject_id))
+        .lock_exclusive()
+        .one(conn)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn store_usage<C: ConnectionTrait>(
+    conn: &C,
+    row: UsageModel,
+    usage: Usage,
+) -> Result<(), AppError> {
+    let mut am: UsageActive = row.into();
+    am.bytes = Set(usage.bytes);
+    am.files = Set(usage.files);
+    am.share_links = Set(usage.share_links);
+    am.updated_at = Set(Utc::now());
+    am.update(conn).await?;
+    Ok(())
+}
+
+impl PostgresService {
+    async fn plan_name_taken(&self, name: &str, except: Option<Uuid>) -> Result<bool, AppError> {
+        let mut query = Plan::find().filter(PlanColumn::Name.eq(name));
+        if let Some(id) = except {
+            query = query.filter(PlanColumn::Id.ne(id));
+        }
+        Ok(query.count(&self.database_connection).await? > 0)
+    }
+
+    pub async fn create_plan(
+        &self,
+        plan: &RPlan,
+        actor_id: Option<Uuid>,
+    ) -> Result<PlanModel, AppError> {
+        if self.plan_name_taken(&plan.name, None).await? {
+            return Err(AppError::AlreadyExists);
+        }
+        let now = Utc::now();
+        let model = PlanModel {
+            id: new_id(),
+            name: plan.name.clone(),
+            max_bytes: plan.max_bytes,
+            max_files: plan.max_files,
+            max_share_links: plan.max_share_links,
+            features: json!(sorted_features(&plan.features)),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let txn = self.database_connection.begin().await?;
+        Plan::insert(PlanActive::from(model.clone()))
+            .exec(&txn)
+            .await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "plan.created",
+            "plan",
+            Some(model.id),
+            settings(&model),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(model)
+    }
+
+    pub async fn list_plans(&self) -> Result<Vec<PlanModel>, AppError> {
+        Ok(Plan::find()
+            .order_by_asc(PlanColumn::Name)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Replaces a plan's settings. Subjects on the plan get the new limits
+    /// on their next reservation; usage over a lowered limit is kept.
+    pub async fn update_plan(
+        &self,
+        plan_id: &Uuid,
+        plan: &RPlan,
+        actor_id: Option<Uuid>,
+    ) -> Result<PlanModel, AppError> {
+        if self.plan_name_taken(&plan.name, Some(*plan_id)).await? {
+            return Err(AppError::AlreadyExists);
+        }
+
+        let txn = self.database_connection.begin().await?;
+        let existing = Plan::find_by_id(*plan_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let previous = settings(&existing);
+        let mut am: PlanActive = existing.into();
+        am.name = Set(plan.name.clone());
+        am.max_bytes = Set(plan.max_bytes);
+        am.max_files = Set(plan.max_files);
+        am.max_share_links = Set(plan.max_share_links);
+        am.features = Set(json!(sorted_features(&plan.features)));
+        am.updated_at = Set(Utc::now());
+        let updated = am.update(&txn).await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "plan.updated",
+            "plan",
+            Some(updated.id),
+            json!({ "from": previous, "to": settings(&updated) }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// Deletes a plan nobody is on.
+    pub async fn delete_plan(
+        &self,
+        plan_id: &Uuid,
+        actor_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        let plan = Plan::find_by_id(*plan_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if PlanAssignment::find()
+            .filter(AssignmentColumn::PlanId.eq(plan.id))
+            .count(&txn)
+            .await?
+            > 0
+        {
+            return Err(AppError::Conflict(
+                "The plan is still assigned to users or teams.".into(),
+            ));
+        }
+        Plan::delete_by_id(plan.id).exec(&txn).await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "plan.deleted",
+            "plan",
+            Some(plan.id),
+            json!({ "name": plan.name }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Puts a user or team on a plan, or takes it off with `None`. Usage is
+    /// kept either way.
+    pub async fn assign_plan(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+        plan_id: Option<Uuid>,
+        actor_id: Option<Uuid>,
+    ) -> Result<Option<PlanModel>, AppError> {
+        if !self.subject_exists(subject_type, subject_id).await? {
+            return Err(AppError::NotFound);
+        }
+        let plan = match plan_id {
+            Some(id) => Some(
+                Plan::find_by_id(id)
+                    .one(&self.database_connection)
+                    .await?
+                    .ok_or_else(|| AppError::Validation("The plan does not exist.".into()))?,
+            ),
+            None => None,
+        };
+
+        let txn = self.database_connection.begin().await?;
+        let previous = subject_plan(&txn, subject_type, subject_id).await?;
+        PlanAssignment::delete_many()
+            .filter(AssignmentColumn::SubjectType.eq(subject_type))
+            .filter(AssignmentColumn::SubjectId.eq(*subject_id))
+            .exec(&txn)
+            .await?;
+        if let Some(plan) = &plan {
+            PlanAssignment::insert(AssignmentActive::from(AssignmentModel {
+                id: new_id(),
+                subject_type,
+                subject_id: *subject_id,
+                plan_id: plan.id,
+                assigned_by: actor_id,
+                created_at: Utc::now(),
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        record_audit(
+            &txn,
+            actor_id,
+            if plan.is_some() {
+                "plan.assigned"
+            } else {
+                "plan.unassigned"
+            },
+            match subject_type {
+                SubjectType::User => "user",
+                SubjectType::Team => "team",
+            },
+            Some(*subject_id),
+            json!({
+                "from": previous.map(|p| p.name),
+                "to": plan.as_ref().map(|p| p.name.clone()),
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(plan)
+    }
+
+    /// The subject's plan, if any, and what it uses right now.
+    pub async fn get_entitlements(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+    ) -> Result<(Option<PlanModel>, Usage), AppError> {
+        let plan = subject_plan(&self.database_connection, subject_type, subject_id).await?;
+        let usage = QuotaUsage::find()
+            .filter(UsageColumn::SubjectType.eq(subject_type))
+            .filter(UsageColumn::SubjectId.eq(*subject_id))
+            .one(&self.database_connection)
+            .await?
+            .map(|row| usage_of(&row))
+            .unwrap_or_default();
+        Ok((plan, usage))
+    }
+
+    /// Adds `delta` to the subject's usage if it stays within the plan, or
+    /// leaves it untouched. Safe to call concurrently for the same subject.
+    pub async fn reserve_usage(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+        delta: &Usage,
+    ) -> Result<Reservation, AppError> {
+        let txn = self.database_connection.begin().await?;
+        let row = lock_usage(&txn, subject_type, subject_id).await?;
+        let limits = subject_plan(&txn, subject_type, subject_id)
+            .await?
+            .map(|plan| plan_limits(&plan))
+            .unwrap_or_default();
+
+        let usage = usage_of(&row);
+        if let Some(exceeded) = limits.exceeded(&usage, delta) {
+            return Ok(Reservation::Denied(exceeded, usage));
+        }
+        let usage = usage.plus(delta);
+        store_usage(&txn, row, usage).await?;
+        txn.commit().await?;
+
+        Ok(Reservation::Granted(usage))
+    }
+
+    /// Gives back usage, e.g. after a delete or a failed upload. Counters
+    /// never go below zero.
+    pub async fn release_usage(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+        delta: &Usage,
+    ) -> Result<Usage, AppError> {
+        let txn = self.database_connection.begin().await?;
+        let row = lock_usage(&txn, subject_type, subject_id).await?;
+        let usage = usage_of(&row).minus(delta);
+        store_usage(&txn, row, usage).await?;
+        txn.commit().await?;
+        Ok(usage)
+    }
+}
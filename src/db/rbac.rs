@@ -0,0 +1,385 @@
+use crate::db::{audit::record_audit, postgres_service::PostgresService};
+use crate::{
+    types::{error::AppError, rbac::RRoleBindingList},
+    utils::{
+        policy::{Access, BindingCondition, Decision, Grant, Policy, RequestContext},
+        token::new_id,
+    },
+};
+use chrono::Utc;
+use entity::permission::{
+    ActiveModel as PermissionActive, Column as PermissionColumn, Entity as Permission,
+    Model as PermissionModel,
+};
+use entity::rbac_role::{
+    ActiveModel as RoleActive, Column as RoleColumn, Entity as RbacRole, Model as RoleModel,
+};
+use entity::rbac_role_permission::{
+    ActiveModel as RolePermissionActive, Column as RolePermissionColumn,
+    Entity as RbacRolePermission, Model as RolePermissionModel,
+};
+use entity::role_binding::{
+    ActiveModel as BindingActive, Column as BindingColumn, Entity as RoleBinding,
+    Model as BindingModel, SubjectType,
+};
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+impl PostgresService {
+    pub async fn create_permission(
+        &self,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<PermissionModel, AppError> {
+        if self.permission_exists(name).await? {
+            return Err(AppError::AlreadyExists);
+        }
+        let permission = PermissionModel {
+            name: name.to_string(),
+            description,
+            created_at: Utc::now(),
+        };
+        Permission::insert(PermissionActive::from(permission.clone()))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(permission)
+    }
+
+    pub async fn permission_exists(&self, name: &str) -> Result<bool, AppError> {
+        Ok(Permission::find_by_id(name.to_string())
+            .count(&self.database_connection)
+            .await?
+            > 0)
+    }
+
+    pub async fn list_permissions(&self) -> Result<Vec<PermissionModel>, AppError> {
+        Ok(Permission::find()
+            .order_by_asc(PermissionColumn::Name)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Creates a role granting `permissions`, which must all exist already.
+    pub async fn create_rbac_role(
+        &self,
+        name: &str,
+        description: Option<String>,
+        permissions: &[String],
+        actor_id: Option<Uuid>,
+    ) -> Result<(RoleModel, Vec<String>), AppError> {
+        let mut permissions = permissions.to_vec();
+        permissions.sort();
+        permissions.dedup();
+
+        if RbacRole::find()
+            .filter(RoleColumn::Name.eq(name))
+            .count(&self.database_connection)
+            .await?
+            > 0
+        {
+            return Err(AppError::AlreadyExists);
+        }
+        let known = Permission::find()
+            .filter(PermissionColumn::Name.is_in(permissions.clone()))
+            .count(&self.database_connection)
+            .await?;
+        if known as usize != permissions.len() {
+            return Err(AppError::Validation(
+                "Every permission must be created before it is granted.".into(),
+            ));
+        }
+
+        let role = RoleModel {
+            id: new_id(),
+            name: name.to_string(),
+            description,
+            created_at: Utc::now(),
+        };
+
+        let txn = self.database_connection.begin().await?;
+        RbacRole::insert(RoleActive::from(role.clone()))
+            .exec(&txn)
+            .await?;
+        if !permissions.is_empty() {
+            RbacRolePermission::insert_many(permissions.iter().map(|permission| {
+                RolePermissionActive::from(RolePermissionModel {
+                    id: new_id(),
+                    role_id: role.id,
+                    permission: permission.clone(),
+                })
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        record_audit(
+            &txn,
+            actor_id,
+            "rbac.role_created",
+            "rbac_role",
+            Some(role.id),
+            json!({ "name": role.name, "permissions": permissions }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok((role, permissions))
+    }
+
+    /// Every role with its permissions, by name.
+    pub async fn list_rbac_roles(&self) -> Result<Vec<(RoleModel, Vec<String>)>, AppError> {
+        let rows = RbacRole::find()
+            .order_by_asc(RoleColumn::Name)
+            .find_with_related(RbacRolePermission)
+            .all(&self.database_connection)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(role, grants)| {
+                let mut permissions: Vec<String> =
+                    grants.into_iter().map(|g| g.permission).collect();
+                permissions.sort();
+                (role, permissions)
+            })
+            .collect())
+    }
+
+    /// Deletes a role along with every binding to it.
+    pub async fn delete_rbac_role(
+        &self,
+        role_id: &Uuid,
+        actor_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        let role = RbacRole::find_by_id(*role_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        RbacRole::delete_by_id(role.id).exec(&txn).await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "rbac.role_deleted",
+            "rbac_role",
+            Some(role.id),
+            json!({ "name": role.name }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Whether the user or team a binding or ACL entry names exists.
+    pub async fn subject_exists(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+    ) -> Result<bool, AppError> {
+        let count = match subject_type {
+            SubjectType::User => {
+                entity::user::Entity::find_by_id(*subject_id)
+                    .count(&self.database_connection)
+                    .await?
+            }
+            SubjectType::Team => {
+                entity::team::Entity::find_by_id(*subject_id)
+                    .count(&self.database_connection)
+                    .await?
+            }
+        };
+        Ok(count > 0)
+    }
+
+    pub async fn create_role_binding(
+        &self,
+        subject_type: SubjectType,
+        subject_id: &Uuid,
+        role_id: &Uuid,
+        scope: &str,
+        conditions: &[BindingCondition],
+        actor_id: Option<Uuid>,
+    ) -> Result<BindingModel, AppError> {
+        if !self.subject_exists(subject_type, subject_id).await? {
+            return Err(AppError::Validation("The subject does not exist.".into()));
+        }
+        if RbacRole::find_by_id(*role_id)
+            .one(&self.database_connection)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::Validation("The role does not exist.".into()));
+        }
+        if RoleBinding::find()
+            .filter(BindingColumn::SubjectType.eq(subject_type))
+            .filter(BindingColumn::SubjectId.eq(*subject_id))
+            .filter(BindingColumn::RoleId.eq(*role_id))
+            .filter(BindingColumn::Scope.eq(scope))
+            .count(&self.database_connection)
+            .await?
+            > 0
+        {
+            return Err(AppError::AlreadyExists);
+        }
+
+        let binding = BindingModel {
+            id: new_id(),
+            subject_type,
+            subject_id: *subject_id,
+            role_id: *role_id,
+            scope: scope.to_string(),
+            conditions: json!(conditions),
+            created_by: actor_id,
+            created_at: Utc::now(),
+        };
+
+        let txn = self.database_connection.begin().await?;
+        RoleBinding::insert(BindingActive::from(binding.clone()))
+            .exec(&txn)
+            .await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "rbac.binding_created",
+            "role_binding",
+            Some(binding.id),
+            json!({
+                "subject_type": binding.subject_type,
+                "subject_id": binding.subject_id,
+                "role_id": binding.role_id,
+                "scope": binding.scope,
+                "conditions": binding.conditions,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(binding)
+    }
+
+    pub async fn list_role_bindings(
+        &self,
+        filter: &RRoleBindingList,
+    ) -> Result<Vec<BindingModel>, AppError> {
+        let mut query = RoleBinding::find();
+        if let Some(subject_type) = filter.subject_type {
+            query = query.filter(BindingColumn::SubjectType.eq(subject_type));
+        }
+        if let Some(subject_id) = filter.subject_id {
+            query = query.filter(BindingColumn::SubjectId.eq(subject_id));
+        }
+        if let Some(role_id) = filter.role_id {
+            query = query.filter(BindingColumn::RoleId.eq(role_id));
+        }
+        Ok(query
+            .order_by_asc(BindingColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    pub async fn delete_role_binding(
+        &self,
+        binding_id: &Uuid,
+        actor_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        let binding = RoleBinding::find_by_id(*binding_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        RoleBinding::delete_by_id(binding.id).exec(&txn).await?;
+        record_audit(
+            &txn,
+            actor_id,
+            "rbac.binding_deleted",
+            "role_binding",
+            Some(binding.id),
+            json!({
+                "subject_type": binding.subject_type,
+                "subject_id": binding.subject_id,
+                "role_id": binding.role_id,
+                "scope": binding.scope,
+            }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Bindings that apply to a user, directly or through a team, resolved to
+    /// the permissions their roles grant.
+    pub async fn load_user_grants(&self, user_id: &Uuid) -> Result<Vec<Grant>, AppError> {
+        let team_ids = self.list_user_team_ids(user_id).await?;
+
+        let bindings = RoleBinding::find()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(BindingColumn::SubjectType.eq(SubjectType::User))
+                            .add(BindingColumn::SubjectId.eq(*user_id)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(BindingColumn::SubjectType.eq(SubjectType::Team))
+                            .add(BindingColumn::SubjectId.is_in(team_ids)),
+                    ),
+            )
+            .all(&self.database_connection)
+            .await?;
+        if bindings.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut by_role: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for row in RbacRolePermission::find()
+            .filter(RolePermissionColumn::RoleId.is_in(bindings.iter().map(|b| b.role_id)))
+            .all(&self.database_connection)
+            .await?
+        {
+            by_role.entry(row.role_id).or_default().push(row.permission);
+        }
+
+        Ok(bindings
+            .into_iter()
+            .filter_map(|binding| {
+                // A binding whose conditions cannot be read must not grant anything.
+                let conditions = match serde_json::from_value(binding.conditions) {
+                    Ok(conditions) => conditions,
+                    Err(e) => {
+                        warn!(
+                            "Ignoring role binding {} with bad conditions: {}",
+                            binding.id, e
+                        );
+                        return None;
+                    }
+                };
+                Some(Grant {
+                    binding_id: binding.id,
+                    permissions: by_role.get(&binding.role_id).cloned().unwrap_or_default(),
+                    scope: binding.scope,
+                    conditions,
+                })
+            })
+            .collect())
+    }
+
+    /// Loads what applies to the user on `resource` and asks `policy`.
+    pub async fn authorize_user(
+        &self,
+        policy: &dyn Policy,
+        user_id: &Uuid,
+        action: &str,
+        resource: &str,
+        ctx: &RequestContext,
+    ) -> Result<Decision, AppError> {
+        let access = Access {
+            grants: self.load_user_grants(user_id).await?,
+            acl: self.load_user_acl(user_id, resource).await?,
+        };
+        Ok(policy.authorize(&access, action, resource, ctx))
+    }
+}
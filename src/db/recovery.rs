@@ -0,0 +1,91 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, token::TokenType},
+    utils::token::{construct_token, encrypt, extract_token_parts, new_id, new_token, verify},
+};
+use chrono::{Duration, Utc};
+use entity::recovery_token::{
+    ActiveModel as RecoveryActive, Column as RecoveryColumn, Entity as RecoveryToken,
+    Model as RecoveryModel,
+};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use uuid::Uuid;
+
+const RECOVERY_TTL_MINUTES: i64 = 15;
+
+fn invalid_link() -> AppError {
+    AppError::BadRequest("Invalid or expired recovery link.".into())
+}
+
+impl PostgresService {
+    /// Issues a single-use recovery token for a user. Earlier unused ones stop working.
+    pub async fn create_recovery_token(&self, user_id: &Uuid) -> Result<String, AppError> {
+        let id = new_id();
+        let secret = new_token(TokenType::Recovery);
+        let secret_hash = encrypt(&secret).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the recovery token.".into())
+        })?;
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+
+        RecoveryToken::update_many()
+            .col_expr(RecoveryColumn::ConsumedAt, Expr::value(now))
+            .filter(RecoveryColumn::UserId.eq(*user_id))
+            .filter(RecoveryColumn::ConsumedAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        RecoveryToken::insert(RecoveryActive {
+            id: Set(id),
+            user_id: Set(*user_id),
+            secret_hash: Set(secret_hash),
+            expires_at: Set(now + Duration::minutes(RECOVERY_TTL_MINUTES)),
+            consumed_at: Set(None),
+            created_at: Set(now),
+        })
+        .exec(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(construct_token(&id, &secret))
+    }
+
+    /// Looks up a recovery token that is still usable, without spending it.
+    pub async fn find_recovery_token(&self, token: &str) -> Result<RecoveryModel, AppError> {
+        let (id, secret) = extract_token_parts(token).ok_or_else(invalid_link)?;
+        let record = RecoveryToken::find_by_id(id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid_link)?;
+
+        if record.consumed_at.is_some()
+            || record.expires_at <= Utc::now()
+            || !verify(&secret, &record.secret_hash).unwrap_or(false)
+        {
+            return Err(invalid_link());
+        }
+
+        Ok(record)
+    }
+
+    /// Spends a recovery token found with [`Self::find_recovery_token`].
+    ///
+    /// The token is marked used with a conditional update, so two concurrent
+    /// redemptions can't both succeed.
+    pub async fn consume_recovery_token(&self, id: &Uuid) -> Result<(), AppError> {
+        let now = Utc::now();
+        let consumed = RecoveryToken::update_many()
+            .col_expr(RecoveryColumn::ConsumedAt, Expr::value(now))
+            .filter(RecoveryColumn::Id.eq(*id))
+            .filter(RecoveryColumn::ConsumedAt.is_null())
+            .filter(RecoveryColumn::ExpiresAt.gt(now))
+            .exec(&self.database_connection)
+            .await?;
+
+        if consumed.rows_affected == 0 {
+            return Err(invalid_link());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::error::AppError,
+    utils::{
+        pagination::like_prefix,
+        scim::{ScimAttr, ScimFilter, ScimOp, ScimValue},
+    },
+};
+use entity::user::{Column, Entity as User, Model as UserModel, UserStatus};
+use sea_orm::{
+    sea_query::{Expr, Func, SimpleExpr},
+    ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use uuid::Uuid;
+
+/// Turns one comparison into SQL. Strings compare case-insensitively, as
+/// SCIM asks for `userName`.
+fn filter_expr(filter: &ScimFilter) -> SimpleExpr {
+    match (&filter.attr, &filter.value) {
+        (ScimAttr::Active, ScimValue::Bool(active)) => {
+            let suspended = *active != (filter.op == ScimOp::Eq);
+            if suspended {
+                Column::Status.eq(UserStatus::Suspended)
+            } else {
+                Column::Status.ne(UserStatus::Suspended)
+            }
+        }
+        (ScimAttr::Id, ScimValue::Str(id)) => match (Uuid::parse_str(id), filter.op) {
+            (Ok(id), ScimOp::Ne) => Column::Id.ne(id),
+            (Ok(id), _) => Column::Id.eq(id),
+            (Err(_), op) => Expr::value(op == ScimOp::Ne),
+        },
+        (attr, ScimValue::Str(value)) => {
+            let column = match attr {
+                ScimAttr::DisplayName => Column::Name,
+                _ => Column::Email,
+            };
+            let lhs = Expr::expr(Func::lower(Expr::col(column)));
+            let value = value.to_lowercase();
+            let prefix = like_prefix(&value);
+            let literal = &prefix[..prefix.len() - 1];
+            match filter.op {
+                ScimOp::Eq => lhs.eq(value),
+                ScimOp::Ne => lhs.ne(value),
+                ScimOp::Co => lhs.like(format!("%{prefix}")),
+                ScimOp::Sw => lhs.like(prefix.clone()),
+                ScimOp::Ew => lhs.like(format!("%{literal}")),
+            }
+        }
+        // Rejected by the parser.
+        (_, ScimValue::Bool(_)) => Expr::value(false),
+    }
+}
+
+impl PostgresService {
+    /// Accounts matching every filter, oldest first so SCIM's index-based
+    /// paging stays stable. `start_index` is 1-based. Also returns the total
+    /// number of matches.
+    pub async fn list_scim_users(
+        &self,
+        filters: &[ScimFilter],
+        start_index: u64,
+        count: u64,
+    ) -> Result<(Vec<UserModel>, u64), AppError> {
+        let mut query = User::find();
+        for filter in filters {
+            query = query.filter(filter_expr(filter));
+        }
+
+        let total = query.clone().count(&self.database_connection).await?;
+        let users = query
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .offset(start_index.saturating_sub(1))
+            .limit(count)
+            .all(&self.database_connection)
+            .await?;
+
+        Ok((users, total))
+    }
+}
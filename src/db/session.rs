@@ -0,0 +1,138 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, token::TokenType},
+    utils::{
+        csrf::{csrf_hash, new_csrf_token},
+        token::{construct_token, encrypt, new_id, new_token},
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use entity::session::{
+    ActiveModel as SessionActive, Column as SessionColumn, Entity as Session, Model as SessionModel,
+};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+pub const SESSION_TTL_HOURS: i64 = 12;
+/// Writes to `last_seen_at` are skipped when the stored value is fresher than this.
+pub const LAST_SEEN_GRANULARITY_SECS: i64 = 60;
+
+/// A session that was just opened. Both secrets are only available here.
+#[derive(Debug, Clone)]
+pub struct NewSession {
+    pub token: String,
+    pub csrf_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Where a session came from, recorded for the user's session list.
+#[derive(Debug, Clone, Default)]
+pub struct SessionOrigin {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl PostgresService {
+    /// Opens a session for a user and returns its bearer and CSRF tokens.
+    ///
+    /// Session tokens share the `<id>.<secret>` layout of API tokens, but the
+    /// id is the session's and the secret starts with `session_`.
+    pub async fn create_session(
+        &self,
+        user_id: &Uuid,
+        auth_method: &str,
+        origin: SessionOrigin,
+    ) -> Result<NewSession, AppError> {
+        let id = new_id();
+        let secret = new_token(TokenType::Session);
+        let secret_hash = encrypt(&secret).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the session token.".into())
+        })?;
+        let csrf_token = new_csrf_token();
+        let now = Utc::now();
+        let expires_at = now + Duration::hours(SESSION_TTL_HOURS);
+
+        Session::insert(SessionActive {
+            id: Set(id),
+            user_id: Set(*user_id),
+            secret_hash: Set(secret_hash),
+            csrf_hash: Set(csrf_hash(&csrf_token)),
+            auth_method: Set(auth_method.to_string()),
+            ip_address: Set(origin.ip_address),
+            user_agent: Set(origin.user_agent),
+            created_at: Set(now),
+            last_seen_at: Set(now),
+            expires_at: Set(expires_at),
+            revoked_at: Set(None),
+        })
+        .exec(&self.database_connection)
+        .await?;
+
+        Ok(NewSession {
+            token: construct_token(&id, &secret),
+            csrf_token,
+            expires_at,
+        })
+    }
+
+    /// Looks up a session that is neither expired nor revoked.
+    pub async fn get_live_session(&self, session_id: &Uuid) -> Result<SessionModel, AppError> {
+        Session::find_by_id(*session_id)
+            .filter(SessionColumn::RevokedAt.is_null())
+            .filter(SessionColumn::ExpiresAt.gt(Utc::now()))
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Ends one of a user's sessions. Its token stops working at once.
+    pub async fn revoke_session(&self, user_id: &Uuid, session_id: &Uuid) -> Result<(), AppError> {
+        let res = Session::update_many()
+            .col_expr(SessionColumn::RevokedAt, Expr::value(Utc::now()))
+            .filter(SessionColumn::Id.eq(*session_id))
+            .filter(SessionColumn::UserId.eq(*user_id))
+            .filter(SessionColumn::RevokedAt.is_null())
+            .exec(&self.database_connection)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Live sessions of a user, most recently used first.
+    pub async fn list_sessions(&self, user_id: &Uuid) -> Result<Vec<SessionModel>, AppError> {
+        Ok(Session::find()
+            .filter(SessionColumn::UserId.eq(*user_id))
+            .filter(SessionColumn::RevokedAt.is_null())
+            .filter(SessionColumn::ExpiresAt.gt(Utc::now()))
+            .order_by_desc(SessionColumn::LastSeenAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    /// Signs a user out everywhere. Returns how many sessions were ended.
+    pub async fn revoke_all_sessions(&self, user_id: &Uuid) -> Result<u64, AppError> {
+        let res = Session::update_many()
+            .col_expr(SessionColumn::RevokedAt, Expr::value(Utc::now()))
+            .filter(SessionColumn::UserId.eq(*user_id))
+            .filter(SessionColumn::RevokedAt.is_null())
+            .exec(&self.database_connection)
+            .await?;
+        Ok(res.rows_affected)
+    }
+
+    /// Records activity on a session, at most once per [`LAST_SEEN_GRANULARITY_SECS`].
+    pub async fn touch_session(&self, session: &SessionModel) -> Result<(), AppError> {
+        let now = Utc::now();
+        if (now - session.last_seen_at).num_seconds() < LAST_SEEN_GRANULARITY_SECS {
+            return Ok(());
+        }
+        Session::update_many()
+            .col_expr(SessionColumn::LastSeenAt, Expr::value(now))
+            .filter(SessionColumn::Id.eq(session.id))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, token::TokenType},
+    utils::token::{construct_token, encrypt, extract_token_parts, new_id, new_token, verify},
+};
+use chrono::{DateTime, Utc};
+use entity::signup_invite::{
+    ActiveModel as InviteActive, Column as InviteColumn, Entity as SignupInvite,
+    Model as InviteModel,
+};
+use entity::user::UserRole;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use uuid::Uuid;
+
+impl PostgresService {
+    /// Creates an invite and returns its id along with the code to hand out.
+    pub async fn create_signup_invite(
+        &self,
+        max_uses: i32,
+        expires_at: Option<DateTime<Utc>>,
+        default_role: UserRole,
+    ) -> Result<(Uuid, String), AppError> {
+        let id = new_id();
+        let secret = new_token(TokenType::Invite);
+        let code_hash = encrypt(&secret).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the invite code.".into())
+        })?;
+
+        SignupInvite::insert(InviteActive {
+            id: Set(id),
+            code_hash: Set(code_hash),
+            default_role: Set(default_role),
+            max_uses: Set(max_uses),
+            uses: Set(0),
+            expires_at: Set(expires_at),
+            revoked_at: Set(None),
+            created_at: Set(Utc::now()),
+        })
+        .exec(&self.database_connection)
+        .await?;
+
+        Ok((id, construct_token(&id, &secret)))
+    }
+
+    pub async fn list_signup_invites(&self) -> Result<Vec<InviteModel>, AppError> {
+        Ok(SignupInvite::find()
+            .order_by_desc(InviteColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    pub async fn revoke_signup_invite(&self, id: &Uuid) -> Result<(), AppError> {
+        let invite = SignupInvite::find_by_id(*id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Invite does not exist".into()))?;
+
+        if invite.revoked_at.is_some() {
+            return Ok(());
+        }
+
+        let mut am: InviteActive = invite.into();
+        am.revoked_at = Set(Some(Utc::now()));
+        Ok(am.update(&self.database_connection).await.map(|_| ())?)
+    }
+
+    /// Takes one use from an invite code.
+    ///
+    /// The use is claimed with a single conditional update so concurrent signups
+    /// can't push an invite past `max_uses`. Unknown, revoked, expired and used-up
+    /// codes all fail the same way.
+    pub async fn claim_signup_invite(&self, code: &str) -> Result<InviteModel, AppError> {
+        let invalid = || AppError::BadRequest("Invalid or expired invite code.".into());
+
+        let (id, secret) = extract_token_parts(code).ok_or_else(invalid)?;
+        let invite = SignupInvite::find_by_id(id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if !verify(&secret, &invite.code_hash).unwrap_or(false) {
+            return Err(invalid());
+        }
+
+        let claimed = SignupInvite::update_many()
+            .col_expr(InviteColumn::Uses, Expr::col(InviteColumn::Uses).add(1))
+            .filter(InviteColumn::Id.eq(id))
+            .filter(Expr::col(InviteColumn::Uses).lt(Expr::col(InviteColumn::MaxUses)))
+            .filter(InviteColumn::RevokedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(InviteColumn::ExpiresAt.is_null())
+                    .add(InviteColumn::ExpiresAt.gt(Utc::now())),
+            )
+            .exec(&self.database_connection)
+            .await?;
+
+        if claimed.rows_affected == 0 {
+            return Err(invalid());
+        }
+
+        Ok(invite)
+    }
+
+    /// Gives back a use taken by [`Self::claim_signup_invite`] when signup fails afterwards.
+    pub async fn release_signup_invite(&self, id: &Uuid) -> Result<(), AppError> {
+        SignupInvite::update_many()
+            .col_expr(InviteColumn::Uses, Expr::col(InviteColumn::Uses).sub(1))
+            .filter(InviteColumn::Id.eq(*id))
+            .filter(InviteColumn::Uses.gt(0))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(())
+    }
+}
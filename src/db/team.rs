@@ -0,0 +1,210 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{types::error::AppError, utils::token::new_id};
+use chrono::Utc;
+use entity::team::{
+    ActiveModel as TeamActive, Column as TeamColumn, Entity as Team, Model as TeamModel,
+};
+use entity::team_membership::{
+    ActiveModel as MembershipActive, Column as MembershipColumn, Entity as TeamMembership,
+    Model as MembershipModel, TeamRole,
+};
+use entity::user::Model as UserModel;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+async fn insert_membership<C: ConnectionTrait>(
+    conn: &C,
+    team_id: &Uuid,
+    user_id: &Uuid,
+    role: TeamRole,
+) -> Result<MembershipModel, AppError> {
+    let model = MembershipModel {
+        id: new_id(),
+        team_id: *team_id,
+        user_id: *user_id,
+        role,
+        created_at: Utc::now(),
+    };
+    TeamMembership::insert(MembershipActive::from(model.clone()))
+        .exec(conn)
+        .await?;
+    Ok(model)
+}
+
+/// Locks the teams' rows until the transaction ends. Every change that could
+/// take away a team's last owner takes this lock first, so two of them can't
+/// each see another owner and both go ahead.
+async fn lock_teams<C: ConnectionTrait>(conn: &C, team_ids: &[Uuid]) -> Result<(), AppError> {
+    Team::find()
+        .filter(TeamColumn::Id.is_in(team_ids.to_vec()))
+        .order_by_asc(TeamColumn::Id)
+        .lock_exclusive()
+        .all(conn)
+        .await?;
+    Ok(())
+}
+
+async fn owner_count<C: ConnectionTrait>(conn: &C, team_id: &Uuid) -> Result<u64, AppError> {
+    Ok(TeamMembership::find()
+        .filter(MembershipColumn::TeamId.eq(*team_id))
+        .filter(MembershipColumn::Role.eq(TeamRole::Owner))
+        .count(conn)
+        .await?)
+}
+
+/// Teams `user_id` is the only owner of, with those teams locked as in
+/// [`lock_teams`]. Must run inside the transaction that removes the user.
+pub(crate) async fn teams_owned_alone<C: ConnectionTrait>(
+    conn: &C,
+    user_id: &Uuid,
+) -> Result<Vec<Uuid>, AppError> {
+    let owned: Vec<Uuid> = TeamMembership::find()
+        .select_only()
+        .column(MembershipColumn::TeamId)
+        .filter(MembershipColumn::UserId.eq(*user_id))
+        .filter(MembershipColumn::Role.eq(TeamRole::Owner))
+        .into_tuple()
+        .all(conn)
+        .await?;
+    lock_teams(conn, &owned).await?;
+
+    let mut alone = vec![];
+    for team_id in owned {
+        if owner_count(conn, &team_id).await? <= 1 {
+            alone.push(team_id);
+        }
+    }
+    Ok(alone)
+}
+
+impl PostgresService {
+    /// Creates a team owned by `owner_id`.
+    pub async fn create_team(&self, owner_id: &Uuid, name: &str) -> Result<TeamModel, AppError> {
+        let now = Utc::now();
+        let team = TeamModel {
+            id: new_id(),
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let txn = self.database_connection.begin().await?;
+        Team::insert(TeamActive::from(team.clone()))
+            .exec(&txn)
+            .await?;
+        insert_membership(&txn, &team.id, owner_id, TeamRole::Owner).await?;
+        txn.commit().await?;
+
+        Ok(team)
+    }
+
+    pub async fn get_team(&self, team_id: &Uuid) -> Result<TeamModel, AppError> {
+        Team::find_by_id(*team_id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn rename_team(&self, team_id: &Uuid, name: &str) -> Result<TeamModel, AppError> {
+        let mut am: TeamActive = self.get_team(team_id).await?.into();
+        am.name = Set(name.to_string());
+        am.updated_at = Set(Utc::now());
+        Ok(am.update(&self.database_connection).await?)
+    }
+
+    pub async fn get_team_membership(
+        &self,
+        team_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<Option<MembershipModel>, AppError> {
+        Ok(TeamMembership::find()
+            .filter(MembershipColumn::TeamId.eq(*team_id))
+            .filter(MembershipColumn::UserId.eq(*user_id))
+            .one(&self.database_connection)
+            .await?)
+    }
+
+    /// Every team the user belongs to, oldest membership first.
+    pub async fn list_user_teams(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<(MembershipModel, TeamModel)>, AppError> {
+        let rows = TeamMembership::find()
+            .filter(MembershipColumn::UserId.eq(*user_id))
+            .order_by_asc(MembershipColumn::CreatedAt)
+            .find_also_related(Team)
+            .all(&self.database_connection)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(membership, team)| Some((membership, team?)))
+            .collect())
+    }
+
+    /// Ids of the teams the user belongs to.
+    pub async fn list_user_team_ids(&self, user_id: &Uuid) -> Result<Vec<Uuid>, AppError> {
+        Ok(TeamMembership::find()
+            .select_only()
+            .column(MembershipColumn::TeamId)
+            .filter(MembershipColumn::UserId.eq(*user_id))
+            .into_tuple()
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    pub async fn list_team_members(
+        &self,
+        team_id: &Uuid,
+    ) -> Result<Vec<(MembershipModel, UserModel)>, AppError> {
+        let rows = TeamMembership::find()
+            .filter(MembershipColumn::TeamId.eq(*team_id))
+            .order_by_asc(MembershipColumn::CreatedAt)
+            .find_also_related(entity::user::Entity)
+            .all(&self.database_connection)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(membership, user)| Some((membership, user?)))
+            .collect())
+    }
+
+    pub async fn add_team_member(
+        &self,
+        team_id: &Uuid,
+        user_id: &Uuid,
+        role: TeamRole,
+    ) -> Result<MembershipModel, AppError> {
+        if self.get_team_membership(team_id, user_id).await?.is_some() {
+            return Err(AppError::AlreadyExists);
+        }
+        insert_membership(&self.database_connection, team_id, user_id, role).await
+    }
+
+    /// Removes a member. The last owner cannot leave; the team would be
+    /// unmanageable.
+    pub async fn remove_team_member(&self, team_id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        lock_teams(&txn, &[*team_id]).await?;
+        let membership = TeamMembership::find()
+            .filter(MembershipColumn::TeamId.eq(*team_id))
+            .filter(MembershipColumn::UserId.eq(*user_id))
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        if membership.role == TeamRole::Owner && owner_count(&txn, team_id).await? <= 1 {
+            return Err(AppError::Conflict(
+                "A team needs at least one owner.".into(),
+            ));
+        }
+
+        TeamMembership::delete_by_id(membership.id)
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::db::{audit::record_audit, postgres_service::PostgresService};
+use crate::{
+    types::error::AppError,
+    utils::token::{encrypt, new_id, new_numeric_code, verify},
+};
+use chrono::{Duration, Utc};
+use entity::acl_entry::{Column as AclColumn, Entity as AclEntry};
+use entity::plan_assignment::{Column as AssignmentColumn, Entity as PlanAssignment};
+use entity::quota_usage::{Column as UsageColumn, Entity as QuotaUsage};
+use entity::role_binding::{Column as BindingColumn, Entity as RoleBinding, SubjectType};
+use entity::team::{Entity as Team, Model as TeamModel};
+use entity::team_deletion::{
+    ActiveModel as DeletionActive, Column as DeletionColumn, Entity as TeamDeletion,
+    Model as DeletionModel,
+};
+use entity::team_invite::{Column as InviteColumn, Entity as TeamInvite};
+use entity::team_membership::{Column as MembershipColumn, Entity as TeamMembership};
+use entity::workspace_key::{Column as WorkspaceKeyColumn, Entity as WorkspaceKey};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+const TEAM_DELETION_CODE_LEN: usize = 8;
+pub const TEAM_DELETION_TTL_MINUTES: i64 = 15;
+const MAX_TEAM_DELETION_ATTEMPTS: i32 = 5;
+
+/// Cuts off everything that grants access to a team apart from its
+/// memberships, which go with the team row. Destroying the workspace key
+/// leaves anything still encrypted under it unreadable. Returns what was
+/// revoked, for the audit trail.
+async fn revoke_team_credentials<C: ConnectionTrait>(
+    conn: &C,
+    team_id: &Uuid,
+) -> Result<serde_json::Value, AppError> {
+    let invites = TeamInvite::update_many()
+        .col_expr(InviteColumn::CancelledAt, Expr::value(Utc::now()))
+        .filter(InviteColumn::TeamId.eq(*team_id))
+        .filter(InviteColumn::AcceptedAt.is_null())
+        .filter(InviteColumn::CancelledAt.is_null())
+        .exec(conn)
+        .await?;
+    let bindings = RoleBinding::delete_many()
+        .filter(BindingColumn::SubjectType.eq(SubjectType::Team))
+        .filter(BindingColumn::SubjectId.eq(*team_id))
+        .exec(conn)
+        .await?;
+    let acl = AclEntry::delete_many()
+        .filter(AclColumn::PrincipalType.eq(SubjectType::Team))
+        .filter(AclColumn::PrincipalId.eq(*team_id))
+        .exec(conn)
+        .await?;
+    let plans = PlanAssignment::delete_many()
+        .filter(AssignmentColumn::SubjectType.eq(SubjectType::Team))
+        .filter(AssignmentColumn::SubjectId.eq(*team_id))
+        .exec(conn)
+        .await?;
+    QuotaUsage::delete_many()
+        .filter(UsageColumn::SubjectType.eq(SubjectType::Team))
+        .filter(UsageColumn::SubjectId.eq(*team_id))
+        .exec(conn)
+        .await?;
+    let keys = WorkspaceKey::delete_many()
+        .filter(WorkspaceKeyColumn::TeamId.eq(*team_id))
+        .exec(conn)
+        .await?;
+
+    Ok(json!({
+        "invites_cancelled": invites.rows_affected,
+        "role_bindings_deleted": bindings.rows_affected,
+        "acl_entries_deleted": acl.rows_affected,
+        "plan_assignments_deleted": plans.rows_affected,
+        "workspace_keys_destroyed": keys.rows_affected,
+    }))
+}
+
+impl PostgresService {
+    /// Starts deleting a team: issues a confirmation code for `requested_by`
+    /// and returns it with the request. Earlier open requests stop working.
+    pub async fn create_team_deletion(
+        &self,
+        team_id: &Uuid,
+        requested_by: &Uuid,
+    ) -> Result<(DeletionModel, String), AppError> {
+        let code = new_numeric_code(TEAM_DELETION_CODE_LEN);
+        let code_hash = encrypt(&code).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the confirmation code.".into())
+        })?;
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+
+        TeamDeletion::update_many()
+            .col_expr(DeletionColumn::ConsumedAt, Expr::value(now))
+            .filter(DeletionColumn::TeamId.eq(*team_id))
+            .filter(DeletionColumn::ConsumedAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        let request = DeletionModel {
+            id: new_id(),
+            team_id: *team_id,
+            requested_by: *requested_by,
+            code_hash,
+            attempts: 0,
+            expires_at: now + Duration::minutes(TEAM_DELETION_TTL_MINUTES),
+            consumed_at: None,
+            created_at: now,
+        };
+        TeamDeletion::insert(DeletionActive::from(request.clone()))
+            .exec(&txn)
+            .await?;
+        record_audit(
+            &txn,
+            Some(*requested_by),
+            "team.deletion_requested",
+            "team",
+            Some(*team_id),
+            json!({ "request_id": request.id, "expires_at": request.expires_at }),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Ok((request, code))
+    }
+
+    /// Finishes a deletion with the mailed code. Only the owner who asked
+    /// can confirm, and a request stops accepting guesses after
+    /// [`MAX_TEAM_DELETION_ATTEMPTS`]. Returns the deleted team.
+    pub async fn confirm_team_deletion(
+        &self,
+        team_id: &Uuid,
+        user_id: &Uuid,
+        code: &str,
+    ) -> Result<TeamModel, AppError> {
+        let invalid = || AppError::BadRequest("Invalid or expired confirmation code.".into());
+
+        let now = Utc::now();
+        let pending = TeamDeletion::find()
+            .filter(DeletionColumn::TeamId.eq(*team_id))
+            .filter(DeletionColumn::RequestedBy.eq(*user_id))
+            .filter(DeletionColumn::ConsumedAt.is_null())
+            .filter(DeletionColumn::ExpiresAt.gt(now))
+            .order_by_desc(DeletionColumn::CreatedAt)
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if pending.attempts >= MAX_TEAM_DELETION_ATTEMPTS {
+            return Err(invalid());
+        }
+
+        if !verify(code, &pending.code_hash).unwrap_or(false) {
+            let attempts = pending.attempts + 1;
+            let request_id = pending.id;
+            let mut am: DeletionActive = pending.into();
+            am.attempts = Set(attempts);
+            am.update(&self.database_connection).await?;
+            self.record_audit(
+                Some(*user_id),
+                "team.deletion_code_rejected",
+                "team",
+                Some(*team_id),
+                json!({ "request_id": request_id, "attempts": attempts }),
+            )
+            .await?;
+            return Err(invalid());
+        }
+
+        let team = Team::find_by_id(*team_id)
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let members: Vec<Uuid> = TeamMembership::find()
+            .filter(MembershipColumn::TeamId.eq(*team_id))
+            .all(&self.database_connection)
+            .await?
+            .into_iter()
+            .map(|m| m.user_id)
+            .collect();
+
+        let txn = self.database_connection.begin().await?;
+
+        let consumed = TeamDeletion::update_many()
+            .col_expr(DeletionColumn::ConsumedAt, Expr::value(now))
+            .filter(DeletionColumn::Id.eq(pending.id))
+            .filter(DeletionColumn::ConsumedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if consumed.rows_affected == 0 {
+            return Err(invalid());
+        }
+
+        let revoked = revoke_team_credentials(&txn, team_id).await?;
+        Team::delete_by_id(*team_id).exec(&txn).await?;
+        record_audit(
+            &txn,
+            Some(*user_id),
+            "team.deleted",
+            "team",
+            Some(*team_id),
+            json!({
+                "request_id": pending.id,
+                "name": team.name,
+                "members": members,
+                "revoked": revoked,
+            }),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Ok(team)
+    }
+}
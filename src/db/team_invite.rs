@@ -0,0 +1,175 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::{error::AppError, token::TokenType},
+    utils::token::{construct_token, encrypt, extract_token_parts, new_id, new_token, verify},
+};
+use chrono::{Duration, Utc};
+use entity::team_invite::{
+    ActiveModel as InviteActive, Column as InviteColumn, Entity as TeamInvite, Model as InviteModel,
+};
+use entity::team_membership::TeamRole;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+pub const TEAM_INVITE_TTL_DAYS: i64 = 7;
+
+fn new_invite_secret() -> Result<(String, String), AppError> {
+    let secret = new_token(TokenType::TeamInvite);
+    let secret_hash = encrypt(&secret).map_err(|_| {
+        AppError::Internal("There was an issue while encrypting the invite token.".into())
+    })?;
+    Ok((secret, secret_hash))
+}
+
+impl PostgresService {
+    /// Records an invite and returns it with the token to mail. At most one
+    /// pending invite exists per address and team.
+    pub async fn create_team_invite(
+        &self,
+        team_id: &Uuid,
+        email: &str,
+        role: TeamRole,
+        invited_by: &Uuid,
+    ) -> Result<(InviteModel, String), AppError> {
+        let email = email.trim().to_lowercase();
+        let pending = TeamInvite::find()
+            .filter(InviteColumn::TeamId.eq(*team_id))
+            .filter(InviteColumn::Email.eq(email.clone()))
+            .filter(InviteColumn::AcceptedAt.is_null())
+            .filter(InviteColumn::CancelledAt.is_null())
+            .filter(InviteColumn::ExpiresAt.gt(Utc::now()))
+            .one(&self.database_connection)
+            .await?;
+        if pending.is_some() {
+            return Err(AppError::AlreadyExists);
+        }
+
+        let (secret, secret_hash) = new_invite_secret()?;
+        let now = Utc::now();
+        let invite = InviteModel {
+            id: new_id(),
+            team_id: *team_id,
+            email,
+            role,
+            secret_hash,
+            invited_by: *invited_by,
+            expires_at: now + Duration::days(TEAM_INVITE_TTL_DAYS),
+            sent_at: now,
+            accepted_at: None,
+            cancelled_at: None,
+            created_at: now,
+        };
+        TeamInvite::insert(InviteActive::from(invite.clone()))
+            .exec(&self.database_connection)
+            .await?;
+
+        let token = construct_token(&invite.id, &secret);
+        Ok((invite, token))
+    }
+
+    /// Invites that were neither accepted nor cancelled, newest first.
+    /// Expired ones are included so owners can resend them.
+    pub async fn list_team_invites(&self, team_id: &Uuid) -> Result<Vec<InviteModel>, AppError> {
+        Ok(TeamInvite::find()
+            .filter(InviteColumn::TeamId.eq(*team_id))
+            .filter(InviteColumn::AcceptedAt.is_null())
+            .filter(InviteColumn::CancelledAt.is_null())
+            .order_by_desc(InviteColumn::CreatedAt)
+            .all(&self.database_connection)
+            .await?)
+    }
+
+    async fn get_open_team_invite(
+        &self,
+        team_id: &Uuid,
+        invite_id: &Uuid,
+    ) -> Result<InviteModel, AppError> {
+        TeamInvite::find_by_id(*invite_id)
+            .filter(InviteColumn::TeamId.eq(*team_id))
+            .filter(InviteColumn::AcceptedAt.is_null())
+            .filter(InviteColumn::CancelledAt.is_null())
+            .one(&self.database_connection)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Issues a fresh token and expiry for an open invite. The previous
+    /// token stops working.
+    pub async fn resend_team_invite(
+        &self,
+        team_id: &Uuid,
+        invite_id: &Uuid,
+    ) -> Result<(InviteModel, String), AppError> {
+        let invite = self.get_open_team_invite(team_id, invite_id).await?;
+        let (secret, secret_hash) = new_invite_secret()?;
+        let now = Utc::now();
+
+        let mut am: InviteActive = invite.into();
+        am.secret_hash = Set(secret_hash);
+        am.expires_at = Set(now + Duration::days(TEAM_INVITE_TTL_DAYS));
+        am.sent_at = Set(now);
+        let invite = am.update(&self.database_connection).await?;
+
+        let token = construct_token(&invite.id, &secret);
+        Ok((invite, token))
+    }
+
+    pub async fn cancel_team_invite(
+        &self,
+        team_id: &Uuid,
+        invite_id: &Uuid,
+    ) -> Result<(), AppError> {
+        let res = TeamInvite::update_many()
+            .col_expr(InviteColumn::CancelledAt, Expr::value(Utc::now()))
+            .filter(InviteColumn::Id.eq(*invite_id))
+            .filter(InviteColumn::TeamId.eq(*team_id))
+            .filter(InviteColumn::AcceptedAt.is_null())
+            .filter(InviteColumn::CancelledAt.is_null())
+            .exec(&self.database_connection)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Looks up the open, unexpired invite a token belongs to without spending it.
+    pub async fn get_live_team_invite(&self, token: &str) -> Result<InviteModel, AppError> {
+        let invalid = || AppError::BadRequest("Invalid or expired invite.".into());
+
+        let (id, secret) = extract_token_parts(token).ok_or_else(invalid)?;
+        let invite = TeamInvite::find_by_id(id)
+            .filter(InviteColumn::AcceptedAt.is_null())
+            .filter(InviteColumn::CancelledAt.is_null())
+            .filter(InviteColumn::ExpiresAt.gt(Utc::now()))
+            .one(&self.database_connection)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if !verify(&secret, &invite.secret_hash).unwrap_or(false) {
+            return Err(invalid());
+        }
+        Ok(invite)
+    }
+
+    /// Spends an invite. The conditional update means a token accepted twice
+    /// at once only succeeds once.
+    pub async fn mark_team_invite_accepted(&self, invite: &InviteModel) -> Result<(), AppError> {
+        let now = Utc::now();
+        let res = TeamInvite::update_many()
+            .col_expr(InviteColumn::AcceptedAt, Expr::value(now))
+            .filter(InviteColumn::Id.eq(invite.id))
+            .filter(InviteColumn::SecretHash.eq(invite.secret_hash.clone()))
+            .filter(InviteColumn::AcceptedAt.is_null())
+            .filter(InviteColumn::CancelledAt.is_null())
+            .filter(InviteColumn::ExpiresAt.gt(now))
+            .exec(&self.database_connection)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::BadRequest("Invalid or expired invite.".into()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::db::postgres_service::PostgresService;
+use crate::{
+    types::error::AppError,
+    utils::{
+        token::{encrypt, new_id, new_nanoid, verify},
+        totp::{new_totp_secret, verify_totp},
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use entity::totp_recovery_code::{
+    ActiveModel as RecoveryCodeActive, Column as RecoveryCodeColumn, Entity as TotpRecoveryCode,
+};
+use entity::user_totp::{ActiveModel as TotpActive, Column as TotpColumn, Entity as UserTotp};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LEN: usize = 12;
+const MAX_SECOND_FACTOR_FAILURES: i32 = 5;
+const SECOND_FACTOR_LOCKOUT_MINUTES: i64 = 15;
+
+impl PostgresService {
+    /// Starts (or restarts) TOTP enrollment and returns the new base32 secret.
+    ///
+    /// The secret only starts guarding routes once [`Self::confirm_totp`] succeeds.
+    pub async fn begin_totp_enrollment(&self, user_id: &Uuid) -> Result<String, AppError> {
+        if let Some(existing) = UserTotp::find_by_id(*user_id)
+            .one(&self.database_connection)
+            .await?
+        {
+            if existing.confirmed_at.is_some() {
+                return Err(AppError::Conflict("TOTP is already enabled.".into()));
+            }
+            UserTotp::delete_by_id(*user_id)
+                .exec(&self.database_connection)
+                .await?;
+        }
+
+        let secret = new_totp_secret();
+        UserTotp::insert(TotpActive {
+            user_id: Set(*user_id),
+            secret: Set(secret.clone()),
+            confirmed_at: Set(None),
+            last_used_step: Set(0),
+            failed_attempts: Set(0),
+            last_failed_at: Set(None),
+            created_at: Set(Utc::now()),
+        })
+        .exec(&self.database_connection)
+        .await?;
+
+        Ok(secret)
+    }
+
+    /// Finishes enrollment with a code from the authenticator and returns fresh
+    /// recovery codes. They are shown once; only hashes are kept.
+    pub async fn confirm_totp(&self, user_id: &Uuid, code: &str) -> Result<Vec<String>, AppError> {
+        let pending = UserTotp::find_by_id(*user_id)
+            .one(&self.database_connection)
+            .await?
+            .filter(|t| t.confirmed_at.is_none())
+            .ok_or_else(|| AppError::BadRequest("No TOTP enrollment in progress.".into()))?;
+
+        let step = verify_totp(&pending.secret, code, Utc::now().timestamp())
+            .ok_or_else(|| AppError::BadRequest("Invalid one-time code.".into()))?;
+
+        let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        let mut rows = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        for _ in 0..RECOVERY_CODE_COUNT {
+            let code = new_nanoid(RECOVERY_CODE_LEN);
+            let code_hash = encrypt(&code).map_err(|_| {
+                AppError::Internal("There was an issue while encrypting recovery codes.".into())
+            })?;
+            rows.push(RecoveryCodeActive {
+                id: Set(new_id()),
+                user_id: Set(*user_id),
+                code_hash: Set(code_hash),
+                used_at: Set(None),
+            });
+            codes.push(code);
+        }
+
+        let txn = self.database_connection.begin().await?;
+
+        let mut am: TotpActive = pending.into();
+        am.confirmed_at = Set(Some(Utc::now()));
+        am.last_used_step = Set(step);
+        am.update(&txn).await?;
+
+        TotpRecoveryCode::delete_many()
+            .filter(RecoveryCodeColumn::UserId.eq(*user_id))
+            .exec(&txn)
+            .await?;
+        TotpRecoveryCode::insert_many(rows).exec(&txn).await?;
+
+        txn.commit().await?;
+        Ok(codes)
+    }
+
+    pub async fn totp_enabled(&self, user_id: &Uuid) -> Result<bool, AppError> {
+        Ok(UserTotp::find_by_id(*user_id)
+            .one(&self.database_connection)
+            .await?
+            .is_some_and(|t| t.confirmed_at.is_some()))
+    }
+
+    /// Checks a second factor: a current TOTP code or an unused recovery code.
+    ///
+    /// Each TOTP step and each recovery code is accepted at most once; both are
+    /// claimed with conditional updates so concurrent requests can't reuse them.
+    /// After [`MAX_SECOND_FACTOR_FAILURES`] wrong codes every code is refused
+    /// until [`SECOND_FACTOR_LOCKOUT_MINUTES`] have passed since the last one.
+    pub async fn verify_second_factor(&self, user_id: &Uuid, code: &str) -> Result<bool, AppError> {
+        let totp = match UserTotp::find_by_id(*user_id)
+            .one(&self.database_connection)
+            .await?
+            .filter(|t| t.confirmed_at.is_some())
+        {
+            Some(totp) => totp,
+            None => return Ok(false),
+        };
+
+        let now = Utc::now();
+        // A lockout that has run its course starts the count over.
+        UserTotp::update_many()
+            .col_expr(TotpColumn::FailedAttempts, Expr::value(0))
+            .filter(TotpColumn::UserId.eq(*user_id))
+            .filter(TotpColumn::FailedAttempts.gte(MAX_SECOND_FACTOR_FAILURES))
+            .filter(
+                TotpColumn::LastFailedAt
+                    .lte(now - Duration::minutes(SECOND_FACTOR_LOCKOUT_MINUTES)),
+            )
+            .exec(&self.database_connection)
+            .await?;
+
+        // Counted as a failure up front and cleared on success, so parallel
+        // guesses can't slip past the limit.
+        let claimed = UserTotp::update_many()
+            .col_expr(
+                TotpColumn::FailedAttempts,
+                Expr::col(TotpColumn::FailedAttempts).add(1),
+            )
+            .col_expr(TotpColumn::LastFailedAt, Expr::value(now))
+            .filter(TotpColumn::UserId.eq(*user_id))
+            .filter(TotpColumn::FailedAttempts.lt(MAX_SECOND_FACTOR_FAILURES))
+            .exec(&self.database_connection)
+            .await?;
+        if claimed.rows_affected == 0 {
+            return Ok(false);
+        }
+
+        if let Some(step) = verify_totp(&totp.secret, code, now.timestamp()) {
+            let claimed = UserTotp::update_many()
+                .col_expr(TotpColumn::LastUsedStep, Expr::value(step))
+                .col_expr(TotpColumn::FailedAttempts, Expr::value(0))
+                .col_expr(
+                    TotpColumn::LastFailedAt,
+                    Expr::value(Option::<DateTime<Utc>>::None),
+                )
+                .filter(TotpColumn::UserId.eq(*user_id))
+                .filter(TotpColumn::LastUsedStep.lt(step))
+                .exec(&self.database_connection)
+                .await?;
+            return Ok(claimed.rows_affected == 1);
+        }
+
+        let unused = TotpRecoveryCode::find()
+            .filter(RecoveryCodeColumn::UserId.eq(*user_id))
+            .filter(RecoveryCodeColumn::UsedAt.is_null())
+            .all(&self.database_connection)
+            .await?;
+
+        let code = code.trim();
+        let matched = match unused
+            .into_iter()
+            .find(|c| verify(code, &c.code_hash).unwrap_or(false))
+        {
+            Some(matched) => matched,
+            None => return Ok(false),
+        };
+
+        let claimed = TotpRecoveryCode::update_many()
+            .col_expr(RecoveryCodeColumn::UsedAt, Expr::value(Utc::now()))
+            .filter(RecoveryCodeColumn::Id.eq(matched.id))
+            .filter(RecoveryCodeColumn::UsedAt.is_null())
+            .exec(&self.database_connection)
+            .await?;
+        if claimed.rows_affected != 1 {
+            return Ok(false);
+        }
+
+        UserTotp::update_many()
+            .col_expr(TotpColumn::FailedAttempts, Expr::value(0))
+            .col_expr(
+                TotpColumn::LastFailedAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(TotpColumn::UserId.eq(*user_id))
+            .exec(&self.database_connection)
+            .await?;
+        Ok(true)
+    }
+
+    pub async fn disable_totp(&self, user_id: &Uuid) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        TotpRecoveryCode::delete_many()
+            .filter(RecoveryCodeColumn::UserId.eq(*user_id))
+            .exec(&txn)
+            .await?;
+        UserTotp::delete_by_id(*user_id).exec(&txn).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+}
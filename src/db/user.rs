@@ -1,14 +1,25 @@
-use crate::db::postgres_service::PostgresService;
+use crate::db::{audit::record_audit, postgres_service::PostgresService, team::teams_owned_alone};
 use crate::{
-    types::{error::AppError, token::TokenType, user},
-    utils::token::{self, encrypt, new_token},
+    types::{
+        error::AppError,
+        token::TokenType,
+        user::{self, RUserList},
+    },
+    utils::{
+        pagination::{decode_cursor, encode_cursor, like_prefix, page_size},
+        token::{self, encrypt, new_token},
+    },
 };
 use chrono::Utc;
-use entity::user::{ActiveModel as UserActive, Entity as User, Model as UserModel};
+use entity::user::{
+    ActiveModel as UserActive, Entity as User, Model as UserModel, UserRole, UserStatus,
+};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, Set,
-    TransactionTrait,
+    sea_query::{Expr, Func},
+    ActiveModelTrait, ColumnTrait, Condition, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
 };
+use serde_json::json;
 use uuid::Uuid;
 
 impl PostgresService {
@@ -57,13 +68,25 @@ impl PostgresService {
         }
         let uid = token::new_id();
         let now = Utc::now();
+        let status = match payload.email_verified_at {
+            Some(_) => UserStatus::Active,
+            None => UserStatus::Pending,
+        };
         let txn = self.database_connection.begin().await?;
 
         User::insert(UserActive {
             id: Set(uid),
             name: Set(payload.name),
             email: Set(payload.email),
+            pending_email: Set(None),
             auth_hash: Set(payload.auth_hash),
+            password_hash: Set(None),
+            password_changed_at: Set(None),
+            email_verified_at: Set(payload.email_verified_at),
+            role: Set(payload.role),
+            status: Set(status),
+            status_reason: Set(None),
+            status_changed_at: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
         })
@@ -81,15 +104,272 @@ impl PostgresService {
         Ok(am.update(&self.database_connection).await.map(|_| ())?)
     }
 
-    pub async fn update_user_email(&self, user_id: Uuid, email: String) -> Result<(), AppError> {
-        if self.user_exists_by_email(&email).await? {
+    /// Starts moving a user to a new email. The account keeps its current
+    /// address, and stays usable, until the new one is verified. A newer
+    /// request replaces an older one. Returns the address being replaced.
+    pub async fn request_email_change(
+        &self,
+        user_id: &Uuid,
+        email: &str,
+    ) -> Result<String, AppError> {
+        let taken = User::find()
+            .filter(
+                Condition::any()
+                    .add(entity::user::Column::Email.eq(email))
+                    .add(entity::user::Column::PendingEmail.eq(email)),
+            )
+            .filter(entity::user::Column::Id.ne(*user_id))
+            .count(&self.database_connection)
+            .await?
+            > 0;
+        if taken {
             return Err(AppError::Db(DbErr::RecordNotUpdated));
         }
-        let mut am: UserActive = self.get_user_by_id(&user_id).await?.into();
-        am.email = Set(email);
+        let user = self.get_user_by_id(user_id).await?;
+        let current = user.email.clone();
+        let mut am: UserActive = user.into();
+        am.pending_email = Set(Some(email.to_string()));
         am.updated_at = Set(Utc::now());
-        Ok(am.update(&self.database_connection).await.map(|_| ())?)
+        am.update(&self.database_connection).await?;
+        Ok(current)
     }
 
-    // Legacy helpers removed: team management no longer exists in the simplified model.
+    /// Deletes an account and, through the foreign keys, everything it holds.
+    /// Refused while the user is the only owner of a team, which would be
+    /// left without anyone who can manage or delete it.
+    pub async fn delete_user(&self, user_id: &Uuid) -> Result<(), AppError> {
+        let txn = self.database_connection.begin().await?;
+        if !teams_owned_alone(&txn, user_id).await?.is_empty() {
+            return Err(AppError::Conflict(
+                "You are the only owner of a team. Add another owner or delete the team first."
+                    .into(),
+            ));
+        }
+        let res = User::delete_by_id(*user_id).exec(&txn).await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound);
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Moves an account between lifecycle states, recording why and who did it.
+    ///
+    /// Validation reads the status on every request, so the change applies to
+    /// the very next call made with the user's token. Accounts that never
+    /// verified their email can't be made active; they go to pending instead.
+    pub async fn set_user_status(
+        &self,
+        user_id: &Uuid,
+        status: UserStatus,
+        actor_id: Option<Uuid>,
+        reason: String,
+    ) -> Result<UserModel, AppError> {
+        let now = Utc::now();
+        let txn = self.database_connection.begin().await?;
+        let user = User::find_by_id(*user_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let status = match status {
+            UserStatus::Active if user.email_verified_at.is_none() => UserStatus::Pending,
+            status => status,
+        };
+
+        let from = user.status;
+        let mut am: UserActive = user.into();
+        am.status = Set(status);
+        am.status_reason = Set(Some(reason.clone()));
+        am.status_changed_at = Set(Some(now));
+        am.updated_at = Set(now);
+        let user = am.update(&txn).await?;
+
+        record_audit(
+            &txn,
+            actor_id,
+            "user.status_changed",
+            "user",
+            Some(user.id),
+            json!({ "from": from, "to": status, "reason": reason }),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Ok(user)
+    }
+
+    /// Changes an account's role and records who did it. Like status, the
+    /// role is read on every validation, so it applies immediately.
+    pub async fn set_user_role(
+        &self,
+        user_id: &Uuid,
+        role: UserRole,
+        actor_id: Option<Uuid>,
+        reason: Option<&str>,
+    ) -> Result<UserModel, AppError> {
+        let txn = self.database_connection.begin().await?;
+        let user = User::find_by_id(*user_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if user.role == role {
+            return Ok(user);
+        }
+
+        let from = user.role;
+        let mut am: UserActive = user.into();
+        am.role = Set(role);
+        am.updated_at = Set(Utc::now());
+        let user = am.update(&txn).await?;
+
+        record_audit(
+            &txn,
+            actor_id,
+            "user.role_changed",
+            "user",
+            Some(user.id),
+            json!({ "from": from, "to": role, "reason": reason }),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Ok(user)
+    }
+
+    /// Suspends an account and revokes every credential it holds: sessions,
+    /// API keys, OIDC access tokens, and the account token itself (replaced
+    /// with one nobody knows). Reactivating does not bring any of them back.
+    pub async fn deactivate_user(
+        &self,
+        user_id: &Uuid,
+        reason: &str,
+    ) -> Result<UserModel, AppError> {
+        use entity::{api_key, oauth_access_token, session};
+
+        let now = Utc::now();
+        let dead_hash = encrypt(&new_token(TokenType::User)).map_err(|_| {
+            AppError::Internal("There was an issue while encrypting the user's token.".into())
+        })?;
+        let txn = self.database_connection.begin().await?;
+
+        let user = User::find_by_id(*user_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let from = user.status;
+        let mut am: UserActive = user.into();
+        am.status = Set(UserStatus::Suspended);
+        am.status_reason = Set(Some(reason.to_string()));
+        am.status_changed_at = Set(Some(now));
+        am.auth_hash = Set(dead_hash);
+        am.updated_at = Set(now);
+        let user = am.update(&txn).await?;
+        record_audit(
+            &txn,
+            None,
+            "user.status_changed",
+            "user",
+            Some(user.id),
+            json!({ "from": from, "to": UserStatus::Suspended, "reason": reason }),
+        )
+        .await?;
+
+        session::Entity::update_many()
+            .col_expr(session::Column::RevokedAt, Expr::value(now))
+            .filter(session::Column::UserId.eq(*user_id))
+            .filter(session::Column::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+        api_key::Entity::update_many()
+            .col_expr(api_key::Column::RevokedAt, Expr::value(now))
+            .filter(api_key::Column::UserId.eq(*user_id))
+            .filter(api_key::Column::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+        oauth_access_token::Entity::update_many()
+            .col_expr(oauth_access_token::Column::RevokedAt, Expr::value(now))
+            .filter(oauth_access_token::Column::UserId.eq(*user_id))
+            .filter(oauth_access_token::Column::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(user)
+    }
+
+    /// Lifts a suspension. Accounts that never verified their email go back
+    /// to pending rather than active.
+    pub async fn reactivate_user(
+        &self,
+        user_id: &Uuid,
+        reason: &str,
+    ) -> Result<UserModel, AppError> {
+        let user = self.get_user_by_id(user_id).await?;
+        if user.status != UserStatus::Suspended {
+            return Ok(user);
+        }
+        self.set_user_status(user_id, UserStatus::Active, None, reason.to_string())
+            .await
+    }
+
+    /// One page of the admin user directory, newest first.
+    ///
+    /// Returns the users and, if more remain, the cursor for the next page.
+    pub async fn list_users(
+        &self,
+        filter: &RUserList,
+    ) -> Result<(Vec<UserModel>, Option<String>), AppError> {
+        use entity::user::Column;
+
+        let limit = page_size(filter.limit);
+        let mut query = User::find();
+
+        if let Some(q) = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let pattern = like_prefix(&q.to_lowercase());
+            query = query.filter(
+                Condition::any()
+                    .add(Expr::expr(Func::lower(Expr::col(Column::Email))).like(pattern.clone()))
+                    .add(Expr::expr(Func::lower(Expr::col(Column::Name))).like(pattern)),
+            );
+        }
+        if let Some(status) = filter.status {
+            query = query.filter(Column::Status.eq(status));
+        }
+        if let Some(role) = filter.role {
+            query = query.filter(Column::Role.eq(role));
+        }
+        if let Some(after) = filter.created_after {
+            query = query.filter(Column::CreatedAt.gte(after));
+        }
+        if let Some(before) = filter.created_before {
+            query = query.filter(Column::CreatedAt.lt(before));
+        }
+        if let Some(cursor) = &filter.cursor {
+            let (ts, id) = decode_cursor(cursor)
+                .ok_or_else(|| AppError::BadRequest("Invalid cursor.".into()))?;
+            query = query.filter(
+                Condition::any().add(Column::CreatedAt.lt(ts)).add(
+                    Condition::all()
+                        .add(Column::CreatedAt.eq(ts))
+                        .add(Column::Id.lt(id)),
+                ),
+            );
+        }
+
+        let mut users = query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .limit(limit + 1)
+            .all(&self.database_connection)
+            .await?;
+
+        let next_cursor = if users.len() as u64 > limit {
+            users.truncate(limit as usize);
+            users.last().map(|u| encode_cursor(&u.created_at, &u.id))
+        } else {
+            None
+        };
+
+        Ok((users, next_cursor))
+    }
 }
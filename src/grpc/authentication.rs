@@ -1,20 +1,149 @@
 use super::pb::{
     authentication_server::{Authentication, AuthenticationServer},
-    ValidationRequest, ValidationResponse,
+    AuthorizeRequest, AuthorizeResponse, EntitlementsRequest, EntitlementsResponse,
+    QuotaUsage as PbQuotaUsage, RequestContext as PbRequestContext, TeamMembership, UsageRequest,
+    UsageResponse, ValidationRequest, ValidationResponse, WorkspaceKeyRequest,
+    WorkspaceKeyResponse,
 };
-use crate::utils::token::{extract_token_parts, token_valid};
+use crate::db::plan::Reservation;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::token::TokenStatus;
+use crate::utils::policy::{is_valid_resource, Decision, Policy, RbacPolicy, RequestContext};
+use crate::utils::quota::Usage;
+use crate::utils::role::role_name;
+use crate::utils::token::check_token;
 use crate::{config::config, db::postgres_service::PostgresService};
+use chrono::Utc;
+use entity::role_binding::SubjectType;
+use entity::team_membership::TeamRole;
+use entity::user::UserStatus;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct AuthenticationSvc {
     pub postgres_service: Arc<PostgresService>,
+    pub policy: Arc<dyn Policy>,
 }
 
 impl AuthenticationSvc {
     pub fn new(postgres_service: Arc<PostgresService>) -> Self {
-        Self { postgres_service }
+        Self::with_policy(postgres_service, Arc::new(RbacPolicy))
+    }
+
+    pub fn with_policy(postgres_service: Arc<PostgresService>, policy: Arc<dyn Policy>) -> Self {
+        Self {
+            postgres_service,
+            policy,
+        }
+    }
+}
+
+fn has_grpc_auth_key<T>(request: &Request<T>) -> bool {
+    request
+        .metadata()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|key| key == config().grpc.auth_key)
+}
+
+/// Reads the context a calling service sent along. Only the source address
+/// is taken from it: the service sees the client's connection, and is
+/// trusted to report the end user's address rather than its own. Without
+/// one, IP conditions don't hold. The time is always now, and what the
+/// credential says is filled in from the token by [`token_context`], so a
+/// caller can't claim a fresh passkey login.
+fn context_from_pb(ctx: Option<PbRequestContext>) -> Result<RequestContext, Status> {
+    let ctx = ctx.unwrap_or_default();
+    let source_ip = match ctx.source_ip.as_str() {
+        "" => None,
+        ip => Some(
+            ip.parse()
+                .map_err(|_| Status::invalid_argument("Invalid source IP."))?,
+        ),
+    };
+    Ok(RequestContext {
+        source_ip,
+        ..RequestContext::at(Utc::now())
+    })
+}
+
+fn context_to_pb(ctx: &RequestContext) -> PbRequestContext {
+    PbRequestContext {
+        source_ip: ctx.source_ip.map(|ip| ip.to_string()).unwrap_or_default(),
+        time: Some(ctx.time.timestamp()),
+        token_age_secs: ctx.token_age.map(|age| age.num_seconds()),
+        auth_method: ctx.auth_method.clone().unwrap_or_default(),
+    }
+}
+
+/// Completes the caller's context with what the token itself says.
+fn token_context(mut ctx: RequestContext, identity: &AuthenticatedUser) -> RequestContext {
+    ctx.token_age = identity.authenticated_at.map(|at| ctx.time - at);
+    ctx.auth_method = Some(identity.auth_method.clone());
+    ctx
+}
+
+fn usage_to_pb(usage: &Usage) -> PbQuotaUsage {
+    PbQuotaUsage {
+        bytes: usage.bytes,
+        files: usage.files,
+        share_links: usage.share_links,
+    }
+}
+
+impl AuthenticationSvc {
+    /// Parses the user or team a quota call is about and checks it exists.
+    async fn quota_subject(
+        &self,
+        subject_type: &str,
+        subject_id: &str,
+    ) -> Result<(SubjectType, Uuid), Status> {
+        let subject_type = match subject_type {
+            "user" => SubjectType::User,
+            "team" => SubjectType::Team,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "Subject type must be user or team.",
+                ))
+            }
+        };
+        let subject_id = Uuid::parse_str(subject_id)
+            .map_err(|_| Status::invalid_argument("Invalid subject id."))?;
+        let exists = self
+            .postgres_service
+            .subject_exists(subject_type, &subject_id)
+            .await
+            .map_err(|_| Status::internal("Failed to look up the subject."))?;
+        if !exists {
+            return Err(Status::not_found("Unknown subject."));
+        }
+        Ok((subject_type, subject_id))
+    }
+
+    /// Checks the caller and reads the amounts of a reserve or release call.
+    async fn usage_request(
+        &self,
+        request: Request<UsageRequest>,
+    ) -> Result<(SubjectType, Uuid, Usage), Status> {
+        if !has_grpc_auth_key(&request) {
+            return Err(Status::unauthenticated("Invalid authorization token."));
+        }
+        let usage_request = request.into_inner();
+        let (subject_type, subject_id) = self
+            .quota_subject(&usage_request.subject_type, &usage_request.subject_id)
+            .await?;
+        let delta = usage_request.delta.unwrap_or_default();
+        let delta = Usage {
+            bytes: delta.bytes,
+            files: delta.files,
+            share_links: delta.share_links,
+        };
+        if delta.is_negative() {
+            return Err(Status::invalid_argument("Amounts cannot be negative."));
+        }
+        Ok((subject_type, subject_id, delta))
     }
 }
 
@@ -33,40 +162,299 @@ impl Authentication for AuthenticationSvc {
 
         let validation_request = request.into_inner();
 
-        let body_token_valid = token_valid(&self.postgres_service, &validation_request.token).await;
+        let status = check_token(&self.postgres_service, &validation_request.token).await;
         if header_token != config().grpc.auth_key {
             return Ok(Response::new(ValidationResponse {
                 is_valid: false,
                 user_id: "".to_string(),
                 message: "Invalid authorization token.".into(),
+                teams: vec![],
+                role: "".into(),
+                context: None,
+                elevation_id: "".into(),
+                elevated_until: None,
             }));
         }
 
-        let user_id = match extract_token_parts(&validation_request.token) {
-            Some(user_id) => user_id.0,
-            None => {
-                return Ok(Response::new(ValidationResponse {
-                    is_valid: false,
-                    user_id: "".to_string(),
-                    message: "Malformed token.".into(),
-                }))
-            }
+        // Filled in from the token so the caller can pass it on to `Authorize`.
+        let context = match &status {
+            TokenStatus::Valid(identity) => Some(context_to_pb(&token_context(
+                context_from_pb(validation_request.context)?,
+                identity,
+            ))),
+            _ => None,
+        };
+
+        // Lets the file service scope storage per workspace without a second call.
+        let teams = match &status {
+            TokenStatus::Valid(identity) => self
+                .postgres_service
+                .list_user_teams(&identity.user_id)
+                .await
+                .map_err(|_| Status::internal("Failed to load team memberships."))?
+                .into_iter()
+                .map(|(membership, team)| TeamMembership {
+                    team_id: team.id.into(),
+                    team_name: team.name,
+                    role: team_role(membership.role).into(),
+                })
+                .collect(),
+            _ => vec![],
         };
 
         Ok(Response::new(ValidationResponse {
-            is_valid: body_token_valid,
-            user_id: if body_token_valid {
-                user_id.into()
-            } else {
-                "".into()
+            is_valid: status.is_valid(),
+            user_id: match &status {
+                TokenStatus::Valid(identity) => identity.user_id.into(),
+                _ => "".into(),
+            },
+            role: match &status {
+                TokenStatus::Valid(identity) => role_name(identity.role).into(),
+                _ => "".into(),
+            },
+            // Tells services the role is temporary, and until when.
+            elevation_id: match &status {
+                TokenStatus::Valid(AuthenticatedUser {
+                    elevation: Some(elevation),
+                    ..
+                }) => elevation.elevation_id.into(),
+                _ => "".into(),
+            },
+            elevated_until: match &status {
+                TokenStatus::Valid(AuthenticatedUser {
+                    elevation: Some(elevation),
+                    ..
+                }) => Some(elevation.expires_at.timestamp()),
+                _ => None,
+            },
+            message: match status {
+                TokenStatus::Valid(_) => "ok".into(),
+                TokenStatus::Pending => "pending email verification".into(),
+                TokenStatus::Suspended => "suspended".into(),
+                TokenStatus::Locked => "locked".into(),
+                TokenStatus::Invalid => "invalid".into(),
+            },
+            teams,
+            context,
+        }))
+    }
+
+    /// Answers "may this user perform `action` on `resource`" from role
+    /// bindings, so services need not reimplement the policy. Binding
+    /// conditions about the credential are checked against the user's token,
+    /// when the caller passes it; without one they don't hold.
+    async fn authorize(
+        &self,
+        request: Request<AuthorizeRequest>,
+    ) -> Result<Response<AuthorizeResponse>, Status> {
+        if !has_grpc_auth_key(&request) {
+            return Err(Status::unauthenticated("Invalid authorization token."));
+        }
+        let authorize_request = request.into_inner();
+
+        let subject = Uuid::parse_str(&authorize_request.subject)
+            .map_err(|_| Status::invalid_argument("Subject must be a user id."))?;
+        let mut ctx = context_from_pb(authorize_request.context)?;
+        if authorize_request.action.is_empty() || !is_valid_resource(&authorize_request.resource) {
+            return Err(Status::invalid_argument(
+                "Action and resource are required.",
+            ));
+        }
+
+        let deny = |message: &str| {
+            Ok(Response::new(AuthorizeResponse {
+                allowed: false,
+                message: message.into(),
+            }))
+        };
+        if !authorize_request.token.is_empty() {
+            match check_token(&self.postgres_service, &authorize_request.token).await {
+                TokenStatus::Valid(identity) if identity.user_id == subject => {
+                    ctx = token_context(ctx, &identity);
+                }
+                TokenStatus::Valid(_) => {
+                    return Err(Status::invalid_argument(
+                        "The token belongs to another user.",
+                    ))
+                }
+                _ => return deny("token is not valid"),
+            }
+        }
+        let user = match self.postgres_service.get_user_by_id(&subject).await {
+            Ok(user) => user,
+            Err(_) => return deny("unknown subject"),
+        };
+        if user.status != UserStatus::Active {
+            return deny("subject is not active");
+        }
+
+        let decision = self
+            .postgres_service
+            .authorize_user(
+                self.policy.as_ref(),
+                &subject,
+                &authorize_request.action,
+                &authorize_request.resource,
+                &ctx,
+            )
+            .await
+            .map_err(|_| Status::internal("Failed to load role bindings and ACLs."))?;
+
+        match decision {
+            Decision::Allow { binding_id } => Ok(Response::new(AuthorizeResponse {
+                allowed: true,
+                message: format!("allowed by binding {binding_id}"),
+            })),
+            Decision::AllowAcl { entry_id } => Ok(Response::new(AuthorizeResponse {
+                allowed: true,
+                message: format!("allowed by acl entry {entry_id}"),
+            })),
+            Decision::Deny => deny("no binding or acl entry grants this action"),
+        }
+    }
+
+    /// Hands out a workspace's data key for SSE-C, on behalf of a user who
+    /// is a member of the team that owns the workspace. The key is created
+    /// on first request.
+    async fn get_workspace_key(
+        &self,
+        request: Request<WorkspaceKeyRequest>,
+    ) -> Result<Response<WorkspaceKeyResponse>, Status> {
+        if !has_grpc_auth_key(&request) {
+            return Err(Status::unauthenticated("Invalid authorization token."));
+        }
+        let key_request = request.into_inner();
+        let keyring = config()
+            .kms
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("Key management is not configured."))?;
+
+        let subject = Uuid::parse_str(&key_request.subject)
+            .map_err(|_| Status::invalid_argument("Subject must be a user id."))?;
+        let workspace_id = Uuid::parse_str(&key_request.workspace_id)
+            .map_err(|_| Status::invalid_argument("Workspace must be a team id."))?;
+
+        let denied = || Status::permission_denied("No access to this workspace.");
+        let user = self
+            .postgres_service
+            .get_user_by_id(&subject)
+            .await
+            .map_err(|_| denied())?;
+        if user.status != UserStatus::Active {
+            return Err(denied());
+        }
+        self.postgres_service
+            .get_team_membership(&workspace_id, &subject)
+            .await
+            .map_err(|_| Status::internal("Failed to load team membership."))?
+            .ok_or_else(denied)?;
+
+        let key = self
+            .postgres_service
+            .workspace_data_key(keyring, &workspace_id)
+            .await
+            .map_err(|_| Status::internal("Failed to load the workspace key."))?;
+
+        Ok(Response::new(WorkspaceKeyResponse {
+            workspace_id: workspace_id.into(),
+            key,
+        }))
+    }
+
+    /// The plan limits, feature flags, and usage of a user or team. Without
+    /// a plan nothing is capped and no features are on.
+    async fn get_entitlements(
+        &self,
+        request: Request<EntitlementsRequest>,
+    ) -> Result<Response<EntitlementsResponse>, Status> {
+        if !has_grpc_auth_key(&request) {
+            return Err(Status::unauthenticated("Invalid authorization token."));
+        }
+        let entitlements_request = request.into_inner();
+        let (subject_type, subject_id) = self
+            .quota_subject(
+                &entitlements_request.subject_type,
+                &entitlements_request.subject_id,
+            )
+            .await?;
+
+        let (plan, usage) = self
+            .postgres_service
+            .get_entitlements(subject_type, &subject_id)
+            .await
+            .map_err(|_| Status::internal("Failed to load entitlements."))?;
+
+        Ok(Response::new(match plan {
+            Some(plan) => EntitlementsResponse {
+                max_bytes: plan.max_bytes,
+                max_files: plan.max_files,
+                max_share_links: plan.max_share_links,
+                features: serde_json::from_value(plan.features).unwrap_or_default(),
+                plan: plan.name,
+                usage: Some(usage_to_pb(&usage)),
             },
-            message: if body_token_valid {
-                "ok".into()
-            } else {
-                "invalid".into()
+            None => EntitlementsResponse {
+                usage: Some(usage_to_pb(&usage)),
+                ..Default::default()
             },
         }))
     }
+
+    /// Counts usage against the plan before the caller stores anything.
+    /// Either all of the amounts are reserved or none are.
+    async fn reserve_usage(
+        &self,
+        request: Request<UsageRequest>,
+    ) -> Result<Response<UsageResponse>, Status> {
+        let (subject_type, subject_id, delta) = self.usage_request(request).await?;
+
+        let reservation = self
+            .postgres_service
+            .reserve_usage(subject_type, &subject_id, &delta)
+            .await
+            .map_err(|_| Status::internal("Failed to reserve usage."))?;
+
+        Ok(Response::new(match reservation {
+            Reservation::Granted(usage) => UsageResponse {
+                granted: true,
+                message: "ok".into(),
+                usage: Some(usage_to_pb(&usage)),
+            },
+            Reservation::Denied(exceeded, usage) => UsageResponse {
+                granted: false,
+                message: exceeded.message().into(),
+                usage: Some(usage_to_pb(&usage)),
+            },
+        }))
+    }
+
+    /// Returns usage the caller no longer holds, e.g. after a delete or an
+    /// aborted upload.
+    async fn release_usage(
+        &self,
+        request: Request<UsageRequest>,
+    ) -> Result<Response<UsageResponse>, Status> {
+        let (subject_type, subject_id, delta) = self.usage_request(request).await?;
+
+        let usage = self
+            .postgres_service
+            .release_usage(subject_type, &subject_id, &delta)
+            .await
+            .map_err(|_| Status::internal("Failed to release usage."))?;
+
+        Ok(Response::new(UsageResponse {
+            granted: true,
+            message: "ok".into(),
+            usage: Some(usage_to_pb(&usage)),
+        }))
+    }
+}
+
+fn team_role(role: TeamRole) -> &'static str {
+    match role {
+        TeamRole::Owner => "owner",
+        TeamRole::Member => "member",
+    }
 }
 
 pub fn server(postgres_service: Arc<PostgresService>) -> AuthenticationServer<AuthenticationSvc> {
@@ -29,6 +29,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     info!("Started postgres!");
 
+    // Elevations stop applying on their own; this records that they ended.
+    let sweeper_db = postgres_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = sweeper_db.expire_elevations().await {
+                error!("Failed to expire elevations: {}", e);
+            }
+        }
+    });
+
     let grpc_addr = format!("0.0.0.0:{}", config.grpc.port).parse()?;
     let grpc_service = authentication::server(postgres_service.clone());
 
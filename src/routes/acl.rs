@@ -0,0 +1,116 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::acl::{AclEntryRes, RAclGrant, RAclList};
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::policy::{is_valid_permission, is_valid_resource, RbacPolicy, GLOBAL_SCOPE};
+use crate::utils::webutils::request_context;
+use actix_web::{delete, get, post, web, HttpRequest};
+use entity::user::UserRole;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Permission needed to see and change the ACL of a resource.
+pub const ACL_MANAGE: &str = "acl.manage";
+
+/// Admins may act on any ACL; everyone else must be allowed `action` on
+/// `resource` by a role binding or an existing ACL entry.
+async fn require_allowed(
+    req: &HttpRequest,
+    db: &PostgresService,
+    identity: &AuthenticatedUser,
+    action: &str,
+    resource: &str,
+) -> Result<(), AppError> {
+    if identity.role == UserRole::Admin {
+        return Ok(());
+    }
+    let decision = db
+        .authorize_user(
+            &RbacPolicy,
+            &identity.user_id,
+            action,
+            resource,
+            &request_context(req, identity),
+        )
+        .await?;
+    if decision.is_allowed() {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden)
+    }
+}
+
+fn acl_resource(raw: &str) -> Result<&str, AppError> {
+    let resource = raw.trim();
+    if resource == GLOBAL_SCOPE || !is_valid_resource(resource) {
+        return Err(AppError::Validation(
+            "A resource path such as workspace:<id>/file:<id> is required.".into(),
+        ));
+    }
+    Ok(resource)
+}
+
+/// Shares a resource. Callers need `acl.manage` on it and can only hand out
+/// permissions they hold there themselves.
+#[post("")]
+async fn grant(
+    req: HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RAclGrant>,
+) -> ApiResult<AclEntryRes> {
+    let body = body.into_inner();
+    let resource = acl_resource(&body.resource)?;
+    let permission = body.permission.trim();
+    if !is_valid_permission(permission) {
+        return Err(AppError::Validation("Invalid permission name.".into()));
+    }
+
+    require_allowed(&req, &db, &identity, ACL_MANAGE, resource).await?;
+    require_allowed(&req, &db, &identity, permission, resource).await?;
+
+    let entry = db
+        .grant_acl(
+            resource,
+            body.principal_type,
+            &body.principal_id,
+            permission,
+            body.inherited,
+            Some(identity.user_id),
+        )
+        .await?;
+    Ok(ApiResponse::Created(AclEntryRes::from(entry)))
+}
+
+/// Entries that apply to a resource, including those inherited from its
+/// parent folders.
+#[get("")]
+async fn list(
+    req: HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    query: web::Query<RAclList>,
+) -> ApiResult<Vec<AclEntryRes>> {
+    let resource = acl_resource(&query.resource)?;
+    require_allowed(&req, &db, &identity, ACL_MANAGE, resource).await?;
+
+    let entries = db.list_acl(resource).await?;
+    Ok(ApiResponse::Ok(
+        entries.into_iter().map(AclEntryRes::from).collect(),
+    ))
+}
+
+#[delete("/{id}")]
+async fn revoke(
+    req: HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    let entry = db.get_acl_entry(&path.into_inner()).await?;
+    require_allowed(&req, &db, &identity, ACL_MANAGE, &entry.resource).await?;
+
+    db.revoke_acl(&entry, Some(identity.user_id)).await?;
+    Ok(ApiResponse::NoContent)
+}
@@ -0,0 +1,20 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::audit::{AuditEventRes, RAuditList};
+use crate::types::response::{ApiResponse, ApiResult, Page};
+use actix_web::{get, web};
+use std::sync::Arc;
+
+/// The audit trail, newest first. Filters combine; `cursor` continues a previous page.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RAuditList>,
+) -> ApiResult<Page<AuditEventRes>> {
+    let (events, next_cursor) = db.list_audit_events(&query).await?;
+
+    Ok(ApiResponse::Ok(Page {
+        items: events.into_iter().map(AuditEventRes::from).collect(),
+        next_cursor,
+    }))
+}
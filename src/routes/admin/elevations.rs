@@ -0,0 +1,117 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::elevation::{ElevationRes, EligibilityRes, RElevationList, REligibilityCreate};
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::elevation::MAX_ELEVATION_MINUTES;
+use actix_web::{delete, get, post, web};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Lets a user or team ask for a role for a limited time.
+#[post("")]
+async fn create_eligibility(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    body: web::Json<REligibilityCreate>,
+) -> ApiResult<EligibilityRes> {
+    let body = body.into_inner();
+    if body.max_minutes < 1 || body.max_minutes > MAX_ELEVATION_MINUTES {
+        return Err(AppError::Validation(format!(
+            "Elevations last between 1 and {MAX_ELEVATION_MINUTES} minutes."
+        )));
+    }
+
+    let eligibility = db
+        .create_elevation_eligibility(
+            body.subject_type,
+            &body.subject_id,
+            body.role,
+            body.max_minutes,
+            body.requires_approval,
+            identity.map(|i| i.user_id),
+        )
+        .await?;
+    Ok(ApiResponse::Created(EligibilityRes::from(eligibility)))
+}
+
+#[get("")]
+async fn list_eligibilities(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> ApiResult<Vec<EligibilityRes>> {
+    let eligibilities = db.list_elevation_eligibilities().await?;
+    Ok(ApiResponse::Ok(
+        eligibilities
+            .into_iter()
+            .map(EligibilityRes::from)
+            .collect(),
+    ))
+}
+
+#[delete("/{id}")]
+async fn delete_eligibility(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.delete_elevation_eligibility(&path.into_inner(), identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
+
+/// Elevation requests and grants, newest first, e.g. `?status=pending`.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RElevationList>,
+) -> ApiResult<Vec<ElevationRes>> {
+    let elevations = db.list_elevations(&query).await?;
+    Ok(ApiResponse::Ok(
+        elevations.into_iter().map(ElevationRes::from).collect(),
+    ))
+}
+
+/// Starts a pending elevation. Nobody approves their own request.
+#[post("/{id}/approve")]
+async fn approve(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<ElevationRes> {
+    let elevation = db
+        .decide_elevation(&path.into_inner(), identity.map(|i| i.user_id), true)
+        .await?;
+    Ok(ApiResponse::Ok(ElevationRes::from(elevation)))
+}
+
+#[post("/{id}/deny")]
+async fn deny(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<ElevationRes> {
+    let elevation = db
+        .decide_elevation(&path.into_inner(), identity.map(|i| i.user_id), false)
+        .await?;
+    Ok(ApiResponse::Ok(ElevationRes::from(elevation)))
+}
+
+/// Ends someone's elevation before it runs out.
+#[post("/{id}/revoke")]
+async fn revoke(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<ElevationRes> {
+    let elevation = db
+        .revoke_elevation(&path.into_inner(), None, identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::Ok(ElevationRes::from(elevation)))
+}
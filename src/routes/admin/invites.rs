@@ -0,0 +1,62 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::error::AppError;
+use crate::types::invite::{InviteCreateRes, InviteRes, RInviteCreate};
+use crate::types::response::{ApiResponse, ApiResult};
+use actix_web::{delete, get, post, web};
+use chrono::{Duration, Utc};
+use entity::user::UserRole;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[post("")]
+async fn create(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RInviteCreate>,
+) -> ApiResult<InviteCreateRes> {
+    let max_uses = body.max_uses.unwrap_or(1);
+    if max_uses < 1 {
+        return Err(AppError::Validation("max_uses must be at least 1.".into()));
+    }
+
+    let expires_at = match body.expires_in_hours {
+        Some(hours) if hours < 1 => {
+            return Err(AppError::Validation(
+                "expires_in_hours must be at least 1.".into(),
+            ))
+        }
+        Some(hours) => Some(Utc::now() + Duration::hours(hours)),
+        None => None,
+    };
+
+    let (id, code) = db
+        .create_signup_invite(
+            max_uses,
+            expires_at,
+            body.default_role.unwrap_or(UserRole::Member),
+        )
+        .await?;
+
+    Ok(ApiResponse::Created(InviteCreateRes { id, code }))
+}
+
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> ApiResult<Vec<InviteRes>> {
+    let invites = db.list_signup_invites().await?;
+    Ok(ApiResponse::Ok(
+        invites.into_iter().map(InviteRes::from).collect(),
+    ))
+}
+
+#[delete("/{id}")]
+async fn revoke(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.revoke_signup_invite(&path.into_inner()).await?;
+    Ok(ApiResponse::NoContent)
+}
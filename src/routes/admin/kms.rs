@@ -0,0 +1,32 @@
+use crate::config::config;
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::kms::KeyRotationRes;
+use crate::types::response::{ApiResponse, ApiResult};
+use actix_web::{post, web};
+use std::sync::Arc;
+
+/// Rewraps every workspace key under `KMS_MASTER_KEY`. Run after moving the
+/// old master key to `KMS_RETIRED_MASTER_KEYS`; once nothing fails, the old
+/// key can be dropped from the config.
+#[post("/rotate")]
+async fn rotate(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+) -> ApiResult<KeyRotationRes> {
+    let keyring = config()
+        .kms
+        .as_ref()
+        .ok_or_else(|| AppError::BadRequest("Key management is not configured.".into()))?;
+
+    let rotation = db
+        .rotate_workspace_keys(keyring, identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::Ok(KeyRotationRes {
+        master_key_id: keyring.current.id().to_string(),
+        rewrapped: rotation.rewrapped,
+        failed: rotation.failed,
+    }))
+}
@@ -0,0 +1,8 @@
+pub mod audit;
+pub mod elevations;
+pub mod invites;
+pub mod kms;
+pub mod oauth_clients;
+pub mod plans;
+pub mod rbac;
+pub mod users;
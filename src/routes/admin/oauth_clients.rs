@@ -0,0 +1,65 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::error::AppError;
+use crate::types::oidc::{OAuthClientCreateRes, OAuthClientRes, ROAuthClientCreate};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::oidc::is_valid_redirect_uri;
+use actix_web::{delete, get, post, web};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Registers an application that signs users in with Ledger.
+#[post("")]
+async fn create(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<ROAuthClientCreate>,
+) -> ApiResult<OAuthClientCreateRes> {
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("A client name is required.".into()));
+    }
+    if body.redirect_uris.is_empty() {
+        return Err(AppError::Validation(
+            "At least one redirect URI is required.".into(),
+        ));
+    }
+    if let Some(bad) = body
+        .redirect_uris
+        .iter()
+        .find(|uri| !is_valid_redirect_uri(uri))
+    {
+        return Err(AppError::Validation(format!(
+            "Redirect URIs must be absolute https URLs (http only for localhost): {bad}"
+        )));
+    }
+
+    let (client, client_secret) = db
+        .create_oauth_client(name, &body.redirect_uris, body.confidential)
+        .await?;
+
+    Ok(ApiResponse::Created(OAuthClientCreateRes {
+        client: OAuthClientRes::from(client),
+        client_secret,
+    }))
+}
+
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> ApiResult<Vec<OAuthClientRes>> {
+    let clients = db.list_oauth_clients().await?;
+    Ok(ApiResponse::Ok(
+        clients.into_iter().map(OAuthClientRes::from).collect(),
+    ))
+}
+
+#[delete("/{id}")]
+async fn revoke(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.revoke_oauth_client(&path.into_inner()).await?;
+    Ok(ApiResponse::NoContent)
+}
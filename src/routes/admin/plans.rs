@@ -0,0 +1,123 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::plan::{EntitlementsRes, PlanRes, RPlan, RPlanAssign};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::quota::is_valid_feature;
+use actix_web::{delete, get, post, put, web};
+use entity::role_binding::SubjectType;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn validate_plan(mut plan: RPlan) -> Result<RPlan, AppError> {
+    plan.name = plan.name.trim().to_string();
+    if plan.name.is_empty() {
+        return Err(AppError::Validation("A plan name is required.".into()));
+    }
+    if [plan.max_bytes, plan.max_files, plan.max_share_links]
+        .iter()
+        .flatten()
+        .any(|max| *max < 0)
+    {
+        return Err(AppError::Validation("Limits cannot be negative.".into()));
+    }
+    if !plan.features.iter().all(|f| is_valid_feature(f)) {
+        return Err(AppError::Validation(
+            "Features are dotted lowercase words, e.g. public_links.".into(),
+        ));
+    }
+    Ok(plan)
+}
+
+#[post("")]
+async fn create_plan(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    body: web::Json<RPlan>,
+) -> ApiResult<PlanRes> {
+    let plan = validate_plan(body.into_inner())?;
+    let plan = db.create_plan(&plan, identity.map(|i| i.user_id)).await?;
+    Ok(ApiResponse::Created(PlanRes::from(plan)))
+}
+
+#[get("")]
+async fn list_plans(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> ApiResult<Vec<PlanRes>> {
+    let plans = db.list_plans().await?;
+    Ok(ApiResponse::Ok(
+        plans.into_iter().map(PlanRes::from).collect(),
+    ))
+}
+
+/// Replaces a plan's name, limits, and features.
+#[put("/{id}")]
+async fn update_plan(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    body: web::Json<RPlan>,
+) -> ApiResult<PlanRes> {
+    let plan = validate_plan(body.into_inner())?;
+    let plan = db
+        .update_plan(&path.into_inner(), &plan, identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::Ok(PlanRes::from(plan)))
+}
+
+#[delete("/{id}")]
+async fn delete_plan(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.delete_plan(&path.into_inner(), identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
+
+/// A user's or team's plan and current usage.
+#[get("/{subject_type}/{subject_id}")]
+async fn get_entitlements(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<(SubjectType, Uuid)>,
+) -> ApiResult<EntitlementsRes> {
+    let (subject_type, subject_id) = path.into_inner();
+    if !db.subject_exists(subject_type, &subject_id).await? {
+        return Err(AppError::NotFound);
+    }
+    let (plan, usage) = db.get_entitlements(subject_type, &subject_id).await?;
+    Ok(ApiResponse::Ok(EntitlementsRes {
+        plan: plan.map(PlanRes::from),
+        usage,
+    }))
+}
+
+/// Puts a user or team on a plan, or takes it off.
+#[put("/{subject_type}/{subject_id}")]
+async fn assign_plan(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<(SubjectType, Uuid)>,
+    body: web::Json<RPlanAssign>,
+) -> ApiResult<EntitlementsRes> {
+    let (subject_type, subject_id) = path.into_inner();
+    db.assign_plan(
+        subject_type,
+        &subject_id,
+        body.plan_id,
+        identity.map(|i| i.user_id),
+    )
+    .await?;
+    let (plan, usage) = db.get_entitlements(subject_type, &subject_id).await?;
+    Ok(ApiResponse::Ok(EntitlementsRes {
+        plan: plan.map(PlanRes::from),
+        usage,
+    }))
+}
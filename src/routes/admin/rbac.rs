@@ -0,0 +1,148 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::rbac::{
+    PermissionRes, RPermissionCreate, RRbacRoleCreate, RRoleBindingCreate, RRoleBindingList,
+    RbacRoleRes, RoleBindingRes,
+};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::policy::{is_valid_permission, is_valid_resource};
+use actix_web::{delete, get, post, web};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Adds an action that roles can grant.
+#[post("")]
+async fn create_permission(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RPermissionCreate>,
+) -> ApiResult<PermissionRes> {
+    let body = body.into_inner();
+    let name = body.name.trim();
+    if !is_valid_permission(name) {
+        return Err(AppError::Validation(
+            "Permission names are dotted lowercase words, e.g. file.delete or file.*.".into(),
+        ));
+    }
+
+    let permission = db.create_permission(name, body.description).await?;
+    Ok(ApiResponse::Created(PermissionRes::from(permission)))
+}
+
+#[get("")]
+async fn list_permissions(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> ApiResult<Vec<PermissionRes>> {
+    let permissions = db.list_permissions().await?;
+    Ok(ApiResponse::Ok(
+        permissions.into_iter().map(PermissionRes::from).collect(),
+    ))
+}
+
+#[post("")]
+async fn create_role(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    body: web::Json<RRbacRoleCreate>,
+) -> ApiResult<RbacRoleRes> {
+    let body = body.into_inner();
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("A role name is required.".into()));
+    }
+
+    let role = db
+        .create_rbac_role(
+            name,
+            body.description,
+            &body.permissions,
+            identity.map(|i| i.user_id),
+        )
+        .await?;
+    Ok(ApiResponse::Created(RbacRoleRes::from(role)))
+}
+
+#[get("")]
+async fn list_roles(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> ApiResult<Vec<RbacRoleRes>> {
+    let roles = db.list_rbac_roles().await?;
+    Ok(ApiResponse::Ok(
+        roles.into_iter().map(RbacRoleRes::from).collect(),
+    ))
+}
+
+/// Deletes a role and every binding to it.
+#[delete("/{id}")]
+async fn delete_role(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.delete_rbac_role(&path.into_inner(), identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
+
+/// Grants a role to a user or team on a resource scope, optionally only
+/// under conditions on the request context.
+#[post("")]
+async fn create_binding(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    body: web::Json<RRoleBindingCreate>,
+) -> ApiResult<RoleBindingRes> {
+    let body = body.into_inner();
+    let scope = body.scope.trim();
+    if !is_valid_resource(scope) {
+        return Err(AppError::Validation(
+            "Scopes are /-separated resource paths, or * for everything.".into(),
+        ));
+    }
+
+    if let Some(problem) = body.conditions.iter().find_map(|c| c.problem()) {
+        return Err(AppError::Validation(problem));
+    }
+
+    let binding = db
+        .create_role_binding(
+            body.subject_type,
+            &body.subject_id,
+            &body.role_id,
+            scope,
+            &body.conditions,
+            identity.map(|i| i.user_id),
+        )
+        .await?;
+    Ok(ApiResponse::Created(RoleBindingRes::from(binding)))
+}
+
+#[get("")]
+async fn list_bindings(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RRoleBindingList>,
+) -> ApiResult<Vec<RoleBindingRes>> {
+    let bindings = db.list_role_bindings(&query).await?;
+    Ok(ApiResponse::Ok(
+        bindings.into_iter().map(RoleBindingRes::from).collect(),
+    ))
+}
+
+#[delete("/{id}")]
+async fn delete_binding(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.delete_role_binding(&path.into_inner(), identity.map(|i| i.user_id))
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
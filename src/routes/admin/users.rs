@@ -0,0 +1,89 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult, Page};
+use crate::types::user::{RUserList, RUserRoleUpdate, RUserStatusUpdate, UserRes};
+use actix_web::{get, put, web};
+use entity::user::UserStatus;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Lists accounts, newest first. Filters combine; `cursor` continues a previous page.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RUserList>,
+) -> ApiResult<Page<UserRes>> {
+    let (users, next_cursor) = db.list_users(&query).await?;
+
+    Ok(ApiResponse::Ok(Page {
+        items: users.into_iter().map(UserRes::from).collect(),
+        next_cursor,
+    }))
+}
+
+/// Suspends, locks or reactivates an account. Reactivating an account that
+/// never verified its email leaves it pending.
+#[put("/{id}/status")]
+async fn set_status(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    body: web::Json<RUserStatusUpdate>,
+) -> ApiResult<UserRes> {
+    let user_id = path.into_inner();
+    let body = body.into_inner();
+
+    let reason = body.reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(AppError::Validation("A reason is required.".into()));
+    }
+    if body.status == UserStatus::Pending {
+        return Err(AppError::Validation(
+            "Pending is set by email verification, not by admins.".into(),
+        ));
+    }
+
+    let actor_id = identity.map(|i| i.user_id);
+    let user = db
+        .set_user_status(&user_id, body.status, actor_id, reason)
+        .await?;
+    info!(
+        "User {} status set to {:?}: {}",
+        user.id,
+        user.status,
+        user.status_reason.as_deref().unwrap_or_default()
+    );
+
+    Ok(ApiResponse::Ok(UserRes::from(user)))
+}
+
+/// Changes an account's role. Only reachable with admin credentials: the
+/// static admin key, or a token of an account that is itself an admin.
+#[put("/{id}/role")]
+async fn set_role(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: Option<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    body: web::Json<RUserRoleUpdate>,
+) -> ApiResult<UserRes> {
+    let user_id = path.into_inner();
+    let body = body.into_inner();
+    let actor_id = identity.map(|i| i.user_id);
+    let reason = body
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    let user = db
+        .set_user_role(&user_id, body.role, actor_id, reason)
+        .await?;
+    info!("User {} role set to {:?}", user.id, user.role);
+
+    Ok(ApiResponse::Ok(UserRes::from(user)))
+}
@@ -0,0 +1,183 @@
+use crate::config::{config, FederatedProviderConfig};
+use crate::db::{federation::FEDERATED_LOGIN_TTL_MINUTES, postgres_service::PostgresService};
+use crate::types::auth::{FederatedProviderRes, RFederatedCallback};
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::{
+    federation::{
+        authorization_url, complete_login, discover, provider, random_urlsafe, ExternalIdentity,
+    },
+    oidc::pkce_challenge,
+    registration::SignupChannel,
+    user::provision_verified_user,
+    webutils::{session_origin, session_response},
+};
+use actix_web::{
+    cookie::{time::Duration as CookieDuration, Cookie, SameSite},
+    get,
+    http::header::LOCATION,
+    web, HttpResponse,
+};
+use entity::user::{Model as UserModel, UserStatus};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Binds a federated login to the browser that started it, so a `state`
+/// lifted from someone else's redirect is useless (login CSRF).
+const FEDERATED_COOKIE: &str = "ledger_federated";
+const FEDERATED_COOKIE_PATH: &str = "/auth/federated";
+
+fn configured(id: &str) -> Result<&'static FederatedProviderConfig, AppError> {
+    provider(id).ok_or(AppError::NotFound)
+}
+
+/// Lists the identity providers shown on the login screen.
+#[get("")]
+async fn providers(_req: actix_web::HttpRequest) -> ApiResult<Vec<FederatedProviderRes>> {
+    Ok(ApiResponse::Ok(
+        config()
+            .federation
+            .iter()
+            .map(|p| FederatedProviderRes {
+                id: p.id.clone(),
+                name: p.name.clone(),
+            })
+            .collect(),
+    ))
+}
+
+/// Sends the browser to the provider's login page.
+#[get("/{provider}/start")]
+async fn start(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let provider = configured(&path)?;
+    let metadata = discover(provider).await?;
+
+    let nonce = random_urlsafe();
+    let code_verifier = random_urlsafe();
+    let state = db
+        .create_federated_login(&provider.id, &nonce, &code_verifier)
+        .await?;
+    let location = authorization_url(
+        &metadata,
+        provider,
+        &state.to_string(),
+        &nonce,
+        &pkce_challenge(&code_verifier),
+    )?;
+
+    // Lax, not Strict: the provider brings the browser back with a top-level GET.
+    let cookie = Cookie::build(FEDERATED_COOKIE, state.to_string())
+        .path(FEDERATED_COOKIE_PATH)
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(CookieDuration::minutes(FEDERATED_LOGIN_TTL_MINUTES))
+        .finish();
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, location))
+        .cookie(cookie)
+        .finish())
+}
+
+/// Finishes a federated login and opens a cookie session.
+///
+/// The provider's account is matched by its subject first. A new subject is
+/// linked to the account with the same email if the provider vouches for the
+/// address, and otherwise gets a new account when the provider allows it.
+/// Second factors are the provider's job, so local TOTP is not asked for.
+#[get("/{provider}/callback")]
+async fn callback(
+    req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<String>,
+    query: web::Query<RFederatedCallback>,
+) -> Result<HttpResponse, AppError> {
+    let provider = configured(&path)?;
+    let invalid = || AppError::BadRequest("Unknown or expired login attempt.".into());
+
+    let state = query.state.as_deref().ok_or_else(invalid)?;
+    let bound = req
+        .cookie(FEDERATED_COOKIE)
+        .is_some_and(|c| c.value() == state);
+    if !bound {
+        return Err(invalid());
+    }
+    let state = Uuid::parse_str(state).map_err(|_| invalid())?;
+    let login = db.take_federated_login(&state, &provider.id).await?;
+
+    if query.error.is_some() {
+        return Err(AppError::Unauthorized);
+    }
+    let code = query.code.as_deref().ok_or_else(invalid)?;
+
+    let metadata = discover(provider).await?;
+    let external = complete_login(
+        &metadata,
+        provider,
+        code,
+        &login.code_verifier,
+        &login.nonce,
+    )
+    .await?;
+    let user = resolve_user(&db, provider, external).await?;
+
+    match user.status {
+        UserStatus::Active => {}
+        UserStatus::Suspended => return Err(AppError::Suspended),
+        UserStatus::Locked => return Err(AppError::Locked),
+        UserStatus::Pending => return Err(AppError::Unauthorized),
+    }
+
+    let session = db
+        .create_session(
+            &user.id,
+            &format!("federated:{}", provider.id),
+            session_origin(&req),
+        )
+        .await?;
+
+    let mut res = session_response(session, true);
+    let mut cookie = Cookie::new(FEDERATED_COOKIE, "");
+    cookie.set_path(FEDERATED_COOKIE_PATH);
+    res.add_removal_cookie(&cookie).ok();
+    Ok(res)
+}
+
+async fn resolve_user(
+    db: &PostgresService,
+    provider: &FederatedProviderConfig,
+    external: ExternalIdentity,
+) -> Result<UserModel, AppError> {
+    if let Some(link) = db
+        .find_federated_identity(&provider.id, &external.subject)
+        .await?
+    {
+        db.touch_federated_identity(&link.id).await?;
+        return db.get_user_by_id(&link.user_id).await;
+    }
+
+    // Never link or create on an address the provider has not checked.
+    let email = match external.email {
+        Some(email) if external.email_verified => email,
+        _ => return Err(AppError::Forbidden),
+    };
+
+    let user_id = match db.get_user_by_email(&email).await {
+        Ok(user) => user.id,
+        Err(AppError::NotFound) if provider.auto_create => {
+            let name = external.name.as_deref().unwrap_or(&email);
+            provision_verified_user(db, name, &email, SignupChannel::Federated).await?
+        }
+        Err(AppError::NotFound) => return Err(AppError::Forbidden),
+        Err(e) => return Err(e),
+    };
+
+    db.link_federated_identity(&user_id, &provider.id, &external.subject, &email)
+        .await?;
+    db.get_user_by_id(&user_id).await
+}
@@ -0,0 +1,51 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::RLogin;
+use crate::types::error::AppError;
+use crate::utils::password::password_matches;
+use crate::utils::webutils::{session_origin, session_response};
+use actix_web::{post, web, HttpResponse};
+use entity::user::UserStatus;
+use std::sync::Arc;
+
+/// Email and password login for the web UI. Opens a session; see
+/// [`session_response`] for how it is delivered.
+///
+/// Unknown emails, accounts without a password and wrong passwords all cost
+/// one Argon2 check and fail with the same response.
+#[post("")]
+async fn login(
+    req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RLogin>,
+) -> Result<HttpResponse, AppError> {
+    let user = db.get_user_by_email(&body.email).await.ok();
+    let stored = user.as_ref().and_then(|u| u.password_hash.as_deref());
+
+    if !password_matches(&body.password, stored) {
+        return Err(AppError::Unauthorized);
+    }
+    let user = user.ok_or(AppError::Unauthorized)?;
+
+    match user.status {
+        UserStatus::Active => {}
+        UserStatus::Suspended => return Err(AppError::Suspended),
+        UserStatus::Locked => return Err(AppError::Locked),
+        UserStatus::Pending => return Err(AppError::Unauthorized),
+    }
+
+    let auth_method = if db.totp_enabled(&user.id).await? {
+        let code = body.otp.as_deref().unwrap_or_default();
+        if code.is_empty() || !db.verify_second_factor(&user.id, code).await? {
+            return Err(AppError::Unauthorized);
+        }
+        "password+otp"
+    } else {
+        "password"
+    };
+
+    let session = db
+        .create_session(&user.id, auth_method, session_origin(&req))
+        .await?;
+
+    Ok(session_response(session, body.cookie))
+}
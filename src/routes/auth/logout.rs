@@ -0,0 +1,24 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::utils::webutils::clear_session_cookies;
+use actix_web::{post, web, HttpResponse};
+use std::sync::Arc;
+
+/// Ends the current session and clears the session cookies.
+#[post("")]
+async fn logout(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let session_id = identity
+        .session_id
+        .ok_or_else(|| AppError::BadRequest("Only sessions can be logged out.".into()))?;
+
+    db.revoke_session(&identity.user_id, &session_id).await?;
+
+    let mut res = HttpResponse::NoContent().finish();
+    clear_session_cookies(&mut res);
+    Ok(res)
+}
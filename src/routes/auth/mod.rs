@@ -0,0 +1,4 @@
+pub mod federated;
+pub mod login;
+pub mod logout;
+pub mod passkey;
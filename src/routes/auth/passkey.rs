@@ -0,0 +1,89 @@
+use crate::db::passkey::CEREMONY_AUTHENTICATION;
+use crate::db::postgres_service::PostgresService;
+use crate::types::error::AppError;
+use crate::types::passkey::{PasskeyChallengeRes, RPasskeyLoginFinish, RPasskeyLoginStart};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::passkey::{from_json, parse_passkeys, to_json, webauthn};
+use crate::utils::webutils::{session_origin, session_response};
+use actix_web::{post, web, HttpResponse};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use entity::user::UserStatus;
+use std::sync::Arc;
+use webauthn_rs::prelude::{Passkey, PasskeyAuthentication, RequestChallengeResponse};
+
+/// Starts a passkey login for the account behind `email`.
+#[post("/start")]
+async fn start(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RPasskeyLoginStart>,
+) -> ApiResult<PasskeyChallengeRes<RequestChallengeResponse>> {
+    let unavailable = || AppError::BadRequest("Passkey login is not available.".into());
+
+    let user = db
+        .get_user_by_email(&body.email)
+        .await
+        .map_err(|_| unavailable())?;
+    let passkeys = parse_passkeys(&db.list_passkeys(&user.id).await?)?;
+    if passkeys.is_empty() {
+        return Err(unavailable());
+    }
+
+    let (options, state) = webauthn()
+        .start_passkey_authentication(&passkeys)
+        .map_err(|_| unavailable())?;
+    let ceremony_id = db
+        .create_webauthn_ceremony(&user.id, CEREMONY_AUTHENTICATION, to_json(&state)?)
+        .await?;
+
+    Ok(ApiResponse::Ok(PasskeyChallengeRes {
+        ceremony_id,
+        options,
+    }))
+}
+
+/// Checks the authenticator's assertion and opens a session.
+#[post("/finish")]
+async fn finish(
+    req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RPasskeyLoginFinish>,
+) -> Result<HttpResponse, AppError> {
+    let ceremony = db
+        .take_webauthn_ceremony(&body.ceremony_id, CEREMONY_AUTHENTICATION)
+        .await?;
+    let state: PasskeyAuthentication = from_json(ceremony.state)?;
+
+    let result = webauthn()
+        .finish_passkey_authentication(&body.credential, &state)
+        .map_err(|_| AppError::Unauthorized)?;
+
+    let cred_id = URL_SAFE_NO_PAD.encode(result.cred_id());
+    let stored = db
+        .list_passkeys(&ceremony.user_id)
+        .await?
+        .into_iter()
+        .find(|p| p.credential_id == cred_id)
+        .ok_or(AppError::Unauthorized)?;
+
+    let mut passkey: Passkey = from_json(stored.passkey)?;
+    let updated = match passkey.update_credential(&result) {
+        Some(true) => Some(to_json(&passkey)?),
+        _ => None,
+    };
+    db.record_passkey_use(&stored.id, updated).await?;
+
+    let user = db.get_user_by_id(&ceremony.user_id).await?;
+    match user.status {
+        UserStatus::Active => {}
+        UserStatus::Suspended => return Err(AppError::Suspended),
+        UserStatus::Locked => return Err(AppError::Locked),
+        UserStatus::Pending => return Err(AppError::Unauthorized),
+    }
+
+    let session = db
+        .create_session(&user.id, "passkey", session_origin(&req))
+        .await?;
+
+    Ok(session_response(session, body.cookie))
+}
@@ -0,0 +1,39 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::rbac::{AuthorizeRes, RAuthorize};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::policy::{is_valid_resource, RbacPolicy};
+use crate::utils::webutils::request_context;
+use actix_web::{post, web};
+use std::sync::Arc;
+
+/// Whether the caller may perform an action on a resource, judged against
+/// this request's context (source address, time, how the token was issued).
+#[post("")]
+async fn authorize(
+    req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RAuthorize>,
+) -> ApiResult<AuthorizeRes> {
+    if body.action.is_empty() || !is_valid_resource(&body.resource) {
+        return Err(AppError::Validation(
+            "Action and resource are required.".into(),
+        ));
+    }
+
+    let decision = db
+        .authorize_user(
+            &RbacPolicy,
+            &identity.user_id,
+            &body.action,
+            &body.resource,
+            &request_context(&req, &identity),
+        )
+        .await?;
+
+    Ok(ApiResponse::Ok(AuthorizeRes {
+        allowed: decision.is_allowed(),
+    }))
+}
@@ -1,16 +1,31 @@
-use crate::utils::webutils::{validate_admin_token, validate_token};
-use actix_web::web;
+use crate::types::scim::ScimError;
+use crate::utils::webutils::{
+    authenticate, require_otp, require_standing_admin, require_writer, validate_admin_token,
+    validate_scim_token,
+};
+use actix_web::{middleware::from_fn, web};
 
+pub mod acl;
+pub mod admin;
+pub mod auth;
+pub mod authorize;
 pub mod fail;
 pub mod health;
+pub mod oauth;
+pub mod oidc;
+pub mod scim;
+pub mod signup;
+pub mod teams;
 pub mod user;
 pub mod validate;
 
-// TODO:
-// Route auth still needs refinement once we add richer roles/scopes beyond simple token validation.
+// Route auth layers: `authenticate` resolves the caller, then `require_writer`
+// checks the account role for the scope. `/admin` goes through
+// `validate_admin_token` instead, which takes the static admin key or an admin
+// account's token; `require_standing_admin` limits temporarily elevated admins
+// to reading there, so they can't change who holds privileges.
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
-    let user_auth = actix_web_httpauth::middleware::HttpAuthentication::bearer(validate_token);
     let admin_auth = actix_web_httpauth::middleware::HttpAuthentication::bearer(validate_admin_token);
 
     // Anything on the /health endpoint
@@ -21,16 +36,317 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/user")
             // user/create
-            .service(web::scope("/create").service(user::create::create))
-            .wrap(admin_auth)
+            .service(
+                web::scope("/create")
+                    .service(user::create::create)
+                    .wrap(from_fn(require_standing_admin))
+                    .wrap(admin_auth.clone()),
+            )
+            // user/verify (public; the mailed code is the credential)
+            .service(
+                web::scope("/verify")
+                    .service(user::verify::resend)
+                    .service(user::verify::verify)
+                    .service(user::verify::verify_link),
+            )
+            // user/recover (public; always answers the same way)
+            .service(
+                web::scope("/recover")
+                    .service(user::recover::redeem)
+                    .service(user::recover::redeem_link)
+                    .service(user::recover::recover),
+            )
+            // user/password (public; the mailed token is the credential)
+            .service(
+                web::scope("/password")
+                    .service(user::password::request)
+                    .service(user::password::set),
+            )
+            // user/mfa
+            .service(
+                web::scope("/mfa")
+                    .service(
+                        web::scope("/totp/disable")
+                            .service(user::mfa::disable)
+                            .wrap(from_fn(require_otp)),
+                    )
+                    .service(user::mfa::enroll)
+                    .service(user::mfa::confirm)
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/api-keys
+            .service(
+                web::scope("/api-keys")
+                    .service(user::api_keys::list)
+                    .service(user::api_keys::revoke)
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/consents (applications signed in to with Ledger)
+            .service(
+                web::scope("/consents")
+                    .service(user::consents::list)
+                    .service(user::consents::revoke)
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/sessions
+            .service(
+                web::scope("/sessions")
+                    .service(user::sessions::list)
+                    .service(user::sessions::revoke_all)
+                    .service(user::sessions::revoke)
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/elevations (asking for a temporary role is sensitive)
+            .service(
+                web::scope("/elevations")
+                    .service(
+                        web::scope("/request")
+                            .service(user::elevations::request)
+                            .wrap(from_fn(require_otp)),
+                    )
+                    .service(user::elevations::list)
+                    .service(user::elevations::end)
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/passkeys (adding or removing one is sensitive)
+            .service(
+                web::scope("/passkeys")
+                    .service(
+                        web::scope("/register/start")
+                            .service(user::passkeys::register_start)
+                            .wrap(from_fn(require_otp)),
+                    )
+                    .service(user::passkeys::register_finish)
+                    .service(user::passkeys::list)
+                    .service(
+                        web::scope("/{id}")
+                            .service(user::passkeys::remove)
+                            .wrap(from_fn(require_otp)),
+                    )
+                    .wrap(from_fn(authenticate)),
+            )
+            // Sensitive routes: a one-time code is required once TOTP is enabled.
             // user/regenerate
             .service(
                 web::scope("/regenerate")
                     .service(user::regenerate::regenerate)
-                    .wrap(user_auth.clone()),
+                    .wrap(from_fn(require_otp))
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/email
+            .service(
+                web::scope("/email")
+                    .service(user::email::update)
+                    .wrap(from_fn(require_otp))
+                    .wrap(from_fn(authenticate)),
+            )
+            // user/delete
+            .service(
+                web::scope("/delete")
+                    .service(user::delete::delete_account)
+                    .wrap(from_fn(require_otp))
+                    .wrap(from_fn(authenticate)),
+            ),
+    );
+
+    // Anything on the /auth endpoint (login ceremonies are public)
+    cfg.service(
+        web::scope("/auth")
+            .service(web::scope("/login").service(auth::login::login))
+            .service(
+                web::scope("/logout")
+                    .service(auth::logout::logout)
+                    .wrap(from_fn(authenticate)),
+            )
+            .service(
+                web::scope("/passkey")
+                    .service(auth::passkey::start)
+                    .service(auth::passkey::finish),
+            )
+            .service(
+                web::scope("/federated")
+                    .service(auth::federated::providers)
+                    .service(auth::federated::start)
+                    .service(auth::federated::callback),
+            ),
+    );
+
+    // Anything on the /oauth endpoint (device grant for the CLI, token endpoint for OIDC)
+    cfg.service(
+        web::scope("/oauth")
+            .service(oauth::device::device_authorization)
+            .service(oauth::token::token)
+            .service(
+                web::scope("/device")
+                    .service(oauth::device::show)
+                    // Approving mints an API key, so it is a sensitive route.
+                    .service(
+                        web::scope("/approve")
+                            .service(oauth::device::approve)
+                            .wrap(from_fn(require_otp)),
+                    )
+                    .service(oauth::device::deny)
+                    .wrap(from_fn(authenticate)),
+            ),
+    );
+
+    // OpenID Connect provider metadata
+    cfg.service(web::scope("/.well-known").service(oidc::discovery::configuration));
+
+    // Anything on the /oidc endpoint (Ledger as an identity provider)
+    cfg.service(
+        web::scope("/oidc")
+            .service(oidc::discovery::jwks)
+            .service(oidc::userinfo::userinfo)
+            .service(
+                web::scope("/authorize")
+                    .service(oidc::authorize::authorize)
+                    .service(oidc::authorize::decide)
+                    .wrap(from_fn(authenticate)),
+            ),
+    );
+
+    // Anything on the /teams endpoint (shared workspaces)
+    cfg.service(
+        web::scope("/teams")
+            // teams/invites/accept (public; the mailed token is the credential)
+            .service(web::scope("/invites/accept").service(teams::invites::accept))
+            .service(
+                web::scope("")
+                    .service(teams::manage::create)
+                    .service(teams::manage::list)
+                    .service(teams::members::list)
+                    .service(teams::members::add)
+                    .service(teams::members::remove)
+                    .service(teams::invites::create)
+                    .service(teams::invites::list)
+                    .service(teams::invites::resend)
+                    .service(teams::invites::cancel)
+                    .service(teams::deletion::confirm)
+                    .service(teams::manage::get)
+                    .service(teams::manage::update)
+                    .service(teams::deletion::request)
+                    .wrap(from_fn(require_writer))
+                    .wrap(from_fn(authenticate)),
             ),
     );
 
+    // Public, invite-gated account creation
+    cfg.service(web::scope("/signup").service(signup::signup));
+
+    // Anything on the /admin endpoint requires admin credentials, and only
+    // reads while the admin role is a temporary elevation
+    cfg.service(
+        web::scope("/admin")
+            .service(web::scope("/audit").service(admin::audit::list))
+            .service(
+                web::scope("/invites")
+                    .service(admin::invites::create)
+                    .service(admin::invites::list)
+                    .service(admin::invites::revoke),
+            )
+            .service(
+                web::scope("/elevations")
+                    .service(
+                        web::scope("/eligibility")
+                            .service(admin::elevations::create_eligibility)
+                            .service(admin::elevations::list_eligibilities)
+                            .service(admin::elevations::delete_eligibility),
+                    )
+                    .service(admin::elevations::list)
+                    .service(admin::elevations::approve)
+                    .service(admin::elevations::deny)
+                    .service(admin::elevations::revoke),
+            )
+            .service(web::scope("/kms").service(admin::kms::rotate))
+            .service(
+                web::scope("/oauth-clients")
+                    .service(admin::oauth_clients::create)
+                    .service(admin::oauth_clients::list)
+                    .service(admin::oauth_clients::revoke),
+            )
+            .service(
+                web::scope("/plans")
+                    .service(admin::plans::create_plan)
+                    .service(admin::plans::list_plans)
+                    .service(admin::plans::update_plan)
+                    .service(admin::plans::delete_plan),
+            )
+            .service(
+                web::scope("/entitlements")
+                    .service(admin::plans::get_entitlements)
+                    .service(admin::plans::assign_plan),
+            )
+            .service(
+                web::scope("/rbac")
+                    .service(
+                        web::scope("/permissions")
+                            .service(admin::rbac::create_permission)
+                            .service(admin::rbac::list_permissions),
+                    )
+                    .service(
+                        web::scope("/roles")
+                            .service(admin::rbac::create_role)
+                            .service(admin::rbac::list_roles)
+                            .service(admin::rbac::delete_role),
+                    )
+                    .service(
+                        web::scope("/bindings")
+                            .service(admin::rbac::create_binding)
+                            .service(admin::rbac::list_bindings)
+                            .service(admin::rbac::delete_binding),
+                    ),
+            )
+            .service(
+                web::scope("/users")
+                    .service(admin::users::list)
+                    .service(admin::users::set_status)
+                    .service(admin::users::set_role),
+            )
+            .wrap(from_fn(require_standing_admin))
+            .wrap(admin_auth.clone()),
+    );
+
+    // SCIM 2.0 provisioning from the HR identity provider
+    cfg.service(
+        web::scope("/scim/v2")
+            .app_data(
+                web::JsonConfig::default()
+                    .error_handler(|err, _| ScimError::InvalidSyntax(err.to_string()).into()),
+            )
+            .service(scim::discovery::service_provider_config)
+            .service(scim::discovery::resource_types)
+            .service(
+                web::scope("/Users")
+                    .service(scim::users::list)
+                    .service(scim::users::create)
+                    .service(scim::users::get)
+                    .service(scim::users::replace)
+                    .service(scim::users::update)
+                    .service(scim::users::remove),
+            )
+            .wrap(actix_web_httpauth::middleware::HttpAuthentication::bearer(
+                validate_scim_token,
+            )),
+    );
+
+    // Per-resource sharing
+    cfg.service(
+        web::scope("/acl")
+            .service(acl::grant)
+            .service(acl::list)
+            .service(acl::revoke)
+            .wrap(from_fn(require_writer))
+            .wrap(from_fn(authenticate)),
+    );
+
+    // Policy checks for the caller's own token
+    cfg.service(
+        web::scope("/authorize")
+            .service(authorize::authorize)
+            .wrap(from_fn(authenticate)),
+    );
+
     // Anything on the /validate endpoint
     cfg.service(web::scope("/validate").service(validate::validate));
 
@@ -0,0 +1,106 @@
+use crate::config::config;
+use crate::db::device::{format_user_code, DEVICE_CODE_TTL_MINUTES};
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::oauth::{
+    DeviceAuthorizationRes, DeviceRequestRes, OAuthError, RDeviceAuthorization, RDeviceUserCode,
+};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::scope::{parse_scopes, split_scopes, API_KEY_SCOPES, DEFAULT_API_KEY_SCOPES};
+use actix_web::{get, post, web, HttpResponse};
+use std::sync::Arc;
+
+const DEFAULT_DEVICE_NAME: &str = "Ledger CLI";
+
+/// Device authorization endpoint (RFC 8628 section 3.1). Public; the CLI
+/// calls it to get a device code for polling and a user code to show.
+#[post("/device_authorization")]
+async fn device_authorization(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    form: web::Form<RDeviceAuthorization>,
+) -> Result<HttpResponse, OAuthError> {
+    let client_id = form.client_id.trim();
+    if client_id.is_empty() {
+        return Err(OAuthError::InvalidRequest("client_id is required.".into()));
+    }
+    let scopes = parse_scopes(
+        form.scope.as_deref(),
+        API_KEY_SCOPES,
+        DEFAULT_API_KEY_SCOPES,
+    )
+    .ok_or(OAuthError::InvalidScope)?;
+    let device_name = form
+        .device_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(DEFAULT_DEVICE_NAME);
+
+    let (grant, device_code) = db
+        .create_device_authorization(client_id, &scopes, device_name)
+        .await?;
+
+    let user_code = format_user_code(&grant.user_code);
+    let verification_uri = format!("{}/oauth/device", config().public_url);
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(DeviceAuthorizationRes {
+            device_code,
+            verification_uri_complete: format!(
+                "{}?user_code={}",
+                verification_uri,
+                urlencoding::encode(&user_code)
+            ),
+            verification_uri,
+            user_code,
+            expires_in: DEVICE_CODE_TTL_MINUTES * 60,
+            interval: grant.interval_secs,
+        }))
+}
+
+/// Shows what a user code would grant, so the user can check it matches
+/// their terminal before approving.
+#[get("")]
+async fn show(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    _identity: AuthenticatedUser,
+    query: web::Query<RDeviceUserCode>,
+) -> ApiResult<DeviceRequestRes> {
+    let grant = db
+        .get_pending_device_authorization(&query.user_code)
+        .await?;
+
+    Ok(ApiResponse::Ok(DeviceRequestRes {
+        user_code: format_user_code(&grant.user_code),
+        client_id: grant.client_id,
+        device_name: grant.device_name,
+        scopes: split_scopes(&grant.scopes),
+        expires_at: grant.expires_at,
+    }))
+}
+
+#[post("")]
+async fn approve(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RDeviceUserCode>,
+) -> ApiResult<()> {
+    db.decide_device_authorization(&body.user_code, &identity.user_id, true)
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
+
+#[post("/deny")]
+async fn deny(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RDeviceUserCode>,
+) -> ApiResult<()> {
+    db.decide_device_authorization(&body.user_code, &identity.user_id, false)
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
@@ -0,0 +1,139 @@
+use crate::db::{oidc::OIDC_ACCESS_TOKEN_TTL_SECS, postgres_service::PostgresService};
+use crate::types::oauth::{OAuthError, RToken, TokenRes};
+use crate::types::oidc::IdTokenClaims;
+use crate::utils::{
+    jwt::server_key,
+    oidc::{issuer, user_claims},
+    scope::split_scopes,
+};
+use actix_web::{http::header::Header, post, web, HttpRequest, HttpResponse};
+use actix_web_httpauth::headers::authorization::{Authorization, Basic};
+use chrono::Utc;
+use entity::user::UserStatus;
+use std::sync::Arc;
+
+pub const GRANT_DEVICE_CODE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+pub const GRANT_AUTHORIZATION_CODE: &str = "authorization_code";
+
+/// OAuth token endpoint. Form-encoded; dispatches on `grant_type`.
+#[post("/token")]
+async fn token(
+    req: HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    form: web::Form<RToken>,
+) -> Result<HttpResponse, OAuthError> {
+    let res = match form.grant_type.as_str() {
+        GRANT_DEVICE_CODE => device_code_grant(&db, &form).await?,
+        GRANT_AUTHORIZATION_CODE => authorization_code_grant(&db, &req, &form).await?,
+        _ => return Err(OAuthError::UnsupportedGrantType),
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(res))
+}
+
+/// RFC 8628 section 3.4. Success mints a named API key for the device.
+async fn device_code_grant(db: &PostgresService, form: &RToken) -> Result<TokenRes, OAuthError> {
+    let device_code = form
+        .device_code
+        .as_deref()
+        .ok_or_else(|| OAuthError::InvalidRequest("device_code is required.".into()))?;
+    let client_id = form
+        .client_id
+        .as_deref()
+        .ok_or_else(|| OAuthError::InvalidRequest("client_id is required.".into()))?;
+
+    let (key, access_token) = db.redeem_device_code(device_code, client_id).await?;
+
+    Ok(TokenRes {
+        access_token,
+        token_type: "Bearer".to_string(),
+        scope: key.scopes,
+        expires_in: None,
+        id_token: None,
+    })
+}
+
+/// OpenID Connect Core section 3.1.3. Returns an access token for
+/// `/oidc/userinfo` and a signed ID token.
+async fn authorization_code_grant(
+    db: &PostgresService,
+    req: &HttpRequest,
+    form: &RToken,
+) -> Result<TokenRes, OAuthError> {
+    let required = |value: &Option<String>, name: &str| {
+        value
+            .clone()
+            .ok_or_else(|| OAuthError::InvalidRequest(format!("{name} is required.")))
+    };
+    let code = required(&form.code, "code")?;
+    let redirect_uri = required(&form.redirect_uri, "redirect_uri")?;
+    let code_verifier = required(&form.code_verifier, "code_verifier")?;
+
+    let (client_id, client_secret) = client_credentials(req, form)?;
+    let client = db
+        .authenticate_oauth_client(&client_id, client_secret.as_deref())
+        .await?;
+
+    let (grant, access_token) = db
+        .redeem_authorization_code(&code, &client, &redirect_uri, &code_verifier)
+        .await?;
+
+    let user = db.get_user_by_id(&grant.user_id).await?;
+    if user.status != UserStatus::Active {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    let now = Utc::now().timestamp();
+    let id_token = server_key().sign(&IdTokenClaims {
+        iss: issuer(),
+        sub: user.id.to_string(),
+        aud: client.client_id,
+        exp: now + OIDC_ACCESS_TOKEN_TTL_SECS,
+        iat: now,
+        auth_time: grant.auth_time.timestamp(),
+        nonce: grant.nonce,
+        claims: user_claims(&user, &split_scopes(&grant.scopes)),
+    })?;
+
+    Ok(TokenRes {
+        access_token,
+        token_type: "Bearer".to_string(),
+        scope: grant.scopes,
+        expires_in: Some(OIDC_ACCESS_TOKEN_TTL_SECS),
+        id_token: Some(id_token),
+    })
+}
+
+/// Client id and secret from HTTP Basic (RFC 6749 section 2.3.1, values
+/// form-urlencoded) or from the form body. Using both is an error.
+fn client_credentials(
+    req: &HttpRequest,
+    form: &RToken,
+) -> Result<(String, Option<String>), OAuthError> {
+    match Authorization::<Basic>::parse(req) {
+        Ok(auth) => {
+            if form.client_secret.is_some() {
+                return Err(OAuthError::InvalidRequest(
+                    "Use one client authentication method.".into(),
+                ));
+            }
+            let basic = auth.into_scheme();
+            let decode = |s: &str| {
+                urlencoding::decode(s)
+                    .map(|s| s.into_owned())
+                    .map_err(|_| OAuthError::InvalidClient)
+            };
+            let secret = basic.password().map(decode).transpose()?;
+            Ok((decode(basic.user_id())?, secret))
+        }
+        Err(_) => {
+            let client_id = form
+                .client_id
+                .clone()
+                .ok_or_else(|| OAuthError::InvalidRequest("client_id is required.".into()))?;
+            Ok((client_id, form.client_secret.clone()))
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use crate::db::{oidc::CodeGrant, postgres_service::PostgresService};
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::oidc::{ConsentPromptRes, RAuthorize, RAuthorizeDecision, RedirectRes};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::{
+    oidc::{issuer, redirect_uris, PKCE_METHOD_S256},
+    scope::{parse_scopes, split_scopes, OIDC_SCOPES, SCOPE_OPENID},
+};
+use actix_web::{get, http::header::LOCATION, post, web, HttpResponse};
+use chrono::Utc;
+use entity::oauth_client::Model as ClientModel;
+use std::sync::Arc;
+
+/// Why an authorization request was refused.
+enum Refusal {
+    /// The client or redirect URI can't be trusted, so the user sees the error.
+    Direct(AppError),
+    /// An error sent back to the client (OpenID Connect Core section 3.1.2.6).
+    Redirect(String),
+}
+
+impl From<AppError> for Refusal {
+    fn from(e: AppError) -> Self {
+        Refusal::Direct(e)
+    }
+}
+
+struct CheckedRequest {
+    client: ClientModel,
+    redirect_uri: String,
+    scopes: Vec<String>,
+    code_challenge: String,
+}
+
+/// Validates an authorization request. Until the redirect URI is known to
+/// belong to the client, errors are shown to the user instead of redirected.
+async fn check_request(db: &PostgresService, req: &RAuthorize) -> Result<CheckedRequest, Refusal> {
+    let client = db
+        .get_live_oauth_client(req.client_id.as_deref().unwrap_or_default())
+        .await
+        .map_err(|e| match e {
+            AppError::NotFound => AppError::BadRequest("Unknown client.".into()),
+            e => e,
+        })?;
+
+    let redirect_uri = req
+        .redirect_uri
+        .clone()
+        .filter(|uri| redirect_uris(&client).contains(uri))
+        .ok_or_else(|| AppError::BadRequest("Unregistered redirect_uri.".into()))?;
+
+    let refuse = |error: &str| Refusal::Redirect(error_redirect(&redirect_uri, error, req));
+
+    if req.response_type.as_deref() != Some("code") {
+        return Err(refuse("unsupported_response_type"));
+    }
+
+    let scopes = parse_scopes(req.scope.as_deref(), OIDC_SCOPES, &[])
+        .filter(|scopes| scopes.iter().any(|s| s == SCOPE_OPENID))
+        .ok_or_else(|| refuse("invalid_scope"))?;
+
+    // PKCE is mandatory for every client, confidential or not.
+    let code_challenge = match (&req.code_challenge, req.code_challenge_method.as_deref()) {
+        (Some(challenge), Some(PKCE_METHOD_S256)) if challenge.len() == 43 => challenge.clone(),
+        _ => return Err(refuse("invalid_request")),
+    };
+
+    Ok(CheckedRequest {
+        client,
+        redirect_uri,
+        scopes,
+        code_challenge,
+    })
+}
+
+/// `redirect_uri` with `params`, `state` and `iss` (RFC 9207) appended.
+fn build_redirect(redirect_uri: &str, params: &[(&str, &str)], req: &RAuthorize) -> String {
+    let Ok(mut url) = url::Url::parse(redirect_uri) else {
+        return redirect_uri.to_string();
+    };
+    {
+        let mut query = url.query_pairs_mut();
+        for (key, value) in params {
+            query.append_pair(key, value);
+        }
+        if let Some(state) = &req.state {
+            query.append_pair("state", state);
+        }
+        query.append_pair("iss", &issuer());
+    }
+    url.into()
+}
+
+fn error_redirect(redirect_uri: &str, error: &str, req: &RAuthorize) -> String {
+    build_redirect(redirect_uri, &[("error", error)], req)
+}
+
+/// Mints a code for a checked request and returns where to send the browser.
+async fn issue_code(
+    db: &PostgresService,
+    identity: &AuthenticatedUser,
+    req: &RAuthorize,
+    checked: CheckedRequest,
+) -> Result<String, AppError> {
+    let auth_time = match identity.session_id {
+        Some(id) => db.get_live_session(&id).await?.created_at,
+        None => Utc::now(),
+    };
+
+    let code = db
+        .create_authorization_code(CodeGrant {
+            client_id: checked.client.id,
+            user_id: identity.user_id,
+            redirect_uri: checked.redirect_uri.clone(),
+            scopes: checked.scopes,
+            nonce: req.nonce.clone(),
+            code_challenge: checked.code_challenge,
+            auth_time,
+        })
+        .await?;
+
+    Ok(build_redirect(
+        &checked.redirect_uri,
+        &[("code", &code)],
+        req,
+    ))
+}
+
+/// Authorization endpoint (OpenID Connect Core section 3.1.2).
+///
+/// The user must already be signed in; there is no login page here, so
+/// front-ends sign the user in first and then come back. If the user has
+/// consented to these scopes before, the browser is redirected straight back
+/// with a code; otherwise the consent prompt is returned for the front-end to
+/// show, and the answer is posted to the same path.
+#[get("")]
+async fn authorize(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    query: web::Query<RAuthorize>,
+) -> Result<HttpResponse, AppError> {
+    let checked = match check_request(&db, &query).await {
+        Ok(checked) => checked,
+        Err(Refusal::Direct(e)) => return Err(e),
+        Err(Refusal::Redirect(location)) => return Ok(found(location)),
+    };
+
+    let consented = db
+        .get_consent(&identity.user_id, &checked.client.id)
+        .await?
+        .is_some_and(|c| {
+            let granted = split_scopes(&c.scopes);
+            checked.scopes.iter().all(|s| granted.contains(s))
+        });
+
+    match query.prompt.as_deref() {
+        Some("none") if !consented => Ok(found(error_redirect(
+            &checked.redirect_uri,
+            "consent_required",
+            &query,
+        ))),
+        Some("consent") => Ok(HttpResponse::Ok().json(consent_prompt(checked))),
+        _ if consented => Ok(found(issue_code(&db, &identity, &query, checked).await?)),
+        _ => Ok(HttpResponse::Ok().json(consent_prompt(checked))),
+    }
+}
+
+/// Records the user's consent decision and returns where the browser goes next.
+#[post("")]
+async fn decide(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RAuthorizeDecision>,
+) -> ApiResult<RedirectRes> {
+    let request = &body.request;
+    let checked = match check_request(&db, request).await {
+        Ok(checked) => checked,
+        Err(Refusal::Direct(e)) => return Err(e),
+        Err(Refusal::Redirect(redirect_to)) => {
+            return Ok(ApiResponse::Ok(RedirectRes { redirect_to }))
+        }
+    };
+
+    if !body.approve {
+        return Ok(ApiResponse::Ok(RedirectRes {
+            redirect_to: error_redirect(&checked.redirect_uri, "access_denied", request),
+        }));
+    }
+
+    db.grant_consent(&identity.user_id, &checked.client.id, &checked.scopes)
+        .await?;
+    let redirect_to = issue_code(&db, &identity, request, checked).await?;
+    Ok(ApiResponse::Ok(RedirectRes { redirect_to }))
+}
+
+fn found(location: String) -> HttpResponse {
+    HttpResponse::Found()
+        .insert_header((LOCATION, location))
+        .finish()
+}
+
+fn consent_prompt(checked: CheckedRequest) -> ConsentPromptRes {
+    ConsentPromptRes {
+        client_id: checked.client.client_id,
+        client_name: checked.client.name,
+        scopes: checked.scopes,
+    }
+}
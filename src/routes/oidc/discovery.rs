@@ -0,0 +1,31 @@
+use crate::utils::{jwt::server_key, oidc::issuer, scope::OIDC_SCOPES};
+use actix_web::{get, HttpResponse};
+use serde_json::json;
+
+/// OpenID Provider metadata (OpenID Connect Discovery section 4).
+#[get("/openid-configuration")]
+async fn configuration(_req: actix_web::HttpRequest) -> HttpResponse {
+    let issuer = issuer();
+    HttpResponse::Ok().json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{issuer}/oidc/authorize"),
+        "token_endpoint": format!("{issuer}/oauth/token"),
+        "userinfo_endpoint": format!("{issuer}/oidc/userinfo"),
+        "jwks_uri": format!("{issuer}/oidc/jwks"),
+        "scopes_supported": OIDC_SCOPES,
+        "response_types_supported": ["code"],
+        "grant_types_supported": ["authorization_code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["RS256"],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post", "none"],
+        "code_challenge_methods_supported": ["S256"],
+        "claims_supported": ["iss", "sub", "aud", "exp", "iat", "auth_time", "nonce", "name", "email", "email_verified"],
+        "authorization_response_iss_parameter_supported": true,
+    }))
+}
+
+/// The public half of the ID token signing key.
+#[get("/jwks")]
+async fn jwks(_req: actix_web::HttpRequest) -> HttpResponse {
+    HttpResponse::Ok().json(server_key().jwks())
+}
@@ -0,0 +1,40 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::oidc::UserInfoRes;
+use crate::utils::{oidc::user_claims, scope::split_scopes};
+use actix_web::{http::header::Header, route, web, HttpResponse};
+use actix_web_httpauth::headers::authorization::{Authorization, Bearer};
+use entity::user::UserStatus;
+use std::sync::Arc;
+
+/// Userinfo endpoint (OpenID Connect Core section 5.3). Takes the access
+/// token from the authorization code grant, not a Ledger API token.
+#[route("/userinfo", method = "GET", method = "POST")]
+async fn userinfo(
+    req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+) -> HttpResponse {
+    let Ok(auth) = Authorization::<Bearer>::parse(&req) else {
+        return invalid_token();
+    };
+    let Ok(token) = db.get_live_oidc_access_token(auth.as_ref().token()).await else {
+        return invalid_token();
+    };
+    let user = match db.get_user_by_id(&token.user_id).await {
+        Ok(user) if user.status == UserStatus::Active => user,
+        _ => return invalid_token(),
+    };
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(UserInfoRes {
+            sub: user.id.to_string(),
+            claims: user_claims(&user, &split_scopes(&token.scopes)),
+        })
+}
+
+/// RFC 6750 section 3.1.
+fn invalid_token() -> HttpResponse {
+    HttpResponse::Unauthorized()
+        .insert_header(("WWW-Authenticate", "Bearer error=\"invalid_token\""))
+        .finish()
+}
@@ -0,0 +1,53 @@
+use crate::types::scim::{
+    SCHEMA_LIST, SCHEMA_RESOURCE_TYPE, SCHEMA_SERVICE_PROVIDER_CONFIG, SCHEMA_USER,
+    SCIM_CONTENT_TYPE,
+};
+use crate::utils::pagination::MAX_PAGE_SIZE;
+use actix_web::{get, HttpResponse};
+use serde_json::json;
+
+/// What this server supports (RFC 7643 section 5), for clients that check
+/// before provisioning.
+#[get("/ServiceProviderConfig")]
+async fn service_provider_config(_req: actix_web::HttpRequest) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(SCIM_CONTENT_TYPE)
+        .json(json!({
+            "schemas": [SCHEMA_SERVICE_PROVIDER_CONFIG],
+            "patch": { "supported": true },
+            "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+            "filter": { "supported": true, "maxResults": MAX_PAGE_SIZE },
+            "changePassword": { "supported": false },
+            "sort": { "supported": false },
+            "etag": { "supported": false },
+            "authenticationSchemes": [{
+                "type": "oauthbearertoken",
+                "name": "Bearer token",
+                "description": "The token configured as SCIM_TOKEN.",
+            }],
+        }))
+}
+
+/// The resources clients may provision (RFC 7643 section 6).
+///
+/// Only users. Groups are deliberately not offered: a team always has an
+/// owner and is only deleted after its owner confirms a mailed code, and an
+/// identity provider can give neither.
+#[get("/ResourceTypes")]
+async fn resource_types(_req: actix_web::HttpRequest) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(SCIM_CONTENT_TYPE)
+        .json(json!({
+            "schemas": [SCHEMA_LIST],
+            "totalResults": 1,
+            "startIndex": 1,
+            "itemsPerPage": 1,
+            "Resources": [{
+                "schemas": [SCHEMA_RESOURCE_TYPE],
+                "id": "User",
+                "name": "User",
+                "endpoint": "/Users",
+                "schema": SCHEMA_USER,
+            }],
+        }))
+}
@@ -0,0 +1,198 @@
+use crate::config::config;
+use crate::db::postgres_service::PostgresService;
+use crate::types::scim::{
+    RScimList, RScimPatch, ScimError, ScimListResponse, ScimUser, SCHEMA_LIST, SCIM_CONTENT_TYPE,
+};
+use crate::utils::mail::{mail_email_change_notice, mail_email_verification};
+use crate::utils::pagination::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::utils::registration::{check_registration, SignupChannel};
+use crate::utils::scim::{
+    is_active, parse_filter, patch_changes, resource_email, resource_name, to_scim_user,
+    ScimUserChanges,
+};
+use crate::utils::user::onboard_user;
+use actix_web::{delete, get, http::StatusCode, patch, post, put, web, HttpResponse};
+use entity::user::{Model as UserModel, UserRole};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Recorded as the status reason when the identity provider turns an account off.
+const DEACTIVATION_REASON: &str = "Deactivated by SCIM provisioning";
+const REACTIVATION_REASON: &str = "Reactivated by SCIM provisioning";
+
+fn scim_json<T: Serialize>(status: StatusCode, body: &T) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type(SCIM_CONTENT_TYPE)
+        .json(body)
+}
+
+/// Unknown or malformed ids are both just missing resources to SCIM.
+fn user_id(raw: &str) -> Result<Uuid, ScimError> {
+    Uuid::parse_str(raw).map_err(|_| ScimError::NotFound)
+}
+
+/// Lists accounts, optionally filtered, e.g. `userName eq "a@corp.com"`.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RScimList>,
+) -> Result<HttpResponse, ScimError> {
+    let filters = match query.filter.as_deref().map(str::trim) {
+        Some(raw) if !raw.is_empty() => parse_filter(raw)?,
+        _ => vec![],
+    };
+    let start_index = query.start_index.unwrap_or(1).max(1);
+    let count = query.count.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    let (users, total) = db.list_scim_users(&filters, start_index, count).await?;
+    let resources: Vec<ScimUser> = users.iter().map(to_scim_user).collect();
+
+    Ok(scim_json(
+        StatusCode::OK,
+        &ScimListResponse {
+            schemas: vec![SCHEMA_LIST.to_string()],
+            total_results: total,
+            start_index,
+            items_per_page: resources.len() as u64,
+            resources,
+        },
+    ))
+}
+
+#[get("/{id}")]
+async fn get(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ScimError> {
+    let user = db.get_user_by_id(&user_id(&path)?).await?;
+    Ok(scim_json(StatusCode::OK, &to_scim_user(&user)))
+}
+
+/// Provisions an account the way an admin would create one. A resource sent
+/// with `active: false` is created and immediately deactivated.
+#[post("")]
+async fn create(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<ScimUser>,
+) -> Result<HttpResponse, ScimError> {
+    let email = resource_email(&body);
+    if db.user_exists_by_email(&email).await? {
+        return Err(ScimError::Uniqueness);
+    }
+
+    let user_id = onboard_user(
+        &db,
+        &resource_name(&body),
+        &email,
+        UserRole::Member,
+        SignupChannel::Admin,
+    )
+    .await?;
+    let user = if body.active {
+        db.get_user_by_id(&user_id).await?
+    } else {
+        db.deactivate_user(&user_id, DEACTIVATION_REASON).await?
+    };
+    info!("SCIM provisioned user {}", user.id);
+
+    let resource = to_scim_user(&user);
+    let mut res = scim_json(StatusCode::CREATED, &resource);
+    if let Some(meta) = &resource.meta {
+        if let Ok(location) = meta.location.parse() {
+            res.headers_mut()
+                .insert(actix_web::http::header::LOCATION, location);
+        }
+    }
+    Ok(res)
+}
+
+/// Replaces the account's name, email and active flag with the resource's.
+#[put("/{id}")]
+async fn replace(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<String>,
+    body: web::Json<ScimUser>,
+) -> Result<HttpResponse, ScimError> {
+    let changes = ScimUserChanges {
+        name: Some(resource_name(&body)),
+        email: Some(resource_email(&body)),
+        active: Some(body.active),
+    };
+    let user = apply_changes(&db, user_id(&path)?, changes).await?;
+    Ok(scim_json(StatusCode::OK, &to_scim_user(&user)))
+}
+
+/// Partial update. `active: false` is how identity providers offboard people.
+#[patch("/{id}")]
+async fn update(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<String>,
+    body: web::Json<RScimPatch>,
+) -> Result<HttpResponse, ScimError> {
+    let user_id = user_id(&path)?;
+    let changes = patch_changes(&body.operations)?;
+    let user = apply_changes(&db, user_id, changes).await?;
+    Ok(scim_json(StatusCode::OK, &to_scim_user(&user)))
+}
+
+/// Deletes the account outright. Most providers deactivate instead.
+#[delete("/{id}")]
+async fn remove(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ScimError> {
+    let user_id = user_id(&path)?;
+    db.delete_user(&user_id).await?;
+    info!("SCIM deleted user {}", user_id);
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Applies a replace or patch. Reactivation comes first and deactivation
+/// last, so a request that does both with other changes ends up consistent.
+async fn apply_changes(
+    db: &PostgresService,
+    user_id: Uuid,
+    changes: ScimUserChanges,
+) -> Result<UserModel, ScimError> {
+    let mut user = db.get_user_by_id(&user_id).await?;
+
+    if changes.active == Some(true) && !is_active(&user) {
+        user = db.reactivate_user(&user_id, REACTIVATION_REASON).await?;
+        info!("SCIM reactivated user {}", user_id);
+    }
+
+    if let Some(name) = changes.name.filter(|n| *n != user.name) {
+        db.update_user_name(user_id, name).await?;
+    }
+
+    // Same flow as a user changing their own address.
+    if let Some(email) = changes
+        .email
+        .filter(|e| !e.eq_ignore_ascii_case(&user.email))
+        .filter(|e| user.pending_email.as_deref() != Some(e.as_str()))
+    {
+        check_registration(&config().registration, &email, SignupChannel::EmailChange)?;
+        if db.user_exists_by_email(&email).await? {
+            return Err(ScimError::Uniqueness);
+        }
+        let current = db.request_email_change(&user_id, &email).await?;
+        let code = db.create_email_verification(&user_id).await?;
+        mail_email_verification(&email, &code).await.ok();
+        mail_email_change_notice(&current, &email).await.ok();
+    }
+
+    if changes.active == Some(false) && is_active(&user) {
+        db.deactivate_user(&user_id, DEACTIVATION_REASON).await?;
+        info!("SCIM deactivated user {}", user_id);
+    }
+
+    Ok(db.get_user_by_id(&user_id).await?)
+}
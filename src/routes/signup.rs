@@ -0,0 +1,45 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::invite::RSignup;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::registration::SignupChannel;
+use crate::utils::user::onboard_user;
+use actix_web::{post, web};
+use entity::user::UserRole;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct Response {
+    pub message: String,
+}
+
+/// Self-service signup. Whether an invite code is needed depends on the
+/// configured registration mode.
+#[post("")]
+async fn signup(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RSignup>,
+) -> ApiResult<Response> {
+    let invite = match &body.code {
+        Some(code) => Some(db.claim_signup_invite(code).await?),
+        None => None,
+    };
+
+    let (role, channel) = match &invite {
+        Some(invite) => (invite.default_role, SignupChannel::Invite),
+        None => (UserRole::Member, SignupChannel::SelfService),
+    };
+
+    if let Err(e) = onboard_user(&db, &body.name, &body.email, role, channel).await {
+        if let Some(invite) = &invite {
+            db.release_signup_invite(&invite.id).await.ok();
+        }
+        return Err(e);
+    }
+
+    Ok(ApiResponse::Created(Response {
+        message: "Account created; token emailed. It activates once the email is verified."
+            .to_string(),
+    }))
+}
@@ -0,0 +1,58 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::team::{RTeamDeletionConfirm, TeamDeletionRes};
+use crate::utils::mail::mail_team_deletion_code;
+use crate::utils::team::owner_membership;
+use actix_web::{delete, post, web};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Starts deleting a team. Nothing is deleted yet: a confirmation code is
+/// mailed to the owner making the request. Owners only.
+#[delete("/{id}")]
+async fn request(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<TeamDeletionRes> {
+    let team_id = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+
+    let team = db.get_team(&team_id).await?;
+    let owner = db.get_user_by_id(&identity.user_id).await?;
+    let (request, code) = db.create_team_deletion(&team_id, &identity.user_id).await?;
+    mail_team_deletion_code(&owner.email, &team.name, &code)
+        .await
+        .ok();
+    info!(
+        "User {} requested deletion of team {}",
+        identity.user_id, team_id
+    );
+
+    Ok(ApiResponse::Ok(TeamDeletionRes {
+        message: "A confirmation code has been mailed to you.".to_string(),
+        expires_at: request.expires_at,
+    }))
+}
+
+/// Deletes the team with the mailed code, revoking its invites.
+#[post("/{id}/delete/confirm")]
+async fn confirm(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<RTeamDeletionConfirm>,
+) -> ApiResult<()> {
+    let team_id = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+
+    db.confirm_team_deletion(&team_id, &identity.user_id, body.code.trim())
+        .await?;
+    info!("User {} deleted team {}", identity.user_id, team_id);
+
+    Ok(ApiResponse::NoContent)
+}
@@ -0,0 +1,165 @@
+use crate::config::config;
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::team::{
+    RTeamInviteAccept, RTeamInviteCreate, TeamInviteAcceptRes, TeamInviteRes,
+};
+use crate::utils::mail::mail_team_invite;
+use crate::utils::registration::{check_registration, email_domain, SignupChannel};
+use crate::utils::team::owner_membership;
+use crate::utils::user::provision_verified_user;
+use actix_web::{delete, get, post, web};
+use entity::team_membership::TeamRole;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Invites someone by email. Owners only.
+#[post("/{id}/invites")]
+async fn create(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<RTeamInviteCreate>,
+) -> ApiResult<TeamInviteRes> {
+    let team_id = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+    if email_domain(&body.email).is_none() {
+        return Err(AppError::Validation(
+            "A valid email address is required.".into(),
+        ));
+    }
+
+    if let Ok(user) = db.get_user_by_email(body.email.trim()).await {
+        if db.get_team_membership(&team_id, &user.id).await?.is_some() {
+            return Err(AppError::Conflict("Already a member of this team.".into()));
+        }
+    }
+
+    let team = db.get_team(&team_id).await?;
+    let (invite, token) = db
+        .create_team_invite(
+            &team_id,
+            &body.email,
+            body.role.unwrap_or(TeamRole::Member),
+            &identity.user_id,
+        )
+        .await?;
+    mail_team_invite(&invite.email, &team.name, &token)
+        .await
+        .ok();
+    info!(
+        "User {} invited {} to team {}",
+        identity.user_id, invite.email, team_id
+    );
+
+    Ok(ApiResponse::Created(TeamInviteRes::from(invite)))
+}
+
+/// Invites nobody has accepted or cancelled yet. Owners only.
+#[get("/{id}/invites")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<Vec<TeamInviteRes>> {
+    let team_id = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+    let invites = db.list_team_invites(&team_id).await?;
+    Ok(ApiResponse::Ok(
+        invites.into_iter().map(TeamInviteRes::from).collect(),
+    ))
+}
+
+/// Mails a fresh token with a new expiry. The old token stops working.
+#[post("/{id}/invites/{invite_id}/resend")]
+async fn resend(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> ApiResult<TeamInviteRes> {
+    let (team_id, invite_id) = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+
+    let team = db.get_team(&team_id).await?;
+    let (invite, token) = db.resend_team_invite(&team_id, &invite_id).await?;
+    mail_team_invite(&invite.email, &team.name, &token)
+        .await
+        .ok();
+
+    Ok(ApiResponse::Ok(TeamInviteRes::from(invite)))
+}
+
+#[delete("/{id}/invites/{invite_id}")]
+async fn cancel(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> ApiResult<()> {
+    let (team_id, invite_id) = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+    db.cancel_team_invite(&team_id, &invite_id).await?;
+    Ok(ApiResponse::NoContent)
+}
+
+/// Joins the team an invite is for, creating the account if the address
+/// has none. Public: the mailed token proves ownership of the address.
+#[post("")]
+async fn accept(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RTeamInviteAccept>,
+) -> ApiResult<TeamInviteAcceptRes> {
+    let invite = db.get_live_team_invite(&body.token).await?;
+
+    let existing = match db.get_user_by_email(&invite.email).await {
+        Ok(user) => Some(user),
+        Err(AppError::NotFound) => None,
+        Err(e) => return Err(e),
+    };
+    // Checked before the invite is spent, so a refusal leaves it usable.
+    if existing.is_none() {
+        check_registration(&config().registration, &invite.email, SignupChannel::Invite)?;
+    }
+
+    db.mark_team_invite_accepted(&invite).await?;
+
+    let (user_id, account_created) = match existing {
+        Some(user) => (user.id, false),
+        None => {
+            let name = body
+                .name
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .unwrap_or(&invite.email);
+            let user_id =
+                provision_verified_user(&db, name, &invite.email, SignupChannel::Invite).await?;
+            (user_id, true)
+        }
+    };
+
+    match db
+        .add_team_member(&invite.team_id, &user_id, invite.role)
+        .await
+    {
+        Ok(_) | Err(AppError::AlreadyExists) => {}
+        Err(e) => return Err(e),
+    }
+    info!(
+        "User {} joined team {} through invite {}",
+        user_id, invite.team_id, invite.id
+    );
+
+    Ok(ApiResponse::Ok(TeamInviteAcceptRes {
+        team_id: invite.team_id,
+        user_id,
+        account_created,
+    }))
+}
@@ -0,0 +1,67 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::team::{RTeamCreate, RTeamUpdate, TeamRes};
+use crate::utils::team::{membership, owner_membership, team_name};
+use actix_web::{get, post, put, web};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Creates a team with the caller as its owner.
+#[post("")]
+async fn create(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RTeamCreate>,
+) -> ApiResult<TeamRes> {
+    let team = db
+        .create_team(&identity.user_id, &team_name(&body.name)?)
+        .await?;
+    let membership = membership(&db, &team.id, &identity.user_id).await?;
+    info!("User {} created team {}", identity.user_id, team.id);
+
+    Ok(ApiResponse::Created(TeamRes::from((membership, team))))
+}
+
+/// Teams the caller belongs to.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<Vec<TeamRes>> {
+    let teams = db.list_user_teams(&identity.user_id).await?;
+    Ok(ApiResponse::Ok(
+        teams.into_iter().map(TeamRes::from).collect(),
+    ))
+}
+
+#[get("/{id}")]
+async fn get(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<TeamRes> {
+    let team_id = path.into_inner();
+    let membership = membership(&db, &team_id, &identity.user_id).await?;
+    let team = db.get_team(&team_id).await?;
+    Ok(ApiResponse::Ok(TeamRes::from((membership, team))))
+}
+
+/// Renames a team. Owners only.
+#[put("/{id}")]
+async fn update(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<RTeamUpdate>,
+) -> ApiResult<TeamRes> {
+    let team_id = path.into_inner();
+    let membership = owner_membership(&db, &team_id, &identity.user_id).await?;
+    let team = db.rename_team(&team_id, &team_name(&body.name)?).await?;
+    Ok(ApiResponse::Ok(TeamRes::from((membership, team))))
+}
@@ -0,0 +1,74 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::team::{RTeamMemberAdd, TeamMemberRes};
+use crate::utils::team::{membership, owner_membership};
+use actix_web::{delete, get, post, web};
+use entity::team_membership::TeamRole;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+#[get("/{id}/members")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<Vec<TeamMemberRes>> {
+    let team_id = path.into_inner();
+    membership(&db, &team_id, &identity.user_id).await?;
+    let members = db.list_team_members(&team_id).await?;
+    Ok(ApiResponse::Ok(
+        members.into_iter().map(TeamMemberRes::from).collect(),
+    ))
+}
+
+/// Adds an existing account to the team. Owners only.
+#[post("/{id}/members")]
+async fn add(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<RTeamMemberAdd>,
+) -> ApiResult<TeamMemberRes> {
+    let team_id = path.into_inner();
+    owner_membership(&db, &team_id, &identity.user_id).await?;
+
+    let user = db.get_user_by_id(&body.user_id).await?;
+    let membership = db
+        .add_team_member(&team_id, &user.id, body.role.unwrap_or(TeamRole::Member))
+        .await?;
+    info!(
+        "User {} added {} to team {} as {:?}",
+        identity.user_id, user.id, team_id, membership.role
+    );
+
+    Ok(ApiResponse::Created(TeamMemberRes::from((
+        membership, user,
+    ))))
+}
+
+/// Removes a member. Owners can remove anyone; members can only leave.
+#[delete("/{id}/members/{user_id}")]
+async fn remove(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> ApiResult<()> {
+    let (team_id, user_id) = path.into_inner();
+    let caller = membership(&db, &team_id, &identity.user_id).await?;
+    if caller.role != TeamRole::Owner && user_id != identity.user_id {
+        return Err(AppError::Forbidden);
+    }
+
+    db.remove_team_member(&team_id, &user_id).await?;
+    info!(
+        "User {} removed {} from team {}",
+        identity.user_id, user_id, team_id
+    );
+    Ok(ApiResponse::NoContent)
+}
@@ -0,0 +1,4 @@
+pub mod deletion;
+pub mod invites;
+pub mod manage;
+pub mod members;
@@ -0,0 +1,31 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::{ApiKeyRes, AuthenticatedUser};
+use crate::types::response::{ApiResponse, ApiResult};
+use actix_web::{delete, get, web};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Lists the caller's named API keys (e.g. one per CLI install).
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<Vec<ApiKeyRes>> {
+    let keys = db.list_api_keys(&identity.user_id).await?;
+    Ok(ApiResponse::Ok(
+        keys.into_iter().map(ApiKeyRes::from).collect(),
+    ))
+}
+
+#[delete("/{id}")]
+async fn revoke(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.revoke_api_key(&identity.user_id, &path.into_inner())
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
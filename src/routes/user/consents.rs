@@ -0,0 +1,48 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::oidc::ConsentRes;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::scope::split_scopes;
+use actix_web::{delete, get, web};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Lists the applications the caller has shared their identity with.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<Vec<ConsentRes>> {
+    let consents = db.list_consents(&identity.user_id).await?;
+    Ok(ApiResponse::Ok(
+        consents
+            .into_iter()
+            .filter_map(|(consent, client)| {
+                let client = client?;
+                Some(ConsentRes {
+                    id: consent.id,
+                    client_id: client.client_id,
+                    client_name: client.name,
+                    scopes: split_scopes(&consent.scopes),
+                    created_at: consent.created_at,
+                    updated_at: consent.updated_at,
+                })
+            })
+            .collect(),
+    ))
+}
+
+/// Withdraws consent; the application's access tokens stop working and the
+/// next login asks again.
+#[delete("/{id}")]
+async fn revoke(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.revoke_consent(&identity.user_id, &path.into_inner())
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
@@ -1,11 +1,10 @@
 use crate::db::postgres_service::PostgresService;
-use crate::types::error::AppError;
 use crate::types::response::{ApiResponse, ApiResult};
-use crate::types::token::TokenType;
-use crate::types::user::{DBUserCreate, RUserCreate};
-use crate::utils::mail::mail_welcome;
-use crate::utils::token::{construct_token, encrypt, new_token};
+use crate::types::user::RUserCreate;
+use crate::utils::registration::SignupChannel;
+use crate::utils::user::onboard_user;
 use actix_web::{post, web};
+use entity::user::UserRole;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -25,31 +24,18 @@ async fn create(
     // Cors.
     // Rate limiting. (governor)
     // Authentication is handled by middleware
-    let token = new_token(TokenType::User);
-
-    let encrypted_token = match encrypt(&token) {
-        Ok(token) => token,
-        Err(_) => {
-            return Err(AppError::Internal(
-                "There was an issue while encrypting the user's token.".to_string(),
-            ))
-        }
-    };
-
-    let user_id = db
-        .create_user(DBUserCreate {
-            name: body.name.clone(),
-            email: body.email.clone(),
-            auth_hash: encrypted_token,
-        })
-        .await?;
-
-    let access_token = construct_token(&user_id, &token);
-
-    mail_welcome(&body.email, &access_token).await.ok();
+    onboard_user(
+        &db,
+        &body.name,
+        &body.email,
+        UserRole::Member,
+        SignupChannel::Admin,
+    )
+    .await?;
 
     let body = Response {
-        message: "User created; token emailed.".to_string(),
+        message: "User created; token emailed. It activates once the email is verified."
+            .to_string(),
     };
 
     Ok(ApiResponse::Created(body))
@@ -0,0 +1,19 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::response::{ApiResponse, ApiResult};
+use actix_web::{delete, web};
+use std::sync::Arc;
+
+/// Permanently deletes the caller's account.
+#[delete("")]
+async fn delete_account(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<()> {
+    let user_id = identity.user_id;
+
+    db.delete_user(&user_id).await?;
+
+    Ok(ApiResponse::NoContent)
+}
@@ -0,0 +1,65 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::elevation::{ElevationRes, RElevationList, RElevationRequest};
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use actix_web::{delete, get, post, web};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Asks for a temporary role. Active right away, or pending until another
+/// admin approves, depending on how the caller is eligible.
+#[post("")]
+async fn request(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RElevationRequest>,
+) -> ApiResult<ElevationRes> {
+    let body = body.into_inner();
+    let reason = body.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::Validation("A reason is required.".into()));
+    }
+
+    let elevation = db
+        .request_elevation(&identity.user_id, body.role, reason, body.duration_minutes)
+        .await?;
+    Ok(ApiResponse::Created(ElevationRes::from(elevation)))
+}
+
+/// The caller's elevation requests and grants, newest first.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<Vec<ElevationRes>> {
+    let elevations = db
+        .list_elevations(&RElevationList {
+            user_id: Some(identity.user_id),
+            ..Default::default()
+        })
+        .await?;
+    Ok(ApiResponse::Ok(
+        elevations.into_iter().map(ElevationRes::from).collect(),
+    ))
+}
+
+/// Withdraws a pending request or gives up an active elevation early.
+#[delete("/{id}")]
+async fn end(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<ElevationRes> {
+    let elevation = db
+        .revoke_elevation(
+            &path.into_inner(),
+            Some(identity.user_id),
+            Some(identity.user_id),
+        )
+        .await?;
+    Ok(ApiResponse::Ok(ElevationRes::from(elevation)))
+}
@@ -0,0 +1,43 @@
+use crate::config::config;
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::user::RUserEmailUpdate;
+use crate::utils::mail::{mail_email_change_notice, mail_email_verification};
+use crate::utils::registration::{check_registration, SignupChannel};
+use actix_web::{put, web};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct Response {
+    pub message: String,
+}
+
+/// Starts moving the account to a new email. The current address stays in
+/// place until the new one is verified, and is told about the change.
+#[put("")]
+async fn update(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RUserEmailUpdate>,
+) -> ApiResult<Response> {
+    let user_id = identity.user_id;
+
+    check_registration(
+        &config().registration,
+        &body.email,
+        SignupChannel::EmailChange,
+    )?;
+
+    let current = db.request_email_change(&user_id, &body.email).await?;
+    let code = db.create_email_verification(&user_id).await?;
+    mail_email_verification(&body.email, &code).await.ok();
+    mail_email_change_notice(&current, &body.email).await.ok();
+
+    Ok(ApiResponse::Ok(Response {
+        message: "Verify the new address to finish the change; the old one stays until then."
+            .to_string(),
+    }))
+}
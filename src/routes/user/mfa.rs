@@ -0,0 +1,54 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::user::{RTotpConfirm, TotpConfirmRes, TotpEnrollRes};
+use crate::utils::totp::provisioning_uri;
+use actix_web::{post, web};
+use std::sync::Arc;
+
+/// Starts TOTP enrollment. Scan `otpauth_uri`, then confirm with a code.
+#[post("/totp")]
+async fn enroll(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<TotpEnrollRes> {
+    let user_id = identity.user_id;
+
+    let user = db.get_user_by_id(&user_id).await?;
+    let secret = db.begin_totp_enrollment(&user_id).await?;
+
+    Ok(ApiResponse::Ok(TotpEnrollRes {
+        otpauth_uri: provisioning_uri("Ledger", &user.email, &secret),
+        secret,
+    }))
+}
+
+/// Turns TOTP on once the authenticator produces a valid code.
+#[post("/totp/confirm")]
+async fn confirm(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RTotpConfirm>,
+) -> ApiResult<TotpConfirmRes> {
+    let user_id = identity.user_id;
+
+    let recovery_codes = db.confirm_totp(&user_id, &body.code).await?;
+
+    Ok(ApiResponse::Ok(TotpConfirmRes { recovery_codes }))
+}
+
+/// Turns TOTP off. Guarded by the one-time code middleware.
+#[post("")]
+async fn disable(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<()> {
+    let user_id = identity.user_id;
+
+    db.disable_totp(&user_id).await?;
+
+    Ok(ApiResponse::NoContent)
+}
@@ -1,2 +1,13 @@
+pub mod api_keys;
+pub mod consents;
 pub mod create;
+pub mod delete;
+pub mod elevations;
+pub mod email;
+pub mod mfa;
+pub mod passkeys;
+pub mod password;
+pub mod recover;
 pub mod regenerate;
+pub mod sessions;
+pub mod verify;
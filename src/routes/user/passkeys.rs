@@ -0,0 +1,102 @@
+use crate::db::passkey::CEREMONY_REGISTRATION;
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::AuthenticatedUser;
+use crate::types::error::AppError;
+use crate::types::passkey::{PasskeyChallengeRes, PasskeyRes, RPasskeyRegisterFinish};
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::passkey::{from_json, parse_passkeys, to_json, webauthn};
+use actix_web::{delete, get, post, web};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use std::sync::Arc;
+use uuid::Uuid;
+use webauthn_rs::prelude::{CreationChallengeResponse, PasskeyRegistration};
+
+/// Starts registering a new passkey on the caller's account.
+#[post("")]
+async fn register_start(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<PasskeyChallengeRes<CreationChallengeResponse>> {
+    let user = db.get_user_by_id(&identity.user_id).await?;
+    let exclude = parse_passkeys(&db.list_passkeys(&user.id).await?)?
+        .iter()
+        .map(|p| p.cred_id().clone())
+        .collect();
+
+    let (options, state) = webauthn()
+        .start_passkey_registration(user.id, &user.email, &user.name, Some(exclude))
+        .map_err(|_| AppError::Internal("Could not start passkey registration.".into()))?;
+    let ceremony_id = db
+        .create_webauthn_ceremony(&user.id, CEREMONY_REGISTRATION, to_json(&state)?)
+        .await?;
+
+    Ok(ApiResponse::Ok(PasskeyChallengeRes {
+        ceremony_id,
+        options,
+    }))
+}
+
+/// Stores the credential the authenticator created.
+#[post("/register/finish")]
+async fn register_finish(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    body: web::Json<RPasskeyRegisterFinish>,
+) -> ApiResult<PasskeyRes> {
+    let ceremony = db
+        .take_webauthn_ceremony(&body.ceremony_id, CEREMONY_REGISTRATION)
+        .await?;
+    if ceremony.user_id != identity.user_id {
+        return Err(AppError::BadRequest(
+            "Unknown or expired passkey challenge.".into(),
+        ));
+    }
+    let state: PasskeyRegistration = from_json(ceremony.state)?;
+
+    let passkey = webauthn()
+        .finish_passkey_registration(&body.credential, &state)
+        .map_err(|_| AppError::BadRequest("Passkey registration failed.".into()))?;
+
+    let name = body
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or("Passkey");
+    let stored = db
+        .add_passkey(
+            &identity.user_id,
+            name,
+            &URL_SAFE_NO_PAD.encode(passkey.cred_id()),
+            to_json(&passkey)?,
+        )
+        .await?;
+
+    Ok(ApiResponse::Created(stored.into()))
+}
+
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<Vec<PasskeyRes>> {
+    let passkeys = db.list_passkeys(&identity.user_id).await?;
+    Ok(ApiResponse::Ok(
+        passkeys.into_iter().map(PasskeyRes::from).collect(),
+    ))
+}
+
+#[delete("")]
+async fn remove(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> ApiResult<()> {
+    db.delete_passkey(&identity.user_id, &path.into_inner())
+        .await?;
+    Ok(ApiResponse::NoContent)
+}
@@ -0,0 +1,61 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::user::{RPasswordRequest, RPasswordSet};
+use crate::utils::mail::mail_password_token;
+use actix_web::{post, web};
+use entity::user::UserStatus;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Serialize, Deserialize)]
+pub struct Response {
+    pub message: String,
+}
+
+/// Mails a set-password token. Works the same for first-time setup and resets.
+///
+/// Like recovery, the lookup runs in the background and the answer never
+/// changes, so the endpoint doesn't reveal which emails have accounts.
+#[post("/request")]
+async fn request(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RPasswordRequest>,
+) -> ApiResult<Response> {
+    let db = Arc::clone(&db);
+    let email = body.into_inner().email;
+
+    tokio::spawn(async move {
+        let user = match db.get_user_by_email(&email).await {
+            Ok(user) if user.status == UserStatus::Active => user,
+            _ => return,
+        };
+        match db.create_password_token(&user.id).await {
+            Ok(token) => {
+                mail_password_token(&user.email, &token).await.ok();
+            }
+            Err(e) => warn!("Failed to issue password token for {}: {}", user.id, e),
+        }
+    });
+
+    Ok(ApiResponse::Ok(Response {
+        message: "If an account exists for that email, a set-password token has been sent."
+            .to_string(),
+    }))
+}
+
+/// Sets the password with the mailed token.
+#[post("")]
+async fn set(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RPasswordSet>,
+) -> ApiResult<Response> {
+    db.set_password_with_token(&body.token, &body.password)
+        .await?;
+
+    Ok(ApiResponse::Ok(Response {
+        message: "Password set; you can now log in with it.".to_string(),
+    }))
+}
@@ -0,0 +1,109 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::user::{RUserRecover, RUserRecoverRedeem, UserRecoverLinkRes, UserRecoverRes};
+use crate::utils::{mail::mail_recovery_link, token::construct_token, webutils::OTP_HEADER};
+use actix_web::{get, post, web};
+use entity::user::UserStatus;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Serialize, Deserialize)]
+pub struct Response {
+    pub message: String,
+}
+
+/// Starts lost-token recovery.
+///
+/// The lookup and mail happen in the background and the answer never changes,
+/// so neither the body nor the response time reveals whether the account exists.
+#[post("")]
+async fn recover(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RUserRecover>,
+) -> ApiResult<Response> {
+    let db = Arc::clone(&db);
+    let email = body.into_inner().email;
+
+    tokio::spawn(async move {
+        let user = match db.get_user_by_email(&email).await {
+            Ok(user) if user.status == UserStatus::Active => user,
+            _ => return,
+        };
+        match db.create_recovery_token(&user.id).await {
+            Ok(token) => {
+                mail_recovery_link(&user.email, &token).await.ok();
+            }
+            Err(e) => warn!("Failed to issue recovery token for {}: {}", user.id, e),
+        }
+    });
+
+    Ok(ApiResponse::Ok(Response {
+        message: "If an account exists for that email, a recovery link has been sent."
+            .to_string(),
+    }))
+}
+
+async fn redeem_token(
+    db: &PostgresService,
+    token: &str,
+    otp: Option<&str>,
+) -> ApiResult<UserRecoverRes> {
+    let record = db.find_recovery_token(token).await?;
+    let user_id = record.user_id;
+
+    // Checked before spending, so a blocked account doesn't burn the link.
+    match db.get_user_by_id(&user_id).await?.status {
+        UserStatus::Active => {}
+        UserStatus::Suspended => return Err(AppError::Suspended),
+        UserStatus::Locked => return Err(AppError::Locked),
+        UserStatus::Pending => return Err(AppError::Unauthorized),
+    }
+
+    // An inbox alone must not get past a second factor the user set up.
+    if db.totp_enabled(&user_id).await? {
+        let code = otp.unwrap_or_default();
+        if code.is_empty() || !db.verify_second_factor(&user_id, code).await? {
+            return Err(AppError::Unauthorized);
+        }
+    }
+
+    db.consume_recovery_token(&record.id).await?;
+    let new_token = db.regenerate_user_token(&user_id).await?;
+
+    Ok(ApiResponse::Ok(UserRecoverRes {
+        message: "Access recovered; your previous token no longer works.".to_string(),
+        token: construct_token(&user_id, &new_token),
+    }))
+}
+
+/// Spends a recovery token and returns a fresh access token. Users with
+/// TOTP also send a one-time or recovery code in [`OTP_HEADER`].
+#[post("/redeem")]
+async fn redeem(
+    req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RUserRecoverRedeem>,
+) -> ApiResult<UserRecoverRes> {
+    let otp = req.headers().get(OTP_HEADER).and_then(|v| v.to_str().ok());
+    redeem_token(&db, &body.token, otp).await
+}
+
+/// Where the link in the recovery mail lands. It only checks the link: mail
+/// scanners and prefetchers open links too, so spending it takes a [`redeem`].
+#[get("/redeem")]
+async fn redeem_link(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RUserRecoverRedeem>,
+) -> ApiResult<UserRecoverLinkRes> {
+    let record = db.find_recovery_token(&query.token).await?;
+
+    Ok(ApiResponse::Ok(UserRecoverLinkRes {
+        message: "This recovery link is valid. Send its token to POST /user/recover/redeem to get a new access token."
+            .to_string(),
+        expires_at: record.expires_at,
+    }))
+}
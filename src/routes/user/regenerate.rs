@@ -1,17 +1,13 @@
 use std::sync::Arc;
 
 use actix_web::{post, web};
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
-use crate::types::error::AppError;
+use crate::types::auth::AuthenticatedUser;
 use crate::types::response::{ApiResponse, ApiResult};
 use crate::{
     db::postgres_service::PostgresService,
     types::mail::SendEmail,
-    utils::{
-        mail::send_email,
-        token::{construct_token, extract_token_parts},
-    },
+    utils::{mail::send_email, token::construct_token},
 };
 use serde::{Deserialize, Serialize};
 
@@ -24,12 +20,9 @@ pub struct Response {
 async fn regenerate(
     _req: actix_web::HttpRequest,
     db: web::Data<Arc<PostgresService>>,
-    auth: BearerAuth,
+    identity: AuthenticatedUser,
 ) -> ApiResult<Response> {
-    let user_id = match extract_token_parts(auth.token()) {
-        Some(user_id) => user_id.0,
-        None => return Err(AppError::Unauthorized),
-    };
+    let user_id = identity.user_id;
 
     let new_token = db.regenerate_user_token(&user_id).await?;
 
@@ -0,0 +1,68 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::auth::{AuthenticatedUser, SessionInfoRes, SessionsRevokedRes};
+use crate::types::error::AppError;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::utils::webutils::{clear_session_cookies, describe_device};
+use actix_web::{delete, get, web, HttpResponse};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Lists the caller's live sessions.
+#[get("")]
+async fn list(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> ApiResult<Vec<SessionInfoRes>> {
+    let sessions = db.list_sessions(&identity.user_id).await?;
+
+    Ok(ApiResponse::Ok(
+        sessions
+            .into_iter()
+            .map(|s| SessionInfoRes {
+                device: describe_device(s.user_agent.as_deref()),
+                current: identity.session_id == Some(s.id),
+                id: s.id,
+                auth_method: s.auth_method,
+                ip_address: s.ip_address,
+                user_agent: s.user_agent,
+                created_at: s.created_at,
+                last_seen_at: s.last_seen_at,
+                expires_at: s.expires_at,
+            })
+            .collect(),
+    ))
+}
+
+/// Ends one session. Its token stops working immediately.
+#[delete("/{id}")]
+async fn revoke(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let session_id = path.into_inner();
+    db.revoke_session(&identity.user_id, &session_id).await?;
+
+    let mut res = HttpResponse::NoContent().finish();
+    if identity.session_id == Some(session_id) {
+        clear_session_cookies(&mut res);
+    }
+    Ok(res)
+}
+
+/// Signs out everywhere, including the calling session. API tokens are not
+/// sessions and keep working; regenerate to rotate those.
+#[delete("")]
+async fn revoke_all(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    identity: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let revoked = db.revoke_all_sessions(&identity.user_id).await?;
+
+    let mut res = HttpResponse::Ok().json(SessionsRevokedRes { revoked });
+    clear_session_cookies(&mut res);
+    Ok(res)
+}
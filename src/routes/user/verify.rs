@@ -0,0 +1,64 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::response::{ApiResponse, ApiResult};
+use crate::types::user::{RUserVerify, RUserVerifyResend};
+use crate::utils::mail::mail_email_verification;
+use actix_web::{get, post, web};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Serialize, Deserialize)]
+pub struct Response {
+    pub message: String,
+}
+
+/// Confirms an email with the code from the verification mail.
+#[post("")]
+async fn verify(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RUserVerify>,
+) -> ApiResult<Response> {
+    db.verify_email(&body.email, &body.code).await?;
+
+    Ok(ApiResponse::Ok(Response {
+        message: "Email verified; your access token is now active.".to_string(),
+    }))
+}
+
+/// Same as [`verify`], for the link embedded in the verification mail.
+#[get("")]
+async fn verify_link(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    query: web::Query<RUserVerify>,
+) -> ApiResult<Response> {
+    db.verify_email(&query.email, &query.code).await?;
+
+    Ok(ApiResponse::Ok(Response {
+        message: "Email verified; your access token is now active.".to_string(),
+    }))
+}
+
+/// Sends a new verification code. Always answers the same way so it can't be
+/// used to find out which emails have accounts.
+#[post("/resend")]
+async fn resend(
+    _req: actix_web::HttpRequest,
+    db: web::Data<Arc<PostgresService>>,
+    body: web::Json<RUserVerifyResend>,
+) -> ApiResult<Response> {
+    if let Ok(Some(user)) = db.user_awaiting_verification(&body.email).await {
+        match db.create_email_verification(&user.id).await {
+            Ok(code) => {
+                mail_email_verification(&body.email, &code).await.ok();
+            }
+            Err(e) => warn!("Failed to issue verification code for {}: {}", user.id, e),
+        }
+    }
+
+    Ok(ApiResponse::Ok(Response {
+        message: "If that account is awaiting verification, a new code has been sent."
+            .to_string(),
+    }))
+}
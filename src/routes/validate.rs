@@ -6,7 +6,8 @@ use std::sync::Arc;
 use crate::db::postgres_service::PostgresService;
 use crate::types::error::AppError;
 use crate::types::response::{ApiResponse, ApiResult};
-use crate::utils::token::token_valid;
+use crate::types::token::TokenStatus;
+use crate::utils::token::check_token;
 
 #[derive(Serialize, Deserialize)]
 pub struct Response {}
@@ -17,8 +18,11 @@ async fn validate(
     auth: BearerAuth,
     db: web::Data<Arc<PostgresService>>,
 ) -> ApiResult<Response> {
-    if !token_valid(&db, auth.token()).await {
-        return Err(AppError::Unauthorized);
+    match check_token(&db, auth.token()).await {
+        TokenStatus::Valid(_) => {}
+        TokenStatus::Suspended => return Err(AppError::Suspended),
+        TokenStatus::Locked => return Err(AppError::Locked),
+        TokenStatus::Pending | TokenStatus::Invalid => return Err(AppError::Unauthorized),
     }
 
     Ok(ApiResponse::EmptyOk)
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use entity::role_binding::SubjectType;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+fn default_inherited() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RAclGrant {
+    /// Resource path, e.g. `workspace:<id>/folder:<id>`.
+    pub resource: String,
+    pub principal_type: SubjectType,
+    pub principal_id: Uuid,
+    /// Name of an existing permission, e.g. `file.read`.
+    pub permission: String,
+    /// Whether the entry also applies beneath `resource`. Defaults to true.
+    #[serde(default = "default_inherited")]
+    pub inherited: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RAclList {
+    pub resource: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AclEntryRes {
+    pub id: Uuid,
+    /// Where the entry is set; an ancestor of the listed resource if inherited.
+    pub resource: String,
+    pub principal_type: SubjectType,
+    pub principal_id: Uuid,
+    pub permission: String,
+    pub inherited: bool,
+    pub granted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::acl_entry::Model> for AclEntryRes {
+    fn from(m: entity::acl_entry::Model) -> Self {
+        Self {
+            id: m.id,
+            resource: m.resource,
+            principal_type: m.principal_type,
+            principal_id: m.principal_id,
+            permission: m.permission,
+            inherited: m.inherited,
+            granted_by: m.granted_by,
+            created_at: m.created_at,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for the admin audit trail. Filters combine.
+#[derive(Serialize, Deserialize, Default)]
+pub struct RAuditList {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditEventRes {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::audit_event::Model> for AuditEventRes {
+    fn from(m: entity::audit_event::Model) -> Self {
+        Self {
+            id: m.id,
+            actor_id: m.actor_id,
+            action: m.action,
+            target_type: m.target_type,
+            target_id: m.target_id,
+            detail: m.detail,
+            created_at: m.created_at,
+        }
+    }
+}
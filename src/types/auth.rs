@@ -0,0 +1,148 @@
+use crate::types::error::AppError;
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use chrono::{DateTime, Utc};
+use entity::user::UserRole;
+use serde::{Deserialize, Serialize};
+use std::future::{ready, Ready};
+use uuid::Uuid;
+
+/// The account behind a validated credential.
+///
+/// [`crate::utils::webutils::authenticate`] puts this in the request
+/// extensions; handlers take it as an extractor instead of re-parsing the token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthenticatedUser {
+    pub user_id: Uuid,
+    /// Set when the credential was a session rather than the user's API token.
+    pub session_id: Option<Uuid>,
+    /// Set when the credential was a named API key.
+    pub api_key_id: Option<Uuid>,
+    /// What the credential may do. `None` means unrestricted (API token, session).
+    pub scopes: Option<Vec<String>>,
+    /// The role the caller acts with when the credential was checked: the
+    /// account role, or a higher one from an active elevation.
+    pub role: UserRole,
+    /// How the credential was obtained: the session's login method, or
+    /// `apikey` / `user` for API keys and the account token.
+    pub auth_method: String,
+    /// When the credential was issued, if known.
+    pub authenticated_at: Option<DateTime<Utc>>,
+    /// The temporary elevation `role` comes from, if any.
+    pub elevation: Option<ActiveElevation>,
+}
+
+/// A just-in-time elevation in effect for the caller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveElevation {
+    pub elevation_id: Uuid,
+    pub role: UserRole,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AuthenticatedUser {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.iter().any(|s| s == scope))
+    }
+
+    /// Whether the caller's role is only temporary. Elevated admins can't
+    /// make anyone's rights permanent, their own included.
+    pub fn is_elevated(&self) -> bool {
+        self.elevation.is_some()
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            req.extensions()
+                .get::<AuthenticatedUser>()
+                .cloned()
+                .ok_or(AppError::Unauthorized),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RLogin {
+    pub email: String,
+    pub password: String,
+    /// TOTP or recovery code, required once TOTP is enabled.
+    pub otp: Option<String>,
+    /// Deliver the session as cookies instead of in the body (browser clients).
+    #[serde(default)]
+    pub cookie: bool,
+}
+
+/// A freshly issued session. Returned once, at login.
+#[derive(Serialize, Deserialize)]
+pub struct SessionRes {
+    /// Bearer token. Omitted when the session was set as a cookie.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Send back in `X-CSRF-Token` on cookie-authenticated mutations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csrf_token: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// One of the caller's live sessions.
+#[derive(Serialize, Deserialize)]
+pub struct SessionInfoRes {
+    pub id: Uuid,
+    /// Browser and OS guessed from the user agent, e.g. `Firefox on Linux`.
+    pub device: String,
+    pub auth_method: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// The session making this request.
+    pub current: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionsRevokedRes {
+    pub revoked: u64,
+}
+
+/// A named API key, without its secret.
+#[derive(Serialize, Deserialize)]
+pub struct ApiKeyRes {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<entity::api_key::Model> for ApiKeyRes {
+    fn from(m: entity::api_key::Model) -> Self {
+        Self {
+            id: m.id,
+            name: m.name,
+            scopes: crate::utils::scope::split_scopes(&m.scopes),
+            created_at: m.created_at,
+            last_used_at: m.last_used_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FederatedProviderRes {
+    pub id: String,
+    pub name: String,
+}
+
+/// What an identity provider appends to our callback URL.
+#[derive(Serialize, Deserialize)]
+pub struct RFederatedCallback {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
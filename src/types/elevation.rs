@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use entity::elevation::ElevationStatus;
+use entity::role_binding::SubjectType;
+use entity::user::UserRole;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct RElevationRequest {
+    pub role: UserRole,
+    /// Why the rights are needed, e.g. a ticket reference. Kept in the audit trail.
+    pub reason: String,
+    pub duration_minutes: i32,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct RElevationList {
+    pub user_id: Option<Uuid>,
+    pub status: Option<ElevationStatus>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ElevationRes {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub role: UserRole,
+    pub reason: String,
+    pub duration_minutes: i32,
+    pub status: ElevationStatus,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::elevation::Model> for ElevationRes {
+    fn from(m: entity::elevation::Model) -> Self {
+        Self {
+            id: m.id,
+            user_id: m.user_id,
+            role: m.role,
+            reason: m.reason,
+            duration_minutes: m.duration_minutes,
+            status: m.status,
+            decided_by: m.decided_by,
+            decided_at: m.decided_at,
+            expires_at: m.expires_at,
+            ended_at: m.ended_at,
+            created_at: m.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct REligibilityCreate {
+    pub subject_type: SubjectType,
+    pub subject_id: Uuid,
+    pub role: UserRole,
+    pub max_minutes: i32,
+    /// Defaults to true: another admin approves each request.
+    #[serde(default = "default_requires_approval")]
+    pub requires_approval: bool,
+}
+
+fn default_requires_approval() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EligibilityRes {
+    pub id: Uuid,
+    pub subject_type: SubjectType,
+    pub subject_id: Uuid,
+    pub role: UserRole,
+    pub max_minutes: i32,
+    pub requires_approval: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::elevation_eligibility::Model> for EligibilityRes {
+    fn from(m: entity::elevation_eligibility::Model) -> Self {
+        Self {
+            id: m.id,
+            subject_type: m.subject_type,
+            subject_id: m.subject_id,
+            role: m.role,
+            max_minutes: m.max_minutes,
+            requires_approval: m.requires_approval,
+            created_by: m.created_by,
+            created_at: m.created_at,
+        }
+    }
+}
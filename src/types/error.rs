@@ -20,6 +20,10 @@ pub enum AppError {
     Unauthorized,
     #[error("forbidden")]
     Forbidden,
+    #[error("account suspended")]
+    Suspended,
+    #[error("account locked")]
+    Locked,
 
     // infra things
     #[error(transparent)]
@@ -50,6 +54,8 @@ impl AppError {
             Self::BadRequest(_) => "BAD_REQUEST",
             Self::Unauthorized => "UNAUTHORIZED",
             Self::Forbidden => "FORBIDDEN",
+            Self::Suspended => "ACCOUNT_SUSPENDED",
+            Self::Locked => "ACCOUNT_LOCKED",
             Self::Db(_) => "DB_ERROR",
             Self::Internal(_) => "INTERNAL_ERROR",
         }
@@ -69,7 +75,7 @@ impl ResponseError for AppError {
             Self::NotFound => StatusCode::NOT_FOUND,
             Self::Validation(_) | Self::BadRequest(_) => StatusCode::BAD_REQUEST,
             Self::Unauthorized => StatusCode::UNAUTHORIZED,
-            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::Forbidden | Self::Suspended | Self::Locked => StatusCode::FORBIDDEN,
             Self::Db(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
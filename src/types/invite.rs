@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use entity::user::UserRole;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct RInviteCreate {
+    /// Defaults to a single use.
+    pub max_uses: Option<i32>,
+    /// Hours until the code stops working. `None` never expires.
+    pub expires_in_hours: Option<i64>,
+    /// Role given to accounts created with this code. Defaults to `member`.
+    pub default_role: Option<UserRole>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InviteCreateRes {
+    pub id: Uuid,
+    /// Only ever returned here; the server keeps a hash.
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InviteRes {
+    pub id: Uuid,
+    pub default_role: UserRole,
+    pub max_uses: i32,
+    pub uses: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::signup_invite::Model> for InviteRes {
+    fn from(m: entity::signup_invite::Model) -> Self {
+        Self {
+            id: m.id,
+            default_role: m.default_role,
+            max_uses: m.max_uses,
+            uses: m.uses,
+            expires_at: m.expires_at,
+            revoked_at: m.revoked_at,
+            created_at: m.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RSignup {
+    /// Required unless the registration mode allows signup without one.
+    pub code: Option<String>,
+    pub name: String,
+    pub email: String,
+}
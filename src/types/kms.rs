@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyRotationRes {
+    /// Id of the master key everything is now wrapped under.
+    pub master_key_id: String,
+    pub rewrapped: u64,
+    /// Teams whose key is wrapped under a master key that isn't configured.
+    pub failed: Vec<Uuid>,
+}
@@ -1,5 +1,18 @@
+pub mod acl;
+pub mod audit;
+pub mod auth;
+pub mod elevation;
 pub mod error;
+pub mod invite;
+pub mod kms;
 pub mod mail;
+pub mod oauth;
+pub mod oidc;
+pub mod passkey;
+pub mod plan;
+pub mod rbac;
 pub mod response;
+pub mod scim;
+pub mod team;
 pub mod token;
 pub mod user;
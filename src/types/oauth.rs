@@ -0,0 +1,148 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::error::AppError;
+
+/// Errors in the shape OAuth clients expect (RFC 6749 section 5.2, RFC 8628 section 3.5).
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("invalid_request: {0}")]
+    InvalidRequest(String),
+    #[error("invalid_client")]
+    InvalidClient,
+    #[error("invalid_grant")]
+    InvalidGrant,
+    #[error("invalid_scope")]
+    InvalidScope,
+    #[error("unsupported_grant_type")]
+    UnsupportedGrantType,
+    #[error("authorization_pending")]
+    AuthorizationPending,
+    #[error("slow_down")]
+    SlowDown,
+    #[error("access_denied")]
+    AccessDenied,
+    #[error("expired_token")]
+    ExpiredToken,
+    #[error("server_error")]
+    ServerError,
+}
+
+impl From<AppError> for OAuthError {
+    fn from(_: AppError) -> Self {
+        OAuthError::ServerError
+    }
+}
+
+impl From<sea_orm::DbErr> for OAuthError {
+    fn from(_: sea_orm::DbErr) -> Self {
+        OAuthError::ServerError
+    }
+}
+
+#[derive(Serialize)]
+struct OAuthErrorBody<'a> {
+    error: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_description: Option<&'a str>,
+}
+
+impl OAuthError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest(_) => "invalid_request",
+            Self::InvalidClient => "invalid_client",
+            Self::InvalidGrant => "invalid_grant",
+            Self::InvalidScope => "invalid_scope",
+            Self::UnsupportedGrantType => "unsupported_grant_type",
+            Self::AuthorizationPending => "authorization_pending",
+            Self::SlowDown => "slow_down",
+            Self::AccessDenied => "access_denied",
+            Self::ExpiredToken => "expired_token",
+            Self::ServerError => "server_error",
+        }
+    }
+}
+
+impl ResponseError for OAuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidClient => StatusCode::UNAUTHORIZED,
+            Self::ServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let description = match self {
+            Self::InvalidRequest(msg) => Some(msg.as_str()),
+            _ => None,
+        };
+        HttpResponse::build(self.status_code())
+            .insert_header(("Cache-Control", "no-store"))
+            .json(OAuthErrorBody {
+                error: self.code(),
+                error_description: description,
+            })
+    }
+}
+
+/// Device authorization request (RFC 8628 section 3.1), form-encoded.
+#[derive(Serialize, Deserialize)]
+pub struct RDeviceAuthorization {
+    pub client_id: String,
+    pub scope: Option<String>,
+    /// Ledger extension: name for the resulting API key, e.g. the host name.
+    pub device_name: Option<String>,
+}
+
+/// Device authorization response (RFC 8628 section 3.2).
+#[derive(Serialize, Deserialize)]
+pub struct DeviceAuthorizationRes {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: i64,
+    pub interval: i32,
+}
+
+/// Token request, form-encoded. Which fields are needed depends on `grant_type`.
+#[derive(Serialize, Deserialize)]
+pub struct RToken {
+    pub grant_type: String,
+    pub client_id: Option<String>,
+    /// `client_secret_post`; confidential clients may use HTTP Basic instead.
+    pub client_secret: Option<String>,
+    pub device_code: Option<String>,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TokenRes {
+    pub access_token: String,
+    pub token_type: String,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RDeviceUserCode {
+    pub user_code: String,
+}
+
+/// What the approving user is shown before deciding.
+#[derive(Serialize, Deserialize)]
+pub struct DeviceRequestRes {
+    pub user_code: String,
+    pub client_id: String,
+    pub device_name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Authorization request (OpenID Connect Core section 3.1.2.1), as query parameters.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RAuthorize {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub nonce: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    /// `consent` asks again even if the user already agreed.
+    pub prompt: Option<String>,
+}
+
+/// The user's answer to a consent prompt: the original request plus a decision.
+#[derive(Serialize, Deserialize)]
+pub struct RAuthorizeDecision {
+    #[serde(flatten)]
+    pub request: RAuthorize,
+    pub approve: bool,
+}
+
+/// Returned by `/oidc/authorize` when the user has to agree first.
+#[derive(Serialize, Deserialize)]
+pub struct ConsentPromptRes {
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+}
+
+/// Where the browser should go next, after a consent decision.
+#[derive(Serialize, Deserialize)]
+pub struct RedirectRes {
+    pub redirect_to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ROAuthClientCreate {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    /// Server-side apps that can keep a secret. Public clients rely on PKCE alone.
+    #[serde(default)]
+    pub confidential: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OAuthClientRes {
+    pub id: Uuid,
+    pub client_id: String,
+    pub name: String,
+    pub confidential: bool,
+    pub redirect_uris: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<entity::oauth_client::Model> for OAuthClientRes {
+    fn from(m: entity::oauth_client::Model) -> Self {
+        Self {
+            redirect_uris: crate::utils::oidc::redirect_uris(&m),
+            id: m.id,
+            client_id: m.client_id,
+            name: m.name,
+            confidential: m.secret_hash.is_some(),
+            created_at: m.created_at,
+            revoked_at: m.revoked_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OAuthClientCreateRes {
+    #[serde(flatten)]
+    pub client: OAuthClientRes,
+    /// Only ever returned here; the server keeps a hash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConsentRes {
+    pub id: Uuid,
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// ID token claims (OpenID Connect Core section 2).
+#[derive(Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub auth_time: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(flatten)]
+    pub claims: UserClaims,
+}
+
+/// Userinfo response (OpenID Connect Core section 5.3.2).
+#[derive(Serialize, Deserialize)]
+pub struct UserInfoRes {
+    pub sub: String,
+    #[serde(flatten)]
+    pub claims: UserClaims,
+}
+
+/// Claims about the user, filtered by the granted scopes.
+#[derive(Serialize, Deserialize)]
+pub struct UserClaims {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use webauthn_rs::prelude::{PublicKeyCredential, RegisterPublicKeyCredential};
+
+/// Challenge handed to the browser. Send `ceremony_id` back with the answer.
+#[derive(Serialize, Deserialize)]
+pub struct PasskeyChallengeRes<T> {
+    pub ceremony_id: Uuid,
+    /// Pass to `navigator.credentials.create()` / `.get()` as-is.
+    pub options: T,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPasskeyRegisterFinish {
+    pub ceremony_id: Uuid,
+    /// Label shown in the passkey list. Defaults to `Passkey`.
+    pub name: Option<String>,
+    pub credential: RegisterPublicKeyCredential,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PasskeyRes {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<entity::passkey_credential::Model> for PasskeyRes {
+    fn from(m: entity::passkey_credential::Model) -> Self {
+        Self {
+            id: m.id,
+            name: m.name,
+            created_at: m.created_at,
+            last_used_at: m.last_used_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPasskeyLoginStart {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPasskeyLoginFinish {
+    pub ceremony_id: Uuid,
+    pub credential: PublicKeyCredential,
+    /// Deliver the session as cookies instead of in the body (browser clients).
+    #[serde(default)]
+    pub cookie: bool,
+}
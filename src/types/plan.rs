@@ -0,0 +1,56 @@
+use crate::utils::quota::Usage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A plan's settings, for creating one or replacing an existing one.
+#[derive(Serialize, Deserialize)]
+pub struct RPlan {
+    pub name: String,
+    /// Omit a limit for unlimited.
+    pub max_bytes: Option<i64>,
+    pub max_files: Option<i64>,
+    pub max_share_links: Option<i64>,
+    /// Feature flags the plan turns on, e.g. `public_links`.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlanRes {
+    pub id: Uuid,
+    pub name: String,
+    pub max_bytes: Option<i64>,
+    pub max_files: Option<i64>,
+    pub max_share_links: Option<i64>,
+    pub features: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<entity::plan::Model> for PlanRes {
+    fn from(m: entity::plan::Model) -> Self {
+        Self {
+            features: serde_json::from_value(m.features).unwrap_or_default(),
+            id: m.id,
+            name: m.name,
+            max_bytes: m.max_bytes,
+            max_files: m.max_files,
+            max_share_links: m.max_share_links,
+            created_at: m.created_at,
+            updated_at: m.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPlanAssign {
+    /// `null` takes the subject off its plan, leaving it unlimited.
+    pub plan_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EntitlementsRes {
+    pub plan: Option<PlanRes>,
+    pub usage: Usage,
+}
@@ -0,0 +1,118 @@
+use crate::utils::policy::BindingCondition;
+use chrono::{DateTime, Utc};
+use entity::role_binding::SubjectType;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct RPermissionCreate {
+    /// Dotted action name, e.g. `file.delete`, or a `file.*` wildcard.
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PermissionRes {
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::permission::Model> for PermissionRes {
+    fn from(m: entity::permission::Model) -> Self {
+        Self {
+            name: m.name,
+            description: m.description,
+            created_at: m.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RRbacRoleCreate {
+    pub name: String,
+    pub description: Option<String>,
+    /// Names of existing permissions.
+    pub permissions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RbacRoleRes {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<(entity::rbac_role::Model, Vec<String>)> for RbacRoleRes {
+    fn from((role, permissions): (entity::rbac_role::Model, Vec<String>)) -> Self {
+        Self {
+            id: role.id,
+            name: role.name,
+            description: role.description,
+            permissions,
+            created_at: role.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RRoleBindingCreate {
+    pub subject_type: SubjectType,
+    pub subject_id: Uuid,
+    pub role_id: Uuid,
+    /// Resource path the role applies under, e.g. `workspace:<id>`; `*` for all.
+    pub scope: String,
+    /// Requirements on the request context; the binding applies only when all hold.
+    #[serde(default)]
+    pub conditions: Vec<BindingCondition>,
+}
+
+/// Query parameters for listing bindings. Filters combine.
+#[derive(Serialize, Deserialize, Default)]
+pub struct RRoleBindingList {
+    pub subject_type: Option<SubjectType>,
+    pub subject_id: Option<Uuid>,
+    pub role_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RoleBindingRes {
+    pub id: Uuid,
+    pub subject_type: SubjectType,
+    pub subject_id: Uuid,
+    pub role_id: Uuid,
+    pub scope: String,
+    pub conditions: serde_json::Value,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::role_binding::Model> for RoleBindingRes {
+    fn from(m: entity::role_binding::Model) -> Self {
+        Self {
+            id: m.id,
+            subject_type: m.subject_type,
+            subject_id: m.subject_id,
+            role_id: m.role_id,
+            scope: m.scope,
+            conditions: m.conditions,
+            created_by: m.created_by,
+            created_at: m.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RAuthorize {
+    /// Permission name, e.g. `file.delete`.
+    pub action: String,
+    /// Resource path, e.g. `workspace:<id>/file:<id>`.
+    pub resource: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuthorizeRes {
+    pub allowed: bool,
+}
@@ -21,4 +21,12 @@ impl<T: Serialize> Responder for ApiResponse<T> {
     }
 }
 
+/// Envelope for cursor-paginated listings, sent inside [`ApiResponse::Ok`].
+#[derive(Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass back as `cursor` to fetch the next page. `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
 pub type ApiResult<T> = Result<ApiResponse<T>, AppError>;
@@ -0,0 +1,194 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::error::AppError;
+
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCHEMA_LIST: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCHEMA_PATCH: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const SCHEMA_ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCHEMA_SERVICE_PROVIDER_CONFIG: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+pub const SCHEMA_RESOURCE_TYPE: &str = "urn:ietf:params:scim:schemas:core:2.0:ResourceType";
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+/// Errors in the shape SCIM clients expect (RFC 7644 section 3.12).
+#[derive(Debug, Error)]
+pub enum ScimError {
+    #[error("invalidFilter: {0}")]
+    InvalidFilter(String),
+    #[error("invalidValue: {0}")]
+    InvalidValue(String),
+    #[error("invalidSyntax: {0}")]
+    InvalidSyntax(String),
+    #[error("invalidPath: {0}")]
+    InvalidPath(String),
+    #[error("mutability: {0}")]
+    Mutability(String),
+    #[error("uniqueness")]
+    Uniqueness,
+    #[error("not found")]
+    NotFound,
+    #[error(transparent)]
+    App(AppError),
+}
+
+impl From<AppError> for ScimError {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::NotFound => ScimError::NotFound,
+            AppError::AlreadyExists => ScimError::Uniqueness,
+            AppError::Validation(msg) | AppError::BadRequest(msg) => ScimError::InvalidValue(msg),
+            e => ScimError::App(e),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for ScimError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        AppError::from(e).into()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScimErrorBody<'a> {
+    schemas: [&'a str; 1],
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scim_type: Option<&'a str>,
+    detail: String,
+}
+
+impl ScimError {
+    fn scim_type(&self) -> Option<&'static str> {
+        match self {
+            Self::InvalidFilter(_) => Some("invalidFilter"),
+            Self::InvalidValue(_) => Some("invalidValue"),
+            Self::InvalidSyntax(_) => Some("invalidSyntax"),
+            Self::InvalidPath(_) => Some("invalidPath"),
+            Self::Mutability(_) => Some("mutability"),
+            Self::Uniqueness => Some("uniqueness"),
+            Self::NotFound | Self::App(_) => None,
+        }
+    }
+}
+
+impl ResponseError for ScimError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Uniqueness => StatusCode::CONFLICT,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::App(e) => e.status_code(),
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let detail = match self {
+            Self::App(e) if e.status_code().is_server_error() => "Internal error.".to_string(),
+            e => e.to_string(),
+        };
+        HttpResponse::build(self.status_code())
+            .content_type(SCIM_CONTENT_TYPE)
+            .json(ScimErrorBody {
+                schemas: [SCHEMA_ERROR],
+                status: self.status_code().as_u16().to_string(),
+                scim_type: self.scim_type(),
+                detail,
+            })
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// The core User resource (RFC 7643 section 4.1), limited to what Ledger stores.
+/// `userName` is the account email.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub user_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<ScimName>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ScimMeta>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: String,
+    pub created: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    pub location: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T> {
+    pub schemas: Vec<String>,
+    pub total_results: u64,
+    pub start_index: u64,
+    pub items_per_page: u64,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+/// Query parameters for listing (RFC 7644 section 3.4.2). `startIndex` is 1-based.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RScimList {
+    pub filter: Option<String>,
+    pub start_index: Option<u64>,
+    pub count: Option<u64>,
+}
+
+/// PATCH body (RFC 7644 section 3.5.2).
+#[derive(Serialize, Deserialize)]
+pub struct RScimPatch {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ScimPatchOperation {
+    pub op: String,
+    pub path: Option<String>,
+    pub value: Option<serde_json::Value>,
+}
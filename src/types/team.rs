@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use entity::team_membership::TeamRole;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct RTeamCreate {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RTeamUpdate {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RTeamMemberAdd {
+    pub user_id: Uuid,
+    /// Defaults to `member`.
+    pub role: Option<TeamRole>,
+}
+
+/// A team as seen by one of its members.
+#[derive(Serialize, Deserialize)]
+pub struct TeamRes {
+    pub id: Uuid,
+    pub name: String,
+    /// The caller's role in the team.
+    pub role: TeamRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<(entity::team_membership::Model, entity::team::Model)> for TeamRes {
+    fn from((membership, team): (entity::team_membership::Model, entity::team::Model)) -> Self {
+        Self {
+            id: team.id,
+            name: team.name,
+            role: membership.role,
+            created_at: team.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TeamMemberRes {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: TeamRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<(entity::team_membership::Model, entity::user::Model)> for TeamMemberRes {
+    fn from((membership, user): (entity::team_membership::Model, entity::user::Model)) -> Self {
+        Self {
+            user_id: user.id,
+            name: user.name,
+            email: user.email,
+            role: membership.role,
+            joined_at: membership.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RTeamInviteCreate {
+    pub email: String,
+    /// Defaults to `member`.
+    pub role: Option<TeamRole>,
+}
+
+/// A pending invite. The token is only ever mailed.
+#[derive(Serialize, Deserialize)]
+pub struct TeamInviteRes {
+    pub id: Uuid,
+    pub email: String,
+    pub role: TeamRole,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub sent_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::team_invite::Model> for TeamInviteRes {
+    fn from(m: entity::team_invite::Model) -> Self {
+        Self {
+            id: m.id,
+            email: m.email,
+            role: m.role,
+            invited_by: m.invited_by,
+            expires_at: m.expires_at,
+            sent_at: m.sent_at,
+            created_at: m.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RTeamInviteAccept {
+    pub token: String,
+    /// Name for the account if one has to be created. Defaults to the email.
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TeamInviteAcceptRes {
+    pub team_id: Uuid,
+    pub user_id: Uuid,
+    /// True when accepting created the account; its token was mailed.
+    pub account_created: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TeamDeletionRes {
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RTeamDeletionConfirm {
+    pub code: String,
+}
@@ -1,3 +1,4 @@
+use crate::types::auth::AuthenticatedUser;
 use base64::{prelude::BASE64_STANDARD, Engine};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -6,6 +7,16 @@ use std::fmt;
 pub enum TokenType {
     User,
     Admin,
+    Invite,
+    Recovery,
+    Session,
+    Password,
+    ApiKey,
+    Device,
+    ClientSecret,
+    AuthorizationCode,
+    OidcAccess,
+    TeamInvite,
 }
 
 impl fmt::Display for TokenType {
@@ -13,10 +24,41 @@ impl fmt::Display for TokenType {
         match self {
             TokenType::User => write!(f, "user"),
             TokenType::Admin => write!(f, "admin"),
+            TokenType::Invite => write!(f, "invite"),
+            TokenType::Recovery => write!(f, "recovery"),
+            TokenType::Session => write!(f, "session"),
+            TokenType::Password => write!(f, "password"),
+            TokenType::ApiKey => write!(f, "apikey"),
+            TokenType::Device => write!(f, "device"),
+            TokenType::ClientSecret => write!(f, "client"),
+            TokenType::AuthorizationCode => write!(f, "code"),
+            TokenType::OidcAccess => write!(f, "oidc"),
+            TokenType::TeamInvite => write!(f, "teaminvite"),
         }
     }
 }
 
+/// Result of checking a bearer token against the stored user record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenStatus {
+    /// Token matches an active account.
+    Valid(AuthenticatedUser),
+    /// Token matches, but the account has not verified its email yet.
+    Pending,
+    /// Token matches, but an admin suspended the account.
+    Suspended,
+    /// Token matches, but the account is locked.
+    Locked,
+    /// Token is malformed, unknown, or does not match.
+    Invalid,
+}
+
+impl TokenStatus {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, TokenStatus::Valid(_))
+    }
+}
+
 pub fn construct_token(user_id: &str, api_key: &str) -> String {
     BASE64_STANDARD.encode(format!("{user_id}.{api_key}"))
 }
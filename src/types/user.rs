@@ -1,10 +1,16 @@
+use chrono::{DateTime, Utc};
+use entity::user::{UserRole, UserStatus};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 pub struct DBUserCreate {
     pub name: String,
     pub email: String,
     pub auth_hash: String,
+    /// `None` leaves the account pending until the email is verified.
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub role: UserRole,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -22,3 +28,130 @@ pub struct UserCreateRes {
 pub struct UserRegenerateTokenRes {
     pub message: String,
 }
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserVerify {
+    pub email: String,
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserVerifyResend {
+    pub email: String,
+}
+
+/// Query parameters for the admin user directory.
+#[derive(Serialize, Deserialize, Default)]
+pub struct RUserList {
+    /// Case-insensitive prefix matched against email and name.
+    pub q: Option<String>,
+    pub status: Option<UserStatus>,
+    pub role: Option<UserRole>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRes {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    /// Address the account is moving to, waiting on verification.
+    pub pending_email: Option<String>,
+    pub role: UserRole,
+    pub status: UserStatus,
+    pub status_reason: Option<String>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<entity::user::Model> for UserRes {
+    fn from(m: entity::user::Model) -> Self {
+        Self {
+            id: m.id,
+            name: m.name,
+            email: m.email,
+            pending_email: m.pending_email,
+            role: m.role,
+            status: m.status,
+            status_reason: m.status_reason,
+            email_verified_at: m.email_verified_at,
+            created_at: m.created_at,
+            updated_at: m.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserStatusUpdate {
+    pub status: UserStatus,
+    /// Why the status changed. Required so every suspension has a paper trail.
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserRoleUpdate {
+    pub role: UserRole,
+    /// Optional note kept with the change in the audit trail.
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserRecover {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserRecoverRedeem {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRecoverRes {
+    pub message: String,
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRecoverLinkRes {
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RUserEmailUpdate {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TotpEnrollRes {
+    /// Base32 secret, for authenticators that can't scan `otpauth_uri`.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RTotpConfirm {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TotpConfirmRes {
+    /// Single-use fallbacks for a lost authenticator. Shown only once.
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RPasswordSet {
+    /// Token from the set-password mail.
+    pub token: String,
+    pub password: String,
+}
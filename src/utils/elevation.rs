@@ -0,0 +1,46 @@
+//! Just-in-time elevation: who may ask for a temporary role, and what role
+//! a credential ends up with.
+
+use crate::utils::role::role_satisfies;
+use entity::user::UserRole;
+
+/// Longest elevation an eligibility can allow.
+pub const MAX_ELEVATION_MINUTES: i32 = 24 * 60;
+/// How long a request waits for approval before it lapses.
+pub const PENDING_ELEVATION_TTL_HOURS: i64 = 24;
+
+/// One way a user may be elevated, directly or through a team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eligibility {
+    pub role: UserRole,
+    pub max_minutes: i32,
+    pub requires_approval: bool,
+}
+
+/// Folds every eligibility that covers `role` into one: the longest
+/// duration any of them allows, and approval only if all of them want it.
+/// `None` when the user may not ask for `role` at all.
+pub fn resolve_eligibility(entries: &[Eligibility], role: UserRole) -> Option<Eligibility> {
+    entries
+        .iter()
+        .filter(|entry| role_satisfies(entry.role, role))
+        .fold(None, |acc: Option<Eligibility>, entry| {
+            Some(match acc {
+                None => Eligibility { role, ..*entry },
+                Some(acc) => Eligibility {
+                    role,
+                    max_minutes: acc.max_minutes.max(entry.max_minutes),
+                    requires_approval: acc.requires_approval && entry.requires_approval,
+                },
+            })
+        })
+}
+
+/// The role a credential acts with: the account role, raised by an active
+/// elevation but never lowered by one.
+pub fn effective_role(account: UserRole, elevated: Option<UserRole>) -> UserRole {
+    match elevated {
+        Some(role) if !role_satisfies(account, role) => role,
+        _ => account,
+    }
+}
@@ -0,0 +1,215 @@
+//! Signing in through an external OpenID provider (Ledger as relying party).
+//!
+//! Provider metadata and keys are fetched on every login rather than cached:
+//! logins are rare, and this picks up key rotation without a restart.
+
+use crate::{
+    config::{config, FederatedProviderConfig},
+    types::error::AppError,
+    utils::jwt::{peek_kid, rsa_from_jwk, verify_rs256},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::Utc;
+use rand_core::{OsRng, RngCore};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::warn;
+
+/// The parts of a provider's discovery document we use.
+#[derive(Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct Jwk {
+    kid: Option<String>,
+    kty: String,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExternalClaims {
+    iss: String,
+    sub: String,
+    /// A string or an array of strings.
+    aud: serde_json::Value,
+    exp: i64,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// Who the provider says signed in.
+pub struct ExternalIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+pub fn provider(id: &str) -> Option<&'static FederatedProviderConfig> {
+    config().federation.iter().find(|p| p.id == id)
+}
+
+/// Where the provider sends the browser back to.
+pub fn callback_uri(provider: &FederatedProviderConfig) -> String {
+    format!(
+        "{}/auth/federated/{}/callback",
+        config().public_url.trim_end_matches('/'),
+        provider.id
+    )
+}
+
+/// 32 random bytes, base64url; used for nonces and PKCE verifiers.
+pub fn random_urlsafe() -> String {
+    let mut buf = [0u8; 32];
+    OsRng.fill_bytes(&mut buf);
+    URL_SAFE_NO_PAD.encode(buf)
+}
+
+fn http() -> Result<Client, AppError> {
+    Client::builder()
+        .user_agent("ledger/1.0 (+reqwest)")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| AppError::Internal(format!("build client failed: {e}")))
+}
+
+fn unreachable(provider: &FederatedProviderConfig, e: impl std::fmt::Display) -> AppError {
+    warn!("Identity provider {} failed: {}", provider.id, e);
+    AppError::Internal("The identity provider could not be reached.".into())
+}
+
+pub async fn discover(provider: &FederatedProviderConfig) -> Result<ProviderMetadata, AppError> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        provider.issuer.trim_end_matches('/')
+    );
+    let metadata: ProviderMetadata = http()?
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| unreachable(provider, e))?
+        .json()
+        .await
+        .map_err(|e| unreachable(provider, e))?;
+
+    // OpenID Connect Discovery section 4.3.
+    if metadata.issuer != provider.issuer {
+        return Err(unreachable(
+            provider,
+            "issuer mismatch in discovery document",
+        ));
+    }
+    Ok(metadata)
+}
+
+pub fn authorization_url(
+    metadata: &ProviderMetadata,
+    provider: &FederatedProviderConfig,
+    state: &str,
+    nonce: &str,
+    code_challenge: &str,
+) -> Result<String, AppError> {
+    let mut url =
+        url::Url::parse(&metadata.authorization_endpoint).map_err(|e| unreachable(provider, e))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &callback_uri(provider))
+        .append_pair("scope", "openid email profile")
+        .append_pair("state", state)
+        .append_pair("nonce", nonce)
+        .append_pair("code_challenge", code_challenge)
+        .append_pair("code_challenge_method", "S256");
+    Ok(url.into())
+}
+
+/// Exchanges the code and checks the ID token (OpenID Connect Core section 3.1.3.7).
+pub async fn complete_login(
+    metadata: &ProviderMetadata,
+    provider: &FederatedProviderConfig,
+    code: &str,
+    code_verifier: &str,
+    nonce: &str,
+) -> Result<ExternalIdentity, AppError> {
+    let client = http()?;
+    let redirect_uri = callback_uri(provider);
+    let tokens: TokenResponse = client
+        .post(&metadata.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", provider.client_id.as_str()),
+            ("client_secret", provider.client_secret.as_str()),
+            ("code_verifier", code_verifier),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| unreachable(provider, e))?
+        .json()
+        .await
+        .map_err(|e| unreachable(provider, e))?;
+
+    let jwks: Jwks = client
+        .get(&metadata.jwks_uri)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| unreachable(provider, e))?
+        .json()
+        .await
+        .map_err(|e| unreachable(provider, e))?;
+
+    let kid = peek_kid(&tokens.id_token);
+    let claims: ExternalClaims = jwks
+        .keys
+        .iter()
+        .filter(|k| k.kty == "RSA" && (kid.is_none() || k.kid == kid))
+        .filter_map(|k| rsa_from_jwk(k.n.as_deref()?, k.e.as_deref()?))
+        .find_map(|key| verify_rs256(&tokens.id_token, &key))
+        .ok_or(AppError::Unauthorized)?;
+
+    let audience_ok = match &claims.aud {
+        serde_json::Value::String(aud) => *aud == provider.client_id,
+        serde_json::Value::Array(auds) => auds
+            .iter()
+            .any(|a| a.as_str() == Some(provider.client_id.as_str())),
+        _ => false,
+    };
+    if claims.iss != provider.issuer
+        || !audience_ok
+        || claims.exp <= Utc::now().timestamp()
+        || claims.nonce.as_deref() != Some(nonce)
+    {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(ExternalIdentity {
+        subject: claims.sub,
+        email: claims.email.map(|e| e.trim().to_ascii_lowercase()),
+        email_verified: claims.email_verified,
+        name: claims.name,
+    })
+}
@@ -0,0 +1,136 @@
+//! Minimal RS256 JSON Web Tokens: enough to sign our ID tokens, publish the
+//! key set, and check tokens signed by someone else's key.
+
+use crate::{config::config, types::error::AppError};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand_core::OsRng;
+use rsa::{
+    pkcs1v15::{Signature, SigningKey, VerifyingKey},
+    pkcs8::DecodePrivateKey,
+    signature::{SignatureEncoding, Signer, Verifier},
+    traits::PublicKeyParts,
+    BigUint, RsaPrivateKey, RsaPublicKey,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// The key this server signs with, and the id it is published under.
+pub struct ServerKey {
+    pub kid: String,
+    key: RsaPrivateKey,
+}
+
+static SERVER_KEY: OnceLock<ServerKey> = OnceLock::new();
+
+/// Loads the configured signing key, or generates a throwaway one.
+pub fn server_key() -> &'static ServerKey {
+    SERVER_KEY.get_or_init(|| {
+        let key = match &config().oidc.signing_key_pem {
+            Some(pem) => RsaPrivateKey::from_pkcs8_pem(pem)
+                .unwrap_or_else(|e| panic!("Invalid OIDC signing key: {e}")),
+            None => {
+                warn!("No OIDC_SIGNING_KEY_FILE set; generating an ephemeral signing key.");
+                RsaPrivateKey::new(&mut OsRng, 2048).expect("Failed to generate an RSA signing key")
+            }
+        };
+        ServerKey {
+            kid: key_id(&key.to_public_key()),
+            key,
+        }
+    })
+}
+
+impl ServerKey {
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        sign_rs256(claims, &self.key, &self.kid)
+    }
+
+    pub fn public_key(&self) -> RsaPublicKey {
+        self.key.to_public_key()
+    }
+
+    /// The JWKS document served at the `jwks_uri`.
+    pub fn jwks(&self) -> serde_json::Value {
+        json!({ "keys": [public_jwk(&self.public_key(), &self.kid)] })
+    }
+}
+
+/// Signs `claims` as a compact RS256 JWS with `kid` in the header.
+pub fn sign_rs256<T: Serialize>(
+    claims: &T,
+    key: &RsaPrivateKey,
+    kid: &str,
+) -> Result<String, AppError> {
+    let header = json!({ "alg": "RS256", "typ": "JWT", "kid": kid });
+    let signing_input = format!("{}.{}", encode_part(&header)?, encode_part(claims)?);
+    let signature = SigningKey::<Sha256>::new(key.clone()).sign(signing_input.as_bytes());
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    ))
+}
+
+fn encode_part<T: Serialize>(value: &T) -> Result<String, AppError> {
+    let bytes = serde_json::to_vec(value)
+        .map_err(|_| AppError::Internal("There was an issue while encoding a token.".into()))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Stable key id: base64url of the first 16 bytes of SHA-256(modulus).
+pub fn key_id(key: &RsaPublicKey) -> String {
+    URL_SAFE_NO_PAD.encode(&Sha256::digest(key.n().to_bytes_be())[..16])
+}
+
+pub fn public_jwk(key: &RsaPublicKey, kid: &str) -> serde_json::Value {
+    json!({
+        "kty": "RSA",
+        "use": "sig",
+        "alg": "RS256",
+        "kid": kid,
+        "n": URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+        "e": URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+    })
+}
+
+/// Builds an RSA key from the `n` and `e` members of a JWK.
+pub fn rsa_from_jwk(n: &str, e: &str) -> Option<RsaPublicKey> {
+    let n = BigUint::from_bytes_be(&URL_SAFE_NO_PAD.decode(n).ok()?);
+    let e = BigUint::from_bytes_be(&URL_SAFE_NO_PAD.decode(e).ok()?);
+    RsaPublicKey::new(n, e).ok()
+}
+
+/// Reads the `kid` from a token header without checking anything.
+pub fn peek_kid(token: &str) -> Option<String> {
+    let header = token.split('.').next()?;
+    let header: serde_json::Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+    header["kid"].as_str().map(str::to_string)
+}
+
+/// Checks an RS256 signature and returns the claims. Expiry, audience and
+/// issuer are left to the caller.
+pub fn verify_rs256<T: DeserializeOwned>(token: &str, key: &RsaPublicKey) -> Option<T> {
+    let mut parts = token.split('.');
+    let (header, payload, signature) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let parsed: serde_json::Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+    if parsed["alg"] != "RS256" {
+        return None;
+    }
+
+    let signature = Signature::try_from(URL_SAFE_NO_PAD.decode(signature).ok()?.as_slice()).ok()?;
+    let signing_input = &token[..header.len() + 1 + payload.len()];
+    VerifyingKey::<Sha256>::new(key.clone())
+        .verify(signing_input.as_bytes(), &signature)
+        .ok()?;
+
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()
+}
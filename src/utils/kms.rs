@@ -0,0 +1,117 @@
+//! Envelope encryption for workspace data keys. Each workspace gets a random
+//! AES-256 key that services use for SSE-C; we only ever store it wrapped
+//! under a master key from the config.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use uuid::Uuid;
+
+pub const DATA_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// A key-encryption key. Its id is derived from the key material, so a
+/// wrapped key records which master key it needs without revealing it.
+#[derive(Clone)]
+pub struct MasterKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MasterKey").field("id", &self.id).finish()
+    }
+}
+
+impl MasterKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            id: hex::encode(&Sha256::digest(key)[..8]),
+            key,
+        }
+    }
+
+    /// Parses 32 base64-encoded bytes, as generated by `openssl rand -base64 32`.
+    pub fn from_base64(encoded: &str) -> Result<Self, String> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| format!("invalid base64: {e}"))?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|b: Vec<u8>| format!("expected 32 bytes, got {}", b.len()))?;
+        Ok(Self::new(key))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Wraps `data_key` for `workspace_id`. The workspace is bound in as
+    /// associated data, so a wrapped key copied onto another workspace's row
+    /// won't unwrap.
+    pub fn wrap(&self, workspace_id: &Uuid, data_key: &[u8]) -> Vec<u8> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: data_key,
+                    aad: workspace_id.as_bytes(),
+                },
+            )
+            .expect("AES-GCM encryption cannot fail for a key-sized input");
+        [nonce.as_slice(), &ciphertext].concat()
+    }
+
+    /// Reverses [`MasterKey::wrap`]. `None` if this is the wrong key, the
+    /// wrong workspace, or the bytes were tampered with.
+    pub fn unwrap(&self, workspace_id: &Uuid, wrapped: &[u8]) -> Option<Vec<u8>> {
+        if wrapped.len() <= NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = wrapped.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: workspace_id.as_bytes(),
+                },
+            )
+            .ok()
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(&self.key).expect("master keys are 32 bytes")
+    }
+}
+
+/// The master key new keys are wrapped under, plus retired ones that are
+/// still accepted until a rotation rewraps everything.
+#[derive(Clone, Debug)]
+pub struct Keyring {
+    pub current: MasterKey,
+    pub retired: Vec<MasterKey>,
+}
+
+impl Keyring {
+    pub fn get(&self, id: &str) -> Option<&MasterKey> {
+        std::iter::once(&self.current)
+            .chain(&self.retired)
+            .find(|key| key.id == id)
+    }
+}
+
+pub fn new_data_key() -> Vec<u8> {
+    let mut key = vec![0u8; DATA_KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    key
+}
@@ -68,3 +68,112 @@ pub async fn mail_welcome(target_email: &str, token: &str) -> Result<String, Str
     //     ..Default::default()
     // }).await
 }
+
+pub async fn mail_email_verification(target_email: &str, code: &str) -> Result<String, String> {
+    let link = format!(
+        "{}/user/verify?email={}&code={}",
+        config().public_url,
+        urlencoding::encode(target_email),
+        code
+    );
+    info!(
+        "Fake email to: {} with verification code: {} ({})",
+        target_email, code, link
+    );
+    Ok("Fake email sent.".to_string())
+    // send_email(SendEmail {
+    //     from: "me@mail.noahdunnagan.com".to_string(),
+    //     to: vec![target_email.to_string()],
+    //     subject: "Verify your Ledger email address.".to_string(),
+    //     text: Some(format!("Confirm this address to activate your Ledger account. \n\nYour verification code is: {} \n\nOr open this link: {}", code, link)),
+    //     ..Default::default()
+    // }).await
+}
+
+pub async fn mail_email_change_notice(
+    target_email: &str,
+    new_email: &str,
+) -> Result<String, String> {
+    info!(
+        "Fake email to: {} about a change of address to: {}",
+        target_email, new_email
+    );
+    Ok("Fake email sent.".to_string())
+    // send_email(SendEmail {
+    //     from: "me@mail.noahdunnagan.com".to_string(),
+    //     to: vec![target_email.to_string()],
+    //     subject: "Your Ledger email address is changing.".to_string(),
+    //     text: Some(format!("Someone asked to move your Ledger account to {}. The change happens once that address is verified. If this wasn't you, recover your access and contact support.", new_email)),
+    //     ..Default::default()
+    // }).await
+}
+
+pub async fn mail_recovery_link(target_email: &str, token: &str) -> Result<String, String> {
+    let link = format!(
+        "{}/user/recover/redeem?token={}",
+        config().public_url,
+        urlencoding::encode(token)
+    );
+    info!("Fake email to: {} with recovery link: {}", target_email, link);
+    Ok("Fake email sent.".to_string())
+    // send_email(SendEmail {
+    //     from: "me@mail.noahdunnagan.com".to_string(),
+    //     to: vec![target_email.to_string()],
+    //     subject: "Recover your Ledger access.".to_string(),
+    //     text: Some(format!("Someone asked to recover access to your Ledger account. If this was you, open the link below within 15 minutes to get a new access token. It can only be used once. \n\n{}\n\nIf this wasn't you, you can ignore this email.", link)),
+    //     ..Default::default()
+    // }).await
+}
+
+pub async fn mail_password_token(target_email: &str, token: &str) -> Result<String, String> {
+    info!(
+        "Fake email to: {} with set-password token: {}",
+        target_email, token
+    );
+    Ok("Fake email sent.".to_string())
+    // send_email(SendEmail {
+    //     from: "me@mail.noahdunnagan.com".to_string(),
+    //     to: vec![target_email.to_string()],
+    //     subject: "Set your Ledger password.".to_string(),
+    //     text: Some(format!("Someone asked to set a password on your Ledger account. If this was you, send the token below to {}/user/password within 30 minutes. It can only be used once. \n\n{}\n\nIf this wasn't you, you can ignore this email.", config().public_url, token)),
+    //     ..Default::default()
+    // }).await
+}
+
+pub async fn mail_team_invite(
+    target_email: &str,
+    team_name: &str,
+    token: &str,
+) -> Result<String, String> {
+    info!(
+        "Fake email to: {} with invite to team {}: {}",
+        target_email, team_name, token
+    );
+    Ok("Fake email sent.".to_string())
+    // send_email(SendEmail {
+    //     from: "me@mail.noahdunnagan.com".to_string(),
+    //     to: vec![target_email.to_string()],
+    //     subject: format!("You're invited to {} on Ledger.", team_name),
+    //     text: Some(format!("You've been invited to join the {} team on Ledger. To accept, send the token below to {}/teams/invites/accept within 7 days. An account is created for you if you don't have one yet. It can only be used once. \n\n{}\n\nIf you weren't expecting this, you can ignore this email.", team_name, config().public_url, token)),
+    //     ..Default::default()
+    // }).await
+}
+
+pub async fn mail_team_deletion_code(
+    target_email: &str,
+    team_name: &str,
+    code: &str,
+) -> Result<String, String> {
+    info!(
+        "Fake email to: {} with deletion code for team {}: {}",
+        target_email, team_name, code
+    );
+    Ok("Fake email sent.".to_string())
+    // send_email(SendEmail {
+    //     from: "me@mail.noahdunnagan.com".to_string(),
+    //     to: vec![target_email.to_string()],
+    //     subject: format!("Confirm deleting {} on Ledger.", team_name),
+    //     text: Some(format!("Someone asked to delete the {} team, along with its memberships and pending invites. This can't be undone. \n\nIf this was you, confirm within 15 minutes with this code: {}\n\nIf this wasn't you, you can ignore this email.", team_name, code)),
+    //     ..Default::default()
+    // }).await
+}
@@ -1,3 +1,21 @@
+pub mod csrf;
+pub mod elevation;
+pub mod federation;
+pub mod jwt;
+pub mod kms;
 pub mod mail;
+pub mod oidc;
+pub mod pagination;
+pub mod passkey;
+pub mod password;
+pub mod policy;
+pub mod quota;
+pub mod registration;
+pub mod role;
+pub mod scim;
+pub mod scope;
+pub mod team;
 pub mod token;
+pub mod totp;
+pub mod user;
 pub mod webutils;
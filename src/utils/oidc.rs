@@ -0,0 +1,71 @@
+//! Helpers for acting as an OpenID Connect provider.
+
+use crate::{
+    config::config,
+    types::oidc::UserClaims,
+    utils::scope::{SCOPE_EMAIL, SCOPE_PROFILE},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use entity::{oauth_client::Model as ClientModel, user::Model as UserModel};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// The only PKCE method accepted; `plain` offers no protection.
+pub const PKCE_METHOD_S256: &str = "S256";
+
+/// Our issuer identifier, which is also the base of every endpoint URL.
+pub fn issuer() -> String {
+    config().public_url.trim_end_matches('/').to_string()
+}
+
+/// RFC 7636 section 4.1: 43 to 128 unreserved characters.
+pub fn is_valid_code_verifier(verifier: &str) -> bool {
+    (43..=128).contains(&verifier.len())
+        && verifier
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
+}
+
+/// `BASE64URL(SHA256(verifier))`, RFC 7636 section 4.2.
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+pub fn pkce_matches(verifier: &str, challenge: &str) -> bool {
+    is_valid_code_verifier(verifier)
+        && bool::from(
+            pkce_challenge(verifier)
+                .as_bytes()
+                .ct_eq(challenge.as_bytes()),
+        )
+}
+
+pub fn redirect_uris(client: &ClientModel) -> Vec<String> {
+    serde_json::from_value(client.redirect_uris.clone()).unwrap_or_default()
+}
+
+/// Redirect URIs must be absolute, without a fragment, and use https unless
+/// they point at the local machine (RFC 8252 section 7.3).
+pub fn is_valid_redirect_uri(uri: &str) -> bool {
+    let Ok(url) = url::Url::parse(uri) else {
+        return false;
+    };
+    if url.fragment().is_some() {
+        return false;
+    }
+    match url.scheme() {
+        "https" => true,
+        "http" => matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")),
+        _ => false,
+    }
+}
+
+/// The claims `scopes` entitle a client to see.
+pub fn user_claims(user: &UserModel, scopes: &[String]) -> UserClaims {
+    let has = |scope: &str| scopes.iter().any(|s| s == scope);
+    UserClaims {
+        name: has(SCOPE_PROFILE).then(|| user.name.clone()),
+        email: has(SCOPE_EMAIL).then(|| user.email.clone()),
+        email_verified: has(SCOPE_EMAIL).then(|| user.email_verified_at.is_some()),
+    }
+}
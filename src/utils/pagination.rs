@@ -0,0 +1,39 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+pub const MAX_PAGE_SIZE: u64 = 200;
+
+/// Clamps a requested page size to `1..=MAX_PAGE_SIZE`.
+pub fn page_size(requested: Option<u64>) -> u64 {
+    requested.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Encodes the position of the last row on a page, for listings ordered by
+/// `(created_at, id)`. Opaque and URL-safe.
+pub fn encode_cursor(created_at: &DateTime<Utc>, id: &Uuid) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}|{}", created_at.to_rfc3339(), id))
+}
+
+pub fn decode_cursor(cursor: &str) -> Option<(DateTime<Utc>, Uuid)> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    let raw = String::from_utf8(bytes).ok()?;
+    let (ts, id) = raw.split_once('|')?;
+    let ts = DateTime::parse_from_rfc3339(ts).ok()?.with_timezone(&Utc);
+    let id = Uuid::parse_str(id).ok()?;
+    Some((ts, id))
+}
+
+/// Escapes `%`, `_` and `\` so user input can be used as a literal `LIKE` prefix.
+pub fn like_prefix(input: &str) -> String {
+    let mut out = String::with_capacity(input.len() + 1);
+    for c in input.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('%');
+    out
+}
@@ -0,0 +1,42 @@
+//! WebAuthn relying party used for passkey registration and login.
+
+use crate::{config::config, types::error::AppError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::OnceLock;
+use webauthn_rs::prelude::{Passkey, Url, Webauthn, WebauthnBuilder};
+
+static WEBAUTHN: OnceLock<Webauthn> = OnceLock::new();
+
+/// The relying party built from [`crate::config::WebauthnConfig`].
+pub fn webauthn() -> &'static Webauthn {
+    WEBAUTHN.get_or_init(|| {
+        let settings = &config().webauthn;
+        let origin =
+            Url::parse(&settings.rp_origin).unwrap_or_else(|e| panic!("WEBAUTHN_RP_ORIGIN: {e}"));
+        WebauthnBuilder::new(&settings.rp_id, &origin)
+            .and_then(|builder| builder.rp_name("Ledger").build())
+            .unwrap_or_else(|e| panic!("Invalid WebAuthn relying party settings: {e}"))
+    })
+}
+
+/// Serializes ceremony state or a credential for a JSON column.
+pub fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(value)
+        .map_err(|_| AppError::Internal("There was an issue while storing passkey state.".into()))
+}
+
+/// Reads back what [`to_json`] stored.
+pub fn from_json<T: DeserializeOwned>(value: serde_json::Value) -> Result<T, AppError> {
+    serde_json::from_value(value)
+        .map_err(|_| AppError::Internal("There was an issue while reading passkey state.".into()))
+}
+
+/// Parses every stored credential of a user.
+pub fn parse_passkeys(
+    stored: &[entity::passkey_credential::Model],
+) -> Result<Vec<Passkey>, AppError> {
+    stored
+        .iter()
+        .map(|p| from_json(p.passkey.clone()))
+        .collect()
+}
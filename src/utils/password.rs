@@ -0,0 +1,89 @@
+//! Optional login passwords. Hashing reuses the Argon2 helpers in
+//! [`crate::utils::token`]; this module adds the policy and the
+//! constant-time login check.
+
+use crate::types::error::AppError;
+use crate::utils::token::{encrypt, verify};
+use std::sync::OnceLock;
+
+pub const MIN_PASSWORD_LEN: usize = 12;
+pub const MAX_PASSWORD_LEN: usize = 128;
+
+/// A few passwords that meet the length rule but are still guessed first.
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456789012",
+    "passwordpassword",
+    "password1234",
+    "qwertyuiopas",
+    "qwerty123456",
+    "iloveyou1234",
+    "letmeinletmein",
+    "administrator",
+    "changemechangeme",
+    "welcome12345",
+];
+
+/// Checks a new password against the policy.
+///
+/// Length is counted in characters. The password may not be a well-known
+/// one, a single repeated character, or contain the email's local part.
+pub fn check_password_policy(password: &str, email: &str) -> Result<(), AppError> {
+    let len = password.chars().count();
+    if len < MIN_PASSWORD_LEN {
+        return Err(AppError::Validation(format!(
+            "Password must be at least {MIN_PASSWORD_LEN} characters."
+        )));
+    }
+    if len > MAX_PASSWORD_LEN {
+        return Err(AppError::Validation(format!(
+            "Password must be at most {MAX_PASSWORD_LEN} characters."
+        )));
+    }
+
+    let lowered = password.to_lowercase();
+    if COMMON_PASSWORDS.contains(&lowered.as_str()) {
+        return Err(AppError::Validation("Password is too common.".into()));
+    }
+
+    let mut chars = password.chars();
+    if let Some(first) = chars.next() {
+        if chars.all(|c| c == first) {
+            return Err(AppError::Validation(
+                "Password can't be one repeated character.".into(),
+            ));
+        }
+    }
+
+    if let Some((local, _)) = email.to_lowercase().rsplit_once('@') {
+        if local.len() >= 3 && lowered.contains(local) {
+            return Err(AppError::Validation(
+                "Password can't contain your email address.".into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn hash_password(password: &str) -> Result<String, AppError> {
+    encrypt(password)
+        .map_err(|_| AppError::Internal("There was an issue while hashing the password.".into()))
+}
+
+/// Hash checked when there is no real one, so unknown accounts cost the same.
+fn dummy_hash() -> &'static str {
+    static DUMMY: OnceLock<String> = OnceLock::new();
+    DUMMY.get_or_init(|| encrypt("ledger-dummy-password").expect("hashing a constant"))
+}
+
+/// Checks a login password. Always runs exactly one Argon2 verification,
+/// whether or not the account has a password, so timing reveals nothing.
+pub fn password_matches(password: &str, stored: Option<&str>) -> bool {
+    match stored {
+        Some(hash) => verify(password, hash).unwrap_or(false),
+        None => {
+            let _ = verify(password, dummy_hash());
+            false
+        }
+    }
+}
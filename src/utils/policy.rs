@@ -0,0 +1,317 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// Scope that covers every resource.
+pub const GLOBAL_SCOPE: &str = "*";
+
+/// Session auth methods that involved a second factor. Passkeys verify the
+/// user on the authenticator itself.
+const MULTI_FACTOR_METHODS: &[&str] = &["passkey", "password+otp"];
+
+/// What is known about the request being authorized. Built from the HTTP
+/// request or the gRPC context; evaluating against it needs no I/O.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestContext {
+    pub source_ip: Option<IpAddr>,
+    pub time: DateTime<Utc>,
+    /// Time since the credential was issued, when it is known.
+    pub token_age: Option<Duration>,
+    /// How the credential was obtained, e.g. `passkey` or `apikey`.
+    pub auth_method: Option<String>,
+}
+
+impl RequestContext {
+    /// A context at `time` about which nothing else is known.
+    pub fn at(time: DateTime<Utc>) -> Self {
+        Self {
+            source_ip: None,
+            time,
+            token_age: None,
+            auth_method: None,
+        }
+    }
+}
+
+/// A requirement a binding places on the request context. A binding only
+/// applies when every one of its conditions holds; unknown context fails.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BindingCondition {
+    /// Source address inside one of the ranges, e.g. `10.20.0.0/16`.
+    SourceIp { cidrs: Vec<String> },
+    /// Request time within `[start, end)` UTC, wrapping past midnight when
+    /// `start > end`. An empty `weekdays` means every day.
+    TimeWindow {
+        start: NaiveTime,
+        end: NaiveTime,
+        #[serde(default)]
+        weekdays: Vec<Weekday>,
+    },
+    /// Credential issued at most this long ago.
+    MaxTokenAge { seconds: i64 },
+    /// Credential obtained through one of these methods.
+    AuthMethod { methods: Vec<String> },
+    /// Credential obtained with a second factor at most this long ago.
+    MfaWithin { seconds: i64 },
+}
+
+impl BindingCondition {
+    pub fn holds(&self, ctx: &RequestContext) -> bool {
+        match self {
+            BindingCondition::SourceIp { cidrs } => ctx
+                .source_ip
+                .is_some_and(|ip| cidrs.iter().any(|cidr| cidr_contains(cidr, ip))),
+            BindingCondition::TimeWindow {
+                start,
+                end,
+                weekdays,
+            } => {
+                let now = ctx.time.time();
+                let in_window = if start <= end {
+                    *start <= now && now < *end
+                } else {
+                    now >= *start || now < *end
+                };
+                in_window && (weekdays.is_empty() || weekdays.contains(&ctx.time.weekday()))
+            }
+            BindingCondition::MaxTokenAge { seconds } => {
+                token_age_within(ctx, Duration::seconds(*seconds))
+            }
+            BindingCondition::AuthMethod { methods } => ctx
+                .auth_method
+                .as_deref()
+                .is_some_and(|method| methods.iter().any(|m| m == method)),
+            BindingCondition::MfaWithin { seconds } => {
+                ctx.auth_method.as_deref().is_some_and(is_multi_factor)
+                    && token_age_within(ctx, Duration::seconds(*seconds))
+            }
+        }
+    }
+
+    /// Why the condition can never be evaluated, if it is malformed.
+    pub fn problem(&self) -> Option<String> {
+        match self {
+            BindingCondition::SourceIp { cidrs } if cidrs.is_empty() => {
+                Some("source_ip needs at least one range.".into())
+            }
+            BindingCondition::SourceIp { cidrs } => cidrs
+                .iter()
+                .find(|cidr| parse_cidr(cidr).is_none())
+                .map(|bad| format!("Invalid CIDR range: {bad}")),
+            BindingCondition::TimeWindow { start, end, .. } if start == end => {
+                Some("time_window start and end must differ.".into())
+            }
+            BindingCondition::MaxTokenAge { seconds } | BindingCondition::MfaWithin { seconds }
+                if *seconds <= 0 =>
+            {
+                Some("Durations must be positive.".into())
+            }
+            BindingCondition::AuthMethod { methods } if methods.is_empty() => {
+                Some("auth_method needs at least one method.".into())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn token_age_within(ctx: &RequestContext, max: Duration) -> bool {
+    ctx.token_age
+        .is_some_and(|age| age >= Duration::zero() && age <= max)
+}
+
+pub fn is_multi_factor(auth_method: &str) -> bool {
+    MULTI_FACTOR_METHODS.contains(&auth_method)
+}
+
+/// Parses `addr/len`, or a bare address as a single-host range.
+fn parse_cidr(cidr: &str) -> Option<(IpAddr, u32)> {
+    let (addr, len) = match cidr.trim().split_once('/') {
+        Some((addr, len)) => (addr.parse::<IpAddr>().ok()?, Some(len.parse::<u32>().ok()?)),
+        None => (cidr.trim().parse::<IpAddr>().ok()?, None),
+    };
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let len = len.unwrap_or(max);
+    (len <= max).then_some((addr, len))
+}
+
+/// True if `ip` falls inside `cidr`. IPv4-mapped IPv6 addresses match IPv4
+/// ranges. Malformed ranges match nothing.
+pub fn cidr_contains(cidr: &str, ip: IpAddr) -> bool {
+    let Some((net, len)) = parse_cidr(cidr) else {
+        return false;
+    };
+    match (net, ip.to_canonical()) {
+        (IpAddr::V4(net), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+            u32::from(net) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(net), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - len).unwrap_or(0);
+            u128::from(net) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+/// A role binding that applies to the subject, with the permissions its role
+/// grants. Loaded from the database; evaluating it needs no I/O.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grant {
+    pub binding_id: Uuid,
+    pub scope: String,
+    pub permissions: Vec<String>,
+    pub conditions: Vec<BindingCondition>,
+}
+
+/// An ACL entry naming the subject, directly or through a team.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AclGrant {
+    pub entry_id: Uuid,
+    pub resource: String,
+    pub permission: String,
+    /// Also applies to everything beneath `resource`.
+    pub inherited: bool,
+}
+
+impl AclGrant {
+    pub fn applies_to(&self, resource: &str) -> bool {
+        self.resource == resource || (self.inherited && scope_covers(&self.resource, resource))
+    }
+}
+
+/// Everything that may let a subject act: role bindings, and the ACL entries
+/// on the resource in question and its ancestors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Access {
+    pub grants: Vec<Grant>,
+    pub acl: Vec<AclGrant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// Allowed by the given binding.
+    Allow {
+        binding_id: Uuid,
+    },
+    /// Allowed by the given ACL entry.
+    AllowAcl {
+        entry_id: Uuid,
+    },
+    Deny,
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        !matches!(self, Decision::Deny)
+    }
+}
+
+/// Decides whether a subject with `access` may perform `action` on
+/// `resource`. Swap implementations to change the model without touching
+/// storage or transport.
+pub trait Policy: Send + Sync {
+    fn authorize(
+        &self,
+        access: &Access,
+        action: &str,
+        resource: &str,
+        ctx: &RequestContext,
+    ) -> Decision;
+}
+
+/// Default policy: allow if some binding's scope covers the resource, its
+/// role grants the action, and all of its conditions hold; otherwise if an
+/// ACL entry on the resource, or an inherited one above it, grants the action.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RbacPolicy;
+
+impl Policy for RbacPolicy {
+    fn authorize(
+        &self,
+        access: &Access,
+        action: &str,
+        resource: &str,
+        ctx: &RequestContext,
+    ) -> Decision {
+        let binding = access.grants.iter().find(|grant| {
+            scope_covers(&grant.scope, resource)
+                && grant
+                    .permissions
+                    .iter()
+                    .any(|p| permission_covers(p, action))
+                && grant.conditions.iter().all(|c| c.holds(ctx))
+        });
+        if let Some(grant) = binding {
+            return Decision::Allow {
+                binding_id: grant.binding_id,
+            };
+        }
+
+        access
+            .acl
+            .iter()
+            .find(|entry| {
+                entry.applies_to(resource) && permission_covers(&entry.permission, action)
+            })
+            .map_or(Decision::Deny, |entry| Decision::AllowAcl {
+                entry_id: entry.entry_id,
+            })
+    }
+}
+
+/// The resource and each of its ancestors, outermost first:
+/// `a/b/c` gives `a`, `a/b`, `a/b/c`.
+pub fn resource_ancestors(resource: &str) -> Vec<String> {
+    resource
+        .match_indices('/')
+        .map(|(i, _)| resource[..i].to_string())
+        .chain(std::iter::once(resource.to_string()))
+        .collect()
+}
+
+/// True if `scope` is the resource itself or one of its ancestors, so
+/// `workspace:a` covers `workspace:a/file:b` but not `workspace:ab`.
+pub fn scope_covers(scope: &str, resource: &str) -> bool {
+    scope == GLOBAL_SCOPE
+        || resource == scope
+        || resource
+            .strip_prefix(scope)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// True if `permission` grants `action`, directly or through a wildcard.
+pub fn permission_covers(permission: &str, action: &str) -> bool {
+    if permission == "*" || permission == action {
+        return true;
+    }
+    permission
+        .strip_suffix('*')
+        .filter(|prefix| prefix.ends_with('.'))
+        .is_some_and(|prefix| action.starts_with(prefix))
+}
+
+/// Dotted lowercase segments, optionally ending in `.*`; or `*` alone.
+pub fn is_valid_permission(name: &str) -> bool {
+    if name == "*" {
+        return true;
+    }
+    let name = name.strip_suffix(".*").unwrap_or(name);
+    !name.is_empty()
+        && name.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        })
+}
+
+/// `*`, or `/`-separated non-empty segments without whitespace.
+pub fn is_valid_resource(resource: &str) -> bool {
+    resource == GLOBAL_SCOPE
+        || (!resource.is_empty()
+            && resource
+                .split('/')
+                .all(|segment| !segment.is_empty() && !segment.contains(char::is_whitespace)))
+}
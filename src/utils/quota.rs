@@ -0,0 +1,95 @@
+//! Storage quotas: what a plan allows and whether a reservation fits.
+
+use serde::{Deserialize, Serialize};
+
+/// Caps from a plan. `None` is unlimited; a subject without a plan has no
+/// caps at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub max_bytes: Option<i64>,
+    pub max_files: Option<i64>,
+    pub max_share_links: Option<i64>,
+}
+
+/// Counters of what is in use, also used for the amounts reserved or
+/// released in one call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub bytes: i64,
+    pub files: i64,
+    pub share_links: i64,
+}
+
+/// The limit a reservation would go over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exceeded {
+    Bytes,
+    Files,
+    ShareLinks,
+}
+
+impl Exceeded {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Bytes => "storage quota exceeded",
+            Self::Files => "file quota exceeded",
+            Self::ShareLinks => "share link quota exceeded",
+        }
+    }
+}
+
+impl Usage {
+    pub fn is_negative(&self) -> bool {
+        self.bytes < 0 || self.files < 0 || self.share_links < 0
+    }
+
+    pub fn plus(&self, delta: &Usage) -> Usage {
+        Usage {
+            bytes: self.bytes.saturating_add(delta.bytes),
+            files: self.files.saturating_add(delta.files),
+            share_links: self.share_links.saturating_add(delta.share_links),
+        }
+    }
+
+    /// Subtracts `delta`, stopping at zero so a double release can't hand
+    /// out extra room.
+    pub fn minus(&self, delta: &Usage) -> Usage {
+        Usage {
+            bytes: self.bytes.saturating_sub(delta.bytes).max(0),
+            files: self.files.saturating_sub(delta.files).max(0),
+            share_links: self.share_links.saturating_sub(delta.share_links).max(0),
+        }
+    }
+}
+
+impl Limits {
+    /// The first limit that reserving `delta` on top of `usage` would go
+    /// over. Only counters that grow are checked, so a subject already over
+    /// a lowered limit can still use what its plan has room for.
+    pub fn exceeded(&self, usage: &Usage, delta: &Usage) -> Option<Exceeded> {
+        let after = usage.plus(delta);
+        let over = |max: Option<i64>, grows: i64, total: i64| {
+            grows > 0 && max.is_some_and(|max| total > max)
+        };
+        if over(self.max_bytes, delta.bytes, after.bytes) {
+            Some(Exceeded::Bytes)
+        } else if over(self.max_files, delta.files, after.files) {
+            Some(Exceeded::Files)
+        } else if over(self.max_share_links, delta.share_links, after.share_links) {
+            Some(Exceeded::ShareLinks)
+        } else {
+            None
+        }
+    }
+}
+
+/// Feature flags are lowercase words joined by `_` or `.`, e.g. `public_links`.
+pub fn is_valid_feature(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        })
+}
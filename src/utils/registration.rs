@@ -0,0 +1,103 @@
+use crate::config::{RegistrationConfig, RegistrationMode};
+use crate::types::error::AppError;
+
+static DISPOSABLE_DOMAINS: &str = include_str!("disposable_domains.txt");
+
+/// How an account is being created, which decides how much of the policy applies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignupChannel {
+    /// Created by an admin through `/user/create`.
+    Admin,
+    /// Public signup that redeemed an invite code.
+    Invite,
+    /// Public signup without a code.
+    SelfService,
+    /// An existing user moving to a new address.
+    EmailChange,
+    /// Created on first login through a configured identity provider.
+    Federated,
+}
+
+/// Checks an email against the registration policy.
+///
+/// Denied and disposable domains are refused on every channel. The allow-list
+/// limits everything but admins, and the mode only limits public signups; the
+/// identity provider stands in for the mode on federated logins.
+pub fn check_registration(
+    policy: &RegistrationConfig,
+    email: &str,
+    channel: SignupChannel,
+) -> Result<(), AppError> {
+    let domain = email_domain(email)
+        .ok_or_else(|| AppError::Validation("A valid email address is required.".into()))?;
+
+    if domain_listed(&domain, policy.denied_domains.iter().map(String::as_str)) {
+        return Err(AppError::Forbidden);
+    }
+
+    if policy.block_disposable && is_disposable(&domain) {
+        return Err(AppError::Forbidden);
+    }
+
+    if channel == SignupChannel::Admin {
+        return Ok(());
+    }
+
+    let allowed = policy.allowed_domains.is_empty()
+        || domain_listed(&domain, policy.allowed_domains.iter().map(String::as_str));
+
+    if matches!(
+        channel,
+        SignupChannel::EmailChange | SignupChannel::Federated
+    ) {
+        return if allowed {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        };
+    }
+
+    match (policy.mode, channel) {
+        (RegistrationMode::Closed, _) => Err(AppError::Forbidden),
+        (RegistrationMode::InviteOnly, SignupChannel::SelfService) => Err(AppError::BadRequest(
+            "An invite code is required to sign up.".into(),
+        )),
+        (RegistrationMode::DomainRestricted, SignupChannel::SelfService)
+            if policy.allowed_domains.is_empty() =>
+        {
+            Err(AppError::Forbidden)
+        }
+        _ if !allowed => Err(AppError::Forbidden),
+        _ => Ok(()),
+    }
+}
+
+/// Lowercased domain part of an email address, if it has one.
+pub fn email_domain(email: &str) -> Option<String> {
+    let (local, domain) = email.trim().rsplit_once('@')?;
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    if local.is_empty() || domain.is_empty() || !domain.contains('.') {
+        return None;
+    }
+    Some(domain)
+}
+
+pub fn is_disposable(domain: &str) -> bool {
+    domain_listed(
+        domain,
+        DISPOSABLE_DOMAINS
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#')),
+    )
+}
+
+/// True if `domain` is one of `list` or a subdomain of one.
+fn domain_listed<'a>(domain: &str, list: impl IntoIterator<Item = &'a str>) -> bool {
+    list.into_iter().any(|entry| {
+        domain == entry
+            || domain
+                .strip_suffix(entry)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
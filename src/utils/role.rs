@@ -0,0 +1,25 @@
+//! Account roles, from most to least privileged: admin, member, read-only.
+
+use entity::user::UserRole;
+
+fn rank(role: UserRole) -> u8 {
+    match role {
+        UserRole::Admin => 2,
+        UserRole::Member => 1,
+        UserRole::ReadOnly => 0,
+    }
+}
+
+/// True if `role` grants at least what `required` does.
+pub fn role_satisfies(role: UserRole, required: UserRole) -> bool {
+    rank(role) >= rank(required)
+}
+
+/// Stable name used outside HTTP, e.g. in gRPC validation.
+pub fn role_name(role: UserRole) -> &'static str {
+    match role {
+        UserRole::Admin => "admin",
+        UserRole::Member => "member",
+        UserRole::ReadOnly => "read_only",
+    }
+}
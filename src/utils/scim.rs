@@ -0,0 +1,318 @@
+//! SCIM 2.0 helpers: the filter subset we support, PATCH interpretation, and
+//! mapping accounts to User resources.
+
+use crate::{
+    config::config,
+    types::scim::{
+        ScimEmail, ScimError, ScimMeta, ScimName, ScimPatchOperation, ScimUser, SCHEMA_USER,
+    },
+};
+use entity::user::{Model as UserModel, UserStatus};
+use serde_json::Value;
+
+/// Attributes that can be filtered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScimAttr {
+    /// `userName` and `emails.value`; both are the account email.
+    UserName,
+    /// `displayName` and `name.formatted`.
+    DisplayName,
+    Active,
+    Id,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScimOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScimValue {
+    Str(String),
+    Bool(bool),
+}
+
+/// One `attr op value` comparison. A filter is a list of them joined by `and`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimFilter {
+    pub attr: ScimAttr,
+    pub op: ScimOp,
+    pub value: ScimValue,
+}
+
+/// Splits on whitespace outside double quotes; quoted tokens keep their quotes.
+fn tokenize(raw: &str) -> Result<Vec<String>, ScimError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut chars = raw.chars();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            '\\' if quoted => match chars.next() {
+                Some(escaped) => current.push(escaped),
+                None => break,
+            },
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(ScimError::InvalidFilter("Unterminated string.".into()));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// Drops the schema URN some clients put in front of core attributes.
+fn attr_name(raw: &str) -> String {
+    raw.strip_prefix(SCHEMA_USER)
+        .map(|rest| rest.trim_start_matches(':'))
+        .unwrap_or(raw)
+        .to_ascii_lowercase()
+}
+
+/// Parses the subset of RFC 7644 section 3.4.2.2 filters identity providers
+/// send: comparisons on `userName`, `emails.value`, `displayName`,
+/// `name.formatted`, `active` and `id`, joined by `and`.
+pub fn parse_filter(raw: &str) -> Result<Vec<ScimFilter>, ScimError> {
+    let tokens = tokenize(raw)?;
+    let mut filters = Vec::new();
+    let mut rest = tokens.as_slice();
+
+    loop {
+        let [attr, op, value, tail @ ..] = rest else {
+            return Err(ScimError::InvalidFilter(
+                "Expected `attribute operator value`.".into(),
+            ));
+        };
+
+        let attr = match attr_name(attr).as_str() {
+            "username" | "emails.value" | "emails" => ScimAttr::UserName,
+            "displayname" | "name.formatted" => ScimAttr::DisplayName,
+            "active" => ScimAttr::Active,
+            "id" => ScimAttr::Id,
+            other => {
+                return Err(ScimError::InvalidFilter(format!(
+                    "Filtering on `{other}` is not supported."
+                )))
+            }
+        };
+        let op = match op.to_ascii_lowercase().as_str() {
+            "eq" => ScimOp::Eq,
+            "ne" => ScimOp::Ne,
+            "co" => ScimOp::Co,
+            "sw" => ScimOp::Sw,
+            "ew" => ScimOp::Ew,
+            other => {
+                return Err(ScimError::InvalidFilter(format!(
+                    "Operator `{other}` is not supported."
+                )))
+            }
+        };
+        let value = match value.as_str() {
+            v if v.len() >= 2 && v.starts_with('"') && v.ends_with('"') => {
+                ScimValue::Str(v[1..v.len() - 1].to_string())
+            }
+            v if v.eq_ignore_ascii_case("true") => ScimValue::Bool(true),
+            v if v.eq_ignore_ascii_case("false") => ScimValue::Bool(false),
+            v => {
+                return Err(ScimError::InvalidFilter(format!(
+                    "Unsupported value `{v}`."
+                )))
+            }
+        };
+
+        let well_typed = match (attr, &value) {
+            (ScimAttr::Active, ScimValue::Bool(_)) => matches!(op, ScimOp::Eq | ScimOp::Ne),
+            (ScimAttr::Active, _) | (_, ScimValue::Bool(_)) => false,
+            (ScimAttr::Id, _) => matches!(op, ScimOp::Eq | ScimOp::Ne),
+            _ => true,
+        };
+        if !well_typed {
+            return Err(ScimError::InvalidFilter(
+                "Operator or value does not fit the attribute.".into(),
+            ));
+        }
+        filters.push(ScimFilter { attr, op, value });
+
+        match tail {
+            [] => return Ok(filters),
+            [and, more @ ..] if and.eq_ignore_ascii_case("and") => rest = more,
+            _ => {
+                return Err(ScimError::InvalidFilter(
+                    "Only `and` is supported between comparisons.".into(),
+                ))
+            }
+        }
+    }
+}
+
+/// What a PATCH asks to change. `None` leaves the field alone.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScimUserChanges {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// Reads a PATCH request (RFC 7644 section 3.5.2) into the changes Ledger
+/// can make. Attributes Ledger does not store are ignored.
+pub fn patch_changes(ops: &[ScimPatchOperation]) -> Result<ScimUserChanges, ScimError> {
+    let mut changes = ScimUserChanges::default();
+    for op in ops {
+        match op.op.to_ascii_lowercase().as_str() {
+            "add" | "replace" => {}
+            "remove" => {
+                return Err(ScimError::Mutability(
+                    "Attributes can be replaced but not removed.".into(),
+                ))
+            }
+            other => {
+                return Err(ScimError::InvalidSyntax(format!(
+                    "Unknown operation `{other}`."
+                )))
+            }
+        }
+        let value = op
+            .value
+            .as_ref()
+            .ok_or_else(|| ScimError::InvalidSyntax("A value is required.".into()))?;
+
+        match &op.path {
+            Some(path) => apply_attr(&mut changes, path, value)?,
+            None => {
+                let Value::Object(attrs) = value else {
+                    return Err(ScimError::InvalidSyntax(
+                        "Without a path, the value must be an object.".into(),
+                    ));
+                };
+                for (path, value) in attrs {
+                    apply_attr(&mut changes, path, value)?;
+                }
+            }
+        }
+    }
+    Ok(changes)
+}
+
+fn apply_attr(changes: &mut ScimUserChanges, path: &str, value: &Value) -> Result<(), ScimError> {
+    let invalid = || ScimError::InvalidValue(format!("Unexpected value for `{path}`."));
+    let path = attr_name(path);
+
+    match path.as_str() {
+        // Some providers send booleans as strings.
+        "active" => {
+            changes.active = Some(match value {
+                Value::Bool(b) => *b,
+                Value::String(s) if s.eq_ignore_ascii_case("true") => true,
+                Value::String(s) if s.eq_ignore_ascii_case("false") => false,
+                _ => return Err(invalid()),
+            })
+        }
+        "displayname" | "name.formatted" => {
+            changes.name = Some(value.as_str().ok_or_else(invalid)?.to_string())
+        }
+        "name" => {
+            let name: ScimName = serde_json::from_value(value.clone()).map_err(|_| invalid())?;
+            changes.name = formatted_name(&name);
+        }
+        "username" => changes.email = Some(value.as_str().ok_or_else(invalid)?.to_string()),
+        p if p.starts_with("emails") => {
+            let email = match value {
+                Value::String(s) => s.clone(),
+                Value::Array(_) => {
+                    let emails: Vec<ScimEmail> =
+                        serde_json::from_value(value.clone()).map_err(|_| invalid())?;
+                    primary_email(&emails).ok_or_else(invalid)?
+                }
+                _ => return Err(invalid()),
+            };
+            changes.email = Some(email);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn formatted_name(name: &ScimName) -> Option<String> {
+    name.formatted.clone().or_else(|| {
+        let parts: Vec<&str> = [&name.given_name, &name.family_name]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    })
+}
+
+fn primary_email(emails: &[ScimEmail]) -> Option<String> {
+    emails
+        .iter()
+        .find(|e| e.primary)
+        .or_else(|| emails.first())
+        .map(|e| e.value.clone())
+}
+
+/// The account email for a User resource: the primary email, else `userName`.
+pub fn resource_email(user: &ScimUser) -> String {
+    primary_email(&user.emails).unwrap_or_else(|| user.user_name.clone())
+}
+
+/// The display name for a User resource, falling back to the email.
+pub fn resource_name(user: &ScimUser) -> String {
+    user.display_name
+        .clone()
+        .or_else(|| user.name.as_ref().and_then(formatted_name))
+        .unwrap_or_else(|| resource_email(user))
+}
+
+/// Suspended accounts are the only inactive ones; pending and locked
+/// accounts still belong to someone who works here.
+pub fn is_active(user: &UserModel) -> bool {
+    user.status != UserStatus::Suspended
+}
+
+pub fn to_scim_user(user: &UserModel) -> ScimUser {
+    ScimUser {
+        schemas: vec![SCHEMA_USER.to_string()],
+        id: Some(user.id.to_string()),
+        user_name: user.email.clone(),
+        name: Some(ScimName {
+            formatted: Some(user.name.clone()),
+            given_name: None,
+            family_name: None,
+        }),
+        display_name: Some(user.name.clone()),
+        emails: vec![ScimEmail {
+            value: user.email.clone(),
+            primary: true,
+            kind: Some("work".to_string()),
+        }],
+        active: is_active(user),
+        meta: Some(ScimMeta {
+            resource_type: "User".to_string(),
+            created: user.created_at,
+            last_modified: user.updated_at,
+            location: format!(
+                "{}/scim/v2/Users/{}",
+                config().public_url.trim_end_matches('/'),
+                user.id
+            ),
+        }),
+    }
+}
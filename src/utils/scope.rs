@@ -0,0 +1,51 @@
+//! Scopes carried by API keys.
+
+/// Read files and metadata through Ledger services.
+pub const SCOPE_FILES_READ: &str = "files:read";
+/// Upload, change and delete files.
+pub const SCOPE_FILES_WRITE: &str = "files:write";
+/// Manage the account itself (the `/user` routes).
+pub const SCOPE_ACCOUNT: &str = "account";
+
+pub const API_KEY_SCOPES: &[&str] = &[SCOPE_FILES_READ, SCOPE_FILES_WRITE, SCOPE_ACCOUNT];
+
+/// Granted when a device asks for nothing in particular.
+pub const DEFAULT_API_KEY_SCOPES: &[&str] = &[SCOPE_FILES_READ, SCOPE_FILES_WRITE];
+
+/// Parses a space-separated scope string against `known`, dropping duplicates.
+///
+/// Returns `None` if any scope is unknown. An empty string yields `default`.
+pub fn parse_scopes(raw: Option<&str>, known: &[&str], default: &[&str]) -> Option<Vec<String>> {
+    let raw = raw.unwrap_or_default().trim();
+    if raw.is_empty() {
+        return Some(default.iter().map(|s| s.to_string()).collect());
+    }
+
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in raw.split_whitespace() {
+        if !known.contains(&scope) {
+            return None;
+        }
+        if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+    Some(scopes)
+}
+
+pub fn join_scopes(scopes: &[String]) -> String {
+    scopes.join(" ")
+}
+
+pub fn split_scopes(stored: &str) -> Vec<String> {
+    stored.split_whitespace().map(str::to_string).collect()
+}
+
+/// OpenID Connect: required for an ID token.
+pub const SCOPE_OPENID: &str = "openid";
+/// OpenID Connect: the `name` claim.
+pub const SCOPE_PROFILE: &str = "profile";
+/// OpenID Connect: the `email` and `email_verified` claims.
+pub const SCOPE_EMAIL: &str = "email";
+
+pub const OIDC_SCOPES: &[&str] = &[SCOPE_OPENID, SCOPE_PROFILE, SCOPE_EMAIL];
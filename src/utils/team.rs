@@ -0,0 +1,41 @@
+use crate::db::postgres_service::PostgresService;
+use crate::types::error::AppError;
+use entity::team_membership::{Model as MembershipModel, TeamRole};
+use uuid::Uuid;
+
+pub const MAX_TEAM_NAME_LEN: usize = 100;
+
+/// Trims a team name and checks it is usable.
+pub fn team_name(raw: &str) -> Result<String, AppError> {
+    let name = raw.trim();
+    if name.is_empty() || name.chars().count() > MAX_TEAM_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Team names must be 1 to {MAX_TEAM_NAME_LEN} characters."
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// The caller's membership. Outsiders get `NotFound` so team ids don't leak.
+pub async fn membership(
+    db: &PostgresService,
+    team_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<MembershipModel, AppError> {
+    db.get_team_membership(team_id, user_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Like [`membership`], but only owners get through.
+pub async fn owner_membership(
+    db: &PostgresService,
+    team_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<MembershipModel, AppError> {
+    let membership = membership(db, team_id, user_id).await?;
+    if membership.role != TeamRole::Owner {
+        return Err(AppError::Forbidden);
+    }
+    Ok(membership)
+}
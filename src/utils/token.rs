@@ -1,11 +1,21 @@
-use crate::{db::postgres_service::PostgresService, types::token::TokenType};
+use crate::utils::{elevation::effective_role, scope::split_scopes};
+use crate::{
+    db::postgres_service::PostgresService,
+    types::{
+        auth::{ActiveElevation, AuthenticatedUser},
+        token::{TokenStatus, TokenType},
+    },
+};
 use anyhow::Result as AResult;
 use argon2::{
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, prelude::BASE64_STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use entity::user::UserStatus;
 use rand_core::{OsRng, RngCore};
+use tracing::warn;
 use uuid::Uuid;
 
 pub fn new_id() -> Uuid {
@@ -16,6 +26,12 @@ pub fn new_nanoid(len: usize) -> String {
     nanoid::nanoid!(len, &nanoid::alphabet::SAFE).to_string()
 }
 
+/// Generates a random code made only of digits, for codes users type by hand.
+pub fn new_numeric_code(len: usize) -> String {
+    const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+    nanoid::nanoid!(len, &DIGITS)
+}
+
 pub fn new_token(token_type: TokenType) -> String {
     let mut buf = [0u8; 32];
     let mut rng = OsRng;
@@ -49,74 +65,158 @@ pub fn decrypt_from_base64(encoded: &str) -> AResult<String> {
 
 /// Validates a user token.
 ///
+/// Shorthand for [`check_token`] when only a yes/no answer is needed.
+///
+/// # Example
+/// ```ignore
+/// let valid = token_valid(&db, "YmFkYmVlZi4xMjM0").await;
+/// assert!(!valid);
+/// ```
+pub async fn token_valid(db: &PostgresService, b64_token: &str) -> bool {
+    check_token(db, b64_token).await.is_valid()
+}
+
+/// Checks a user token and reports why it was refused.
+///
 /// # Arguments
 /// * `db` - Reference to the PostgresService used to fetch the stored token.
 /// * `b64_token` - A base64-encoded token string in the format `<uuid>.<raw_token>`.
 ///
 /// # Returns
-/// `true` if:
+/// [`TokenStatus::Valid`] if:
 /// - the base64 string decodes successfully,
 /// - the first part is a valid UUID,
-/// - a token for that UUID exists in the database,
-/// - and the provided raw token matches the stored encrypted token.
+/// - a user with that UUID exists in the database,
+/// - the provided raw token matches the stored encrypted token,
+/// - and the account status is active.
 ///
-/// Otherwise, returns `false`.
+/// Session tokens (raw part prefixed with `session_`) and named API keys
+/// (`apikey_`) carry their own id instead of a user id; they must also be
+/// live (not expired or revoked). API keys come back with their scopes.
 ///
-/// # Example
-/// ```ignore
-/// let valid = token_valid(&db, "YmFkYmVlZi4xMjM0").await;
-/// assert!(!valid);
-/// ```
-pub async fn token_valid(db: &PostgresService, b64_token: &str) -> bool {
-    let token = match decrypt_from_base64(b64_token) {
-        Ok(token) => token,
-        Err(_) => {
-            return false;
-        }
+/// A matching token on an inactive account reports the account status
+/// ([`TokenStatus::Pending`], [`TokenStatus::Suspended`], [`TokenStatus::Locked`]);
+/// anything else is [`TokenStatus::Invalid`].
+pub async fn check_token(db: &PostgresService, b64_token: &str) -> TokenStatus {
+    let (id, raw_token) = match extract_token_parts(b64_token) {
+        Some(parts) => parts,
+        None => return TokenStatus::Invalid,
     };
 
-    let mut parts = token.split('.');
+    let session_prefix = format!("{}_", TokenType::Session);
+    let api_key_prefix = format!("{}_", TokenType::ApiKey);
 
-    let id_str = match parts.next() {
-        Some(s) => s,
-        None => {
-            return false;
+    let credential = if raw_token.starts_with(&session_prefix) {
+        let session = match db.get_live_session(&id).await {
+            Ok(session) => session,
+            Err(_) => return TokenStatus::Invalid,
+        };
+        match verify(&raw_token, &session.secret_hash) {
+            Ok(true) => {}
+            _ => return TokenStatus::Invalid,
         }
-    };
-
-    let id = match Uuid::parse_str(id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            return false;
+        if let Err(e) = db.touch_session(&session).await {
+            warn!("Failed to record activity on session {}: {}", session.id, e);
         }
-    };
-
-    let encrypted_token = match db.get_user_auth_hash(id).await {
-        Ok(encrypted) => encrypted,
-        Err(_) => {
-            return false;
+        Credential {
+            user_id: session.user_id,
+            session_id: Some(session.id),
+            api_key_id: None,
+            scopes: None,
+            auth_method: session.auth_method,
+            issued_at: Some(session.created_at),
+        }
+    } else if raw_token.starts_with(&api_key_prefix) {
+        let key = match db.get_live_api_key(&id).await {
+            Ok(key) => key,
+            Err(_) => return TokenStatus::Invalid,
+        };
+        match verify(&raw_token, &key.secret_hash) {
+            Ok(true) => {}
+            _ => return TokenStatus::Invalid,
+        }
+        if let Err(e) = db.touch_api_key(&key).await {
+            warn!("Failed to record use of API key {}: {}", key.id, e);
+        }
+        Credential {
+            user_id: key.user_id,
+            session_id: None,
+            api_key_id: Some(key.id),
+            scopes: Some(split_scopes(&key.scopes)),
+            auth_method: TokenType::ApiKey.to_string(),
+            issued_at: Some(key.created_at),
+        }
+    } else {
+        // The account token does not record when it was issued.
+        Credential {
+            user_id: id,
+            session_id: None,
+            api_key_id: None,
+            scopes: None,
+            auth_method: TokenType::User.to_string(),
+            issued_at: None,
         }
     };
 
-    let raw_token = match parts.next() {
-        Some(token) => token,
-        None => {
-            return false;
-        }
+    let user = match db.get_user_by_id(&credential.user_id).await {
+        Ok(user) => user,
+        Err(_) => return TokenStatus::Invalid,
     };
 
-    match verify(raw_token, &encrypted_token) {
-        Ok(result) => {
-            if !result {
-                return false;
-            }
+    // Plain API tokens are checked against the user row itself.
+    if credential.session_id.is_none() && credential.api_key_id.is_none() {
+        match verify(&raw_token, &user.auth_hash) {
+            Ok(true) => {}
+            _ => return TokenStatus::Invalid,
+        }
+    }
 
-            true
+    match user.status {
+        UserStatus::Active => {
+            // Failing to look up an elevation leaves the caller with their
+            // account role, never more. One that doesn't raise it is ignored.
+            let elevation = match db.active_elevation(&user.id).await {
+                Ok(elevation) => elevation
+                    .filter(|e| effective_role(user.role, Some(e.role)) != user.role)
+                    .and_then(|e| {
+                        Some(ActiveElevation {
+                            elevation_id: e.id,
+                            role: e.role,
+                            expires_at: e.expires_at?,
+                        })
+                    }),
+                Err(e) => {
+                    warn!("Failed to look up elevations of user {}: {}", user.id, e);
+                    None
+                }
+            };
+            TokenStatus::Valid(AuthenticatedUser {
+                user_id: credential.user_id,
+                session_id: credential.session_id,
+                api_key_id: credential.api_key_id,
+                scopes: credential.scopes,
+                role: effective_role(user.role, elevation.as_ref().map(|e| e.role)),
+                auth_method: credential.auth_method,
+                authenticated_at: credential.issued_at,
+                elevation,
+            })
         }
-        Err(_) => false,
+        UserStatus::Pending => TokenStatus::Pending,
+        UserStatus::Suspended => TokenStatus::Suspended,
+        UserStatus::Locked => TokenStatus::Locked,
     }
 }
 
+/// What a bearer token resolved to, before the account itself is checked.
+struct Credential {
+    user_id: Uuid,
+    session_id: Option<Uuid>,
+    api_key_id: Option<Uuid>,
+    scopes: Option<Vec<String>>,
+    auth_method: String,
+    issued_at: Option<DateTime<Utc>>,
+}
+
 /// Extracts the components of a base64-encoded token string.
 ///
 /// A valid token has the form `<uuid>.<raw_token>`, base64-encoded.
@@ -0,0 +1,87 @@
+use crate::config::config;
+use crate::db::postgres_service::PostgresService;
+use crate::types::{error::AppError, token::TokenType, user::DBUserCreate};
+use crate::utils::mail::{mail_email_verification, mail_welcome};
+use crate::utils::registration::{check_registration, SignupChannel};
+use crate::utils::token::{construct_token, encrypt, new_token};
+use chrono::Utc;
+use entity::user::UserRole;
+use uuid::Uuid;
+
+/// Creates a pending account and mails its access token and verification code.
+///
+/// Shared by every route that creates users so they all go through the
+/// registration policy and [`PostgresService::create_user`] the same way.
+pub async fn onboard_user(
+    db: &PostgresService,
+    name: &str,
+    email: &str,
+    role: UserRole,
+    channel: SignupChannel,
+) -> Result<Uuid, AppError> {
+    check_registration(&config().registration, email, channel)?;
+
+    let token = new_token(TokenType::User);
+
+    let encrypted_token = match encrypt(&token) {
+        Ok(token) => token,
+        Err(_) => {
+            return Err(AppError::Internal(
+                "There was an issue while encrypting the user's token.".to_string(),
+            ))
+        }
+    };
+
+    let user_id = db
+        .create_user(DBUserCreate {
+            name: name.to_string(),
+            email: email.to_string(),
+            auth_hash: encrypted_token,
+            email_verified_at: None,
+            role,
+        })
+        .await?;
+
+    let access_token = construct_token(&user_id, &token);
+    let verification_code = db.create_email_verification(&user_id).await?;
+
+    mail_welcome(email, &access_token).await.ok();
+    mail_email_verification(email, &verification_code)
+        .await
+        .ok();
+
+    Ok(user_id)
+}
+
+/// Creates an active account for someone who has already proven they own
+/// `email` (through an identity provider or an emailed team invite), and
+/// mails its access token.
+pub async fn provision_verified_user(
+    db: &PostgresService,
+    name: &str,
+    email: &str,
+    channel: SignupChannel,
+) -> Result<Uuid, AppError> {
+    check_registration(&config().registration, email, channel)?;
+
+    let token = new_token(TokenType::User);
+    let encrypted_token = encrypt(&token).map_err(|_| {
+        AppError::Internal("There was an issue while encrypting the user's token.".to_string())
+    })?;
+
+    let user_id = db
+        .create_user(DBUserCreate {
+            name: name.to_string(),
+            email: email.to_string(),
+            auth_hash: encrypted_token,
+            email_verified_at: Some(Utc::now()),
+            role: UserRole::Member,
+        })
+        .await?;
+
+    mail_welcome(email, &construct_token(&user_id, &token))
+        .await
+        .ok();
+
+    Ok(user_id)
+}
@@ -1,15 +1,232 @@
-use crate::utils::token::token_valid;
-use actix_web::{dev::ServiceRequest, error::ErrorUnauthorized, web};
+use crate::types::auth::{AuthenticatedUser, SessionRes};
+use crate::types::token::TokenStatus;
+use crate::utils::csrf::{csrf_matches, CSRF_HEADER};
+use crate::utils::policy::RequestContext;
+use crate::utils::role::role_satisfies;
+use crate::utils::scope::SCOPE_ACCOUNT;
+use crate::utils::token::check_token;
+use actix_web::{
+    body::MessageBody,
+    cookie::{time::Duration as CookieDuration, Cookie, SameSite},
+    dev::{ServiceRequest, ServiceResponse},
+    error::{ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized},
+    http::header::{self, Header},
+    middleware::Next,
+    web, HttpMessage, HttpRequest, HttpResponse,
+};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
+use actix_web_httpauth::headers::authorization::{Authorization, Bearer};
+use chrono::Utc;
+use entity::user::UserRole;
 use std::sync::Arc;
 use urlencoding;
 
-use crate::{config::config, db::postgres_service::PostgresService};
+use crate::{
+    config::config,
+    db::{
+        postgres_service::PostgresService,
+        session::{NewSession, SessionOrigin, SESSION_TTL_HOURS},
+    },
+};
 
 pub fn decode_all(input: &str) -> Option<String> {
     urlencoding::decode(input).ok().map(|cow| cow.into_owned())
 }
 
+/// Client address and user agent, recorded on sessions opened by this request.
+pub fn session_origin(req: &HttpRequest) -> SessionOrigin {
+    SessionOrigin {
+        ip_address: req
+            .connection_info()
+            .realip_remote_addr()
+            .map(str::to_string),
+        user_agent: req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+    }
+}
+
+/// Policy context for a request made by `identity`. Takes the peer address
+/// rather than forwarding headers, which the client controls.
+pub fn request_context(req: &HttpRequest, identity: &AuthenticatedUser) -> RequestContext {
+    let now = Utc::now();
+    RequestContext {
+        source_ip: req.peer_addr().map(|addr| addr.ip()),
+        time: now,
+        token_age: identity.authenticated_at.map(|at| now - at),
+        auth_method: Some(identity.auth_method.clone()),
+    }
+}
+
+/// Rough `<browser> on <os>` label for the session list.
+///
+/// Only the common families are recognised; anything else is `Unknown device`.
+pub fn describe_device(user_agent: Option<&str>) -> String {
+    let ua = match user_agent {
+        Some(ua) if !ua.trim().is_empty() => ua,
+        _ => return "Unknown device".to_string(),
+    };
+
+    // Order matters: Edge and Chrome both claim to be Safari, Edge claims Chrome.
+    let browser = [
+        ("Edg/", "Edge"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+        ("curl/", "curl"),
+    ]
+    .iter()
+    .find(|(needle, _)| ua.contains(needle))
+    .map(|(_, name)| *name);
+
+    let os = [
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(needle, _)| ua.contains(needle))
+    .map(|(_, name)| *name);
+
+    match (browser, os) {
+        (Some(b), Some(o)) => format!("{b} on {o}"),
+        (Some(b), None) => b.to_string(),
+        (None, Some(o)) => format!("Unknown browser on {o}"),
+        (None, None) => "Unknown device".to_string(),
+    }
+}
+
+fn identity_from(status: TokenStatus) -> Result<AuthenticatedUser, actix_web::Error> {
+    match status {
+        TokenStatus::Valid(identity) => Ok(identity),
+        TokenStatus::Suspended => Err(ErrorForbidden("Account suspended.")),
+        TokenStatus::Locked => Err(ErrorForbidden("Account locked.")),
+        TokenStatus::Pending | TokenStatus::Invalid => {
+            Err(ErrorUnauthorized("Invalid token std validate"))
+        }
+    }
+}
+
+/// Session cookie set by the login endpoints for browser clients.
+pub const SESSION_COOKIE: &str = "ledger_session";
+/// Readable copy of the CSRF token, so the dashboard can echo it after a reload.
+pub const CSRF_COOKIE: &str = "ledger_csrf";
+
+/// Answers a successful login.
+///
+/// Browser clients (`cookie`) get the session as an `HttpOnly` cookie and only
+/// see the CSRF token; API clients get the bearer token in the body.
+pub fn session_response(session: NewSession, cookie: bool) -> HttpResponse {
+    if !cookie {
+        return HttpResponse::Ok().json(SessionRes {
+            token: Some(session.token),
+            csrf_token: None,
+            expires_at: session.expires_at,
+        });
+    }
+
+    let max_age = CookieDuration::hours(SESSION_TTL_HOURS);
+    let session_cookie = Cookie::build(SESSION_COOKIE, session.token)
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(max_age)
+        .finish();
+    let csrf_cookie = Cookie::build(CSRF_COOKIE, session.csrf_token.clone())
+        .path("/")
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .max_age(max_age)
+        .finish();
+
+    HttpResponse::Ok()
+        .cookie(session_cookie)
+        .cookie(csrf_cookie)
+        .json(SessionRes {
+            token: None,
+            csrf_token: Some(session.csrf_token),
+            expires_at: session.expires_at,
+        })
+}
+
+/// Expires both session cookies on the client.
+pub fn clear_session_cookies(res: &mut HttpResponse) {
+    for name in [SESSION_COOKIE, CSRF_COOKIE] {
+        let mut cookie = Cookie::new(name, "");
+        cookie.set_path("/");
+        res.add_removal_cookie(&cookie).ok();
+    }
+}
+
+/// Authenticates with a bearer token or, if there is no `Authorization`
+/// header, the session cookie.
+///
+/// Cookie-authenticated requests that change state (anything but GET, HEAD,
+/// OPTIONS, TRACE) must also carry the session's CSRF token in [`CSRF_HEADER`].
+/// Bearer requests are not exposed to CSRF and skip the check.
+///
+/// Everything behind this middleware manages the account, so scoped API
+/// keys need the `account` scope.
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let db = req
+        .app_data::<web::Data<Arc<PostgresService>>>()
+        .cloned()
+        .ok_or_else(|| {
+            ErrorInternalServerError("DB unavailable. Please contact admin something bad happened.")
+        })?;
+
+    if req.headers().contains_key(header::AUTHORIZATION) {
+        let credentials = Authorization::<Bearer>::parse(&req)
+            .map_err(|_| ErrorUnauthorized("Invalid authorization header."))?
+            .into_scheme();
+        if credentials.token() == config().admin_key {
+            return next.call(req).await;
+        }
+
+        let identity = identity_from(check_token(&db, credentials.token()).await)?;
+        if !identity.has_scope(SCOPE_ACCOUNT) {
+            return Err(ErrorForbidden("API key lacks the account scope."));
+        }
+        req.extensions_mut().insert(identity);
+        return next.call(req).await;
+    }
+
+    let cookie = req
+        .cookie(SESSION_COOKIE)
+        .ok_or_else(|| ErrorUnauthorized("Authentication required."))?;
+    let identity = identity_from(check_token(&db, cookie.value()).await)?;
+    let session_id = identity
+        .session_id
+        .ok_or_else(|| ErrorUnauthorized("Invalid session cookie."))?;
+
+    if !req.method().is_safe() {
+        let session = db
+            .get_live_session(&session_id)
+            .await
+            .map_err(|_| ErrorUnauthorized("Invalid session cookie."))?;
+        let presented = req
+            .headers()
+            .get(CSRF_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        if !csrf_matches(presented, &session.csrf_hash) {
+            return Err(ErrorForbidden("Missing or invalid CSRF token."));
+        }
+    }
+
+    req.extensions_mut().insert(identity);
+    next.call(req).await
+}
+
 pub async fn validate_token(
     req: ServiceRequest,
     credentials: BearerAuth,
@@ -29,12 +246,55 @@ pub async fn validate_token(
             }
         };
 
-        if token_valid(&db, credentials.token()).await {
-            return Ok(req);
+        match identity_from(check_token(&db, credentials.token()).await) {
+            Ok(identity) => {
+                req.extensions_mut().insert(identity);
+                Ok(req)
+            }
+            Err(e) => Err((e, req)),
         }
+    }
+}
+
+/// Header carrying a TOTP or recovery code for sensitive routes.
+pub const OTP_HEADER: &str = "X-Ledger-OTP";
+
+/// Middleware that asks TOTP-enrolled users for a one-time code.
+///
+/// Goes inside [`authenticate`] (wrap it first) so the bearer token is already
+/// known to be valid here. Users without TOTP, and the admin key, pass straight through.
+pub async fn require_otp(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    // The admin key carries no identity.
+    let user_id = req.extensions().get::<AuthenticatedUser>().map(|i| i.user_id);
+    let user_id = match user_id {
+        Some(user_id) => user_id,
+        None => return next.call(req).await,
+    };
+
+    let db = req
+        .app_data::<web::Data<Arc<PostgresService>>>()
+        .cloned()
+        .ok_or_else(|| {
+            ErrorInternalServerError("DB unavailable. Please contact admin something bad happened.")
+        })?;
+
+    if db.totp_enabled(&user_id).await? {
+        let code = req
+            .headers()
+            .get(OTP_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
 
-        Err((ErrorUnauthorized("Invalid token std validate"), req))
+        if code.is_empty() || !db.verify_second_factor(&user_id, &code).await? {
+            return Err(ErrorUnauthorized("A valid one-time code is required."));
+        }
     }
+
+    next.call(req).await
 }
 
 pub fn grpc_valid(tok: &str) -> bool {
@@ -49,5 +309,84 @@ pub async fn validate_admin_token(
     if credentials.token() == config().admin_key {
         return Ok(req);
     }
-    return Err((ErrorUnauthorized("Invalid admin key."), req))
+
+    // Then credentials of accounts with the admin role
+    if let Some(db) = req.app_data::<web::Data<Arc<PostgresService>>>().cloned() {
+        if let TokenStatus::Valid(identity) = check_token(&db, credentials.token()).await {
+            if identity.role == UserRole::Admin && identity.has_scope(SCOPE_ACCOUNT) {
+                req.extensions_mut().insert(identity);
+                return Ok(req);
+            }
+        }
+    }
+    Err((ErrorUnauthorized("Invalid admin key."), req))
+}
+
+/// Lets through callers whose role is at least `required`. Runs inside
+/// [`authenticate`], which has already put the identity in the request.
+/// The static admin key carries no identity and always passes.
+async fn require_role(
+    required: UserRole,
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let role = req.extensions().get::<AuthenticatedUser>().map(|i| i.role);
+    match role {
+        Some(role) if !role_satisfies(role, required) => {
+            Err(ErrorForbidden("Your role does not allow this."))
+        }
+        None if !bearer_is_admin_key(&req) => Err(ErrorUnauthorized("Authentication required.")),
+        _ => next.call(req).await,
+    }
+}
+
+fn bearer_is_admin_key(req: &ServiceRequest) -> bool {
+    Authorization::<Bearer>::parse(req)
+        .is_ok_and(|auth| auth.as_ref().token() == config().admin_key)
+}
+
+/// Scopes that change shared state. Read-only accounts may still look:
+/// GET, HEAD, OPTIONS and TRACE only need the read-only role.
+pub async fn require_writer(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let required = if req.method().is_safe() {
+        UserRole::ReadOnly
+    } else {
+        UserRole::Member
+    };
+    require_role(required, req, next).await
+}
+
+/// The whole `/admin` scope, where most writes create lasting privilege or
+/// change who holds it. An admin acting under a temporary elevation may look
+/// but not change anything there, or the elevation could be made permanent.
+/// Runs inside the admin check, which has already put the identity in the
+/// request.
+pub async fn require_standing_admin(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let elevated = req
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .is_some_and(|i| i.is_elevated());
+    if elevated && !req.method().is_safe() {
+        return Err(ErrorForbidden(
+            "Not available while your admin role is a temporary elevation.",
+        ));
+    }
+    next.call(req).await
+}
+
+/// Bearer check for `/scim/v2`, against the token given to the HR system.
+pub async fn validate_scim_token(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
+    match &config().scim_token {
+        Some(token) if credentials.token() == token => Ok(req),
+        _ => Err((ErrorUnauthorized("Invalid SCIM token."), req)),
+    }
 }
@@ -0,0 +1,160 @@
+use chrono::{Duration, NaiveTime, TimeZone, Utc, Weekday};
+use ledger_auth::utils::policy::{
+    cidr_contains, Access, BindingCondition, Grant, Policy, RbacPolicy, RequestContext,
+};
+use uuid::Uuid;
+
+fn ctx(ip: &str, age_secs: Option<i64>, method: &str) -> RequestContext {
+    RequestContext {
+        source_ip: Some(ip.parse().unwrap()),
+        // A Wednesday.
+        time: Utc.with_ymd_and_hms(2026, 10, 14, 10, 30, 0).unwrap(),
+        token_age: age_secs.map(Duration::seconds),
+        auth_method: Some(method.to_string()),
+    }
+}
+
+fn time(s: &str) -> NaiveTime {
+    NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+}
+
+#[test]
+fn test_abac_cidr_matching() {
+    assert!(cidr_contains("10.20.0.0/16", "10.20.3.4".parse().unwrap()));
+    assert!(!cidr_contains("10.20.0.0/16", "10.21.0.1".parse().unwrap()));
+    assert!(cidr_contains("0.0.0.0/0", "192.0.2.1".parse().unwrap()));
+    assert!(cidr_contains("203.0.113.7", "203.0.113.7".parse().unwrap()));
+    assert!(cidr_contains(
+        "10.0.0.0/8",
+        "::ffff:10.1.2.3".parse().unwrap()
+    ));
+    assert!(cidr_contains(
+        "2001:db8::/32",
+        "2001:db8::1".parse().unwrap()
+    ));
+    assert!(!cidr_contains("2001:db8::/32", "10.0.0.1".parse().unwrap()));
+    assert!(!cidr_contains("10.0.0.0/33", "10.0.0.1".parse().unwrap()));
+    assert!(!cidr_contains("office", "10.0.0.1".parse().unwrap()));
+}
+
+#[test]
+fn test_abac_source_ip_condition() {
+    let office = BindingCondition::SourceIp {
+        cidrs: vec!["10.20.0.0/16".into()],
+    };
+    assert!(office.holds(&ctx("10.20.1.1", None, "password")));
+    assert!(!office.holds(&ctx("198.51.100.1", None, "password")));
+    assert!(!office.holds(&RequestContext::at(Utc::now())));
+}
+
+#[test]
+fn test_abac_time_window_condition() {
+    let business_hours = BindingCondition::TimeWindow {
+        start: time("09:00"),
+        end: time("17:00"),
+        weekdays: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed],
+    };
+    assert!(business_hours.holds(&ctx("10.0.0.1", None, "password")));
+
+    let weekend = BindingCondition::TimeWindow {
+        start: time("09:00"),
+        end: time("17:00"),
+        weekdays: vec![Weekday::Sat, Weekday::Sun],
+    };
+    assert!(!weekend.holds(&ctx("10.0.0.1", None, "password")));
+
+    let overnight = BindingCondition::TimeWindow {
+        start: time("22:00"),
+        end: time("06:00"),
+        weekdays: vec![],
+    };
+    assert!(!overnight.holds(&ctx("10.0.0.1", None, "password")));
+    let mut late = ctx("10.0.0.1", None, "password");
+    late.time = Utc.with_ymd_and_hms(2026, 10, 14, 23, 0, 0).unwrap();
+    assert!(overnight.holds(&late));
+}
+
+#[test]
+fn test_abac_mfa_freshness_condition() {
+    let recent_mfa = BindingCondition::MfaWithin { seconds: 600 };
+    assert!(recent_mfa.holds(&ctx("10.0.0.1", Some(300), "passkey")));
+    assert!(recent_mfa.holds(&ctx("10.0.0.1", Some(300), "password+otp")));
+    assert!(!recent_mfa.holds(&ctx("10.0.0.1", Some(900), "passkey")));
+    assert!(!recent_mfa.holds(&ctx("10.0.0.1", Some(60), "password")));
+    assert!(!recent_mfa.holds(&ctx("10.0.0.1", None, "passkey")));
+
+    let fresh = BindingCondition::MaxTokenAge { seconds: 60 };
+    assert!(fresh.holds(&ctx("10.0.0.1", Some(30), "apikey")));
+    assert!(!fresh.holds(&ctx("10.0.0.1", Some(61), "apikey")));
+
+    let keys_only = BindingCondition::AuthMethod {
+        methods: vec!["apikey".into()],
+    };
+    assert!(keys_only.holds(&ctx("10.0.0.1", None, "apikey")));
+    assert!(!keys_only.holds(&ctx("10.0.0.1", None, "passkey")));
+}
+
+#[test]
+fn test_abac_policy_requires_every_condition() {
+    let grant = Grant {
+        binding_id: Uuid::new_v4(),
+        scope: "workspace:z".into(),
+        permissions: vec!["file.delete".into()],
+        conditions: vec![
+            BindingCondition::SourceIp {
+                cidrs: vec!["10.0.0.0/8".into()],
+            },
+            BindingCondition::MfaWithin { seconds: 600 },
+        ],
+    };
+    let access = Access {
+        grants: vec![grant],
+        acl: vec![],
+    };
+    let resource = "workspace:z/file:1";
+
+    assert!(RbacPolicy
+        .authorize(
+            &access,
+            "file.delete",
+            resource,
+            &ctx("10.1.1.1", Some(60), "passkey")
+        )
+        .is_allowed());
+    assert!(!RbacPolicy
+        .authorize(
+            &access,
+            "file.delete",
+            resource,
+            &ctx("192.0.2.1", Some(60), "passkey")
+        )
+        .is_allowed());
+    assert!(!RbacPolicy
+        .authorize(
+            &access,
+            "file.delete",
+            resource,
+            &ctx("10.1.1.1", Some(60), "password")
+        )
+        .is_allowed());
+}
+
+#[test]
+fn test_abac_conditions_parse_and_validate() {
+    let conditions: Vec<BindingCondition> = serde_json::from_value(serde_json::json!([
+        { "type": "source_ip", "cidrs": ["10.0.0.0/8"] },
+        { "type": "time_window", "start": "09:00:00", "end": "17:00:00", "weekdays": ["Mon"] },
+        { "type": "mfa_within", "seconds": 600 },
+    ]))
+    .unwrap();
+    assert_eq!(conditions.len(), 3);
+    assert!(conditions.iter().all(|c| c.problem().is_none()));
+
+    let bad = BindingCondition::SourceIp {
+        cidrs: vec!["10.0.0.0/40".into()],
+    };
+    assert!(bad.problem().is_some());
+    assert!(BindingCondition::MfaWithin { seconds: 0 }
+        .problem()
+        .is_some());
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{
+    client::{authed, TestClient},
+    TestContext,
+};
+use entity::role_binding::SubjectType;
+use ledger_auth::db::session::SessionOrigin;
+use ledger_auth::grpc::authentication::AuthenticationSvc;
+use ledger_auth::grpc::pb::{
+    authentication_server::Authentication, AuthorizeRequest, RequestContext, ValidationRequest,
+};
+use ledger_auth::utils::policy::BindingCondition;
+use tonic::Request;
+
+fn grpc<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+    request
+}
+
+#[tokio::test]
+async fn test_abac_flow_conditions_follow_request_context() {
+    println!("\n\n[+] Running test: test_abac_flow_conditions_follow_request_context");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, user_token) = client.create_test_user(None).await.unwrap();
+    let passkey_session = ctx
+        .db
+        .create_session(&user_id, "passkey", SessionOrigin::default())
+        .await
+        .unwrap();
+
+    println!("[>] Writes only from the office; deletes only right after MFA.");
+    for name in ["file.write", "file.delete"] {
+        ctx.db.create_permission(name, None).await.unwrap();
+    }
+    let (writer, _) = ctx
+        .db
+        .create_rbac_role("office-writer", None, &["file.write".into()], None)
+        .await
+        .unwrap();
+    let (deleter, _) = ctx
+        .db
+        .create_rbac_role("mfa-deleter", None, &["file.delete".into()], None)
+        .await
+        .unwrap();
+    ctx.db
+        .create_role_binding(
+            SubjectType::User,
+            &user_id,
+            &writer.id,
+            "workspace:z",
+            &[BindingCondition::SourceIp {
+                cidrs: vec!["10.0.0.0/8".into()],
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    ctx.db
+        .create_role_binding(
+            SubjectType::User,
+            &user_id,
+            &deleter.id,
+            "workspace:z",
+            &[BindingCondition::MfaWithin { seconds: 600 }],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let ask = |token: &str, peer: &str, action: &str| {
+        authed(test::TestRequest::post().uri("/authorize"), token)
+            .peer_addr(peer.parse().unwrap())
+            .set_json(serde_json::json!({ "action": action, "resource": "workspace:z/file:1" }))
+            .to_request()
+    };
+
+    println!("[>] Writing from inside and outside the office range.");
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, ask(&user_token, "10.1.2.3:4000", "file.write")).await;
+    assert_eq!(body["allowed"], true);
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, ask(&user_token, "192.0.2.1:4000", "file.write")).await;
+    assert_eq!(body["allowed"], false);
+
+    println!("[>] Deleting with the account token, then a fresh passkey session.");
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, ask(&user_token, "10.1.2.3:4000", "file.delete")).await;
+    assert_eq!(body["allowed"], false);
+    let body: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        ask(&passkey_session.token, "10.1.2.3:4000", "file.delete"),
+    )
+    .await;
+    assert_eq!(body["allowed"], true);
+
+    println!("[>] Bad conditions are refused when binding.");
+    let req = authed(
+        test::TestRequest::post().uri("/admin/rbac/bindings"),
+        &ledger_auth::config::config().admin_key,
+    )
+    .set_json(serde_json::json!({
+        "subject_type": "user",
+        "subject_id": user_id,
+        "role_id": writer.id,
+        "scope": "workspace:y",
+        "conditions": [{ "type": "source_ip", "cidrs": ["not-a-range"] }],
+    }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    println!("[>] gRPC: validation resolves the context, Authorize evaluates it.");
+    let svc = AuthenticationSvc::new(ctx.db.clone());
+    let validation = svc
+        .validate_authentication(grpc(ValidationRequest {
+            token: passkey_session.token.clone(),
+            context: Some(RequestContext {
+                source_ip: "192.0.2.1".into(),
+                ..Default::default()
+            }),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", validation);
+    let context = validation.context.unwrap();
+    assert_eq!(context.auth_method, "passkey");
+    assert!(context.token_age_secs.unwrap() < 600);
+
+    let authorize = |action: &str, token: &str, context: RequestContext| {
+        grpc(AuthorizeRequest {
+            subject: user_id.to_string(),
+            action: action.to_string(),
+            resource: "workspace:z/file:1".into(),
+            context: Some(context),
+            token: token.to_string(),
+        })
+    };
+    let response = svc
+        .authorize(authorize(
+            "file.delete",
+            &passkey_session.token,
+            context.clone(),
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.allowed);
+    let response = svc
+        .authorize(authorize(
+            "file.write",
+            &passkey_session.token,
+            context.clone(),
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.allowed);
+
+    println!("[>] gRPC: a caller can't vouch for the credential itself.");
+    let response = svc
+        .authorize(authorize("file.delete", "", context.clone()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.allowed);
+    let response = svc
+        .authorize(authorize("file.delete", &user_token, context))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.allowed);
+    println!("[/] Test passed: Binding conditions checked against request context.");
+}
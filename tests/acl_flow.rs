@@ -0,0 +1,126 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{
+    client::{authed, TestClient},
+    TestContext,
+};
+use entity::role_binding::SubjectType;
+
+#[tokio::test]
+async fn test_acl_flow_share_folder_with_another_user() {
+    println!("\n\n[+] Running test: test_acl_flow_share_folder_with_another_user");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (alice_id, alice_token) = client.create_test_user(None).await.unwrap();
+    let (bob_id, bob_token) = client.create_test_user(None).await.unwrap();
+
+    println!("[>] Alice can read and share anything in workspace z.");
+    for name in ["file.read", "file.delete", "acl.manage"] {
+        ctx.db.create_permission(name, None).await.unwrap();
+    }
+    let (sharer, _) = ctx
+        .db
+        .create_rbac_role(
+            "sharer",
+            None,
+            &["file.read".into(), "acl.manage".into()],
+            None,
+        )
+        .await
+        .unwrap();
+    ctx.db
+        .create_role_binding(
+            SubjectType::User,
+            &alice_id,
+            &sharer.id,
+            "workspace:z",
+            &[],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let folder = "workspace:z/folder:reports";
+    let file = "workspace:z/folder:reports/file:q3.pdf";
+    let can_bob = |action: &str, resource: &str| {
+        authed(test::TestRequest::post().uri("/authorize"), &bob_token)
+            .set_json(serde_json::json!({ "action": action, "resource": resource }))
+            .to_request()
+    };
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, can_bob("file.read", file)).await;
+    assert_eq!(body["allowed"], false);
+
+    println!("[>] Alice cannot hand out a permission she does not hold.");
+    let req = authed(test::TestRequest::post().uri("/acl"), &alice_token)
+        .set_json(serde_json::json!({
+            "resource": folder,
+            "principal_type": "user",
+            "principal_id": bob_id,
+            "permission": "file.delete",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Alice shares the folder with Bob for reading.");
+    let req = authed(test::TestRequest::post().uri("/acl"), &alice_token)
+        .set_json(serde_json::json!({
+            "resource": folder,
+            "principal_type": "user",
+            "principal_id": bob_id,
+            "permission": "file.read",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let entry: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(entry["inherited"], true);
+
+    println!("[>] Bob can read files in the folder, but not elsewhere.");
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, can_bob("file.read", file)).await;
+    assert_eq!(body["allowed"], true);
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, can_bob("file.read", "workspace:z/file:other")).await;
+    assert_eq!(body["allowed"], false);
+
+    println!("[>] The file's ACL lists the entry inherited from the folder.");
+    let req = authed(
+        test::TestRequest::get().uri(&format!("/acl?resource={}", file)),
+        &alice_token,
+    )
+    .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["resource"], folder);
+
+    println!("[>] Bob cannot manage the ACL.");
+    let req = authed(
+        test::TestRequest::delete().uri(&format!("/acl/{}", entry["id"].as_str().unwrap())),
+        &bob_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Alice revokes it.");
+    let req = authed(
+        test::TestRequest::delete().uri(&format!("/acl/{}", entry["id"].as_str().unwrap())),
+        &alice_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, can_bob("file.read", file)).await;
+    assert_eq!(body["allowed"], false);
+    println!("[/] Test passed: ACL entries shared, inherited and revoked.");
+}
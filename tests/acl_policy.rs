@@ -0,0 +1,62 @@
+use chrono::Utc;
+use ledger_auth::utils::policy::{
+    resource_ancestors, Access, AclGrant, Decision, Policy, RbacPolicy, RequestContext,
+};
+use uuid::Uuid;
+
+fn entry(resource: &str, permission: &str, inherited: bool) -> AclGrant {
+    AclGrant {
+        entry_id: Uuid::new_v4(),
+        resource: resource.to_string(),
+        permission: permission.to_string(),
+        inherited,
+    }
+}
+
+#[test]
+fn test_acl_resource_ancestors() {
+    assert_eq!(
+        resource_ancestors("workspace:z/folder:a/file:b"),
+        vec![
+            "workspace:z",
+            "workspace:z/folder:a",
+            "workspace:z/folder:a/file:b"
+        ]
+    );
+    assert_eq!(resource_ancestors("workspace:z"), vec!["workspace:z"]);
+}
+
+#[test]
+fn test_acl_inheritance_from_parent_folders() {
+    let folder = entry("workspace:z/folder:a", "file.read", true);
+    let pinned = entry("workspace:z/folder:b", "file.read", false);
+    let access = Access {
+        grants: vec![],
+        acl: vec![folder.clone(), pinned],
+    };
+    let ctx = RequestContext::at(Utc::now());
+
+    assert_eq!(
+        RbacPolicy.authorize(
+            &access,
+            "file.read",
+            "workspace:z/folder:a/sub:c/file:d",
+            &ctx
+        ),
+        Decision::AllowAcl {
+            entry_id: folder.entry_id
+        }
+    );
+    assert!(RbacPolicy
+        .authorize(&access, "file.read", "workspace:z/folder:b", &ctx)
+        .is_allowed());
+    assert!(!RbacPolicy
+        .authorize(&access, "file.read", "workspace:z/folder:b/file:e", &ctx)
+        .is_allowed());
+    assert!(!RbacPolicy
+        .authorize(&access, "file.write", "workspace:z/folder:a/file:d", &ctx)
+        .is_allowed());
+    assert!(!RbacPolicy
+        .authorize(&access, "file.read", "workspace:z/folder:ab", &ctx)
+        .is_allowed());
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+
+#[tokio::test]
+async fn test_admin_user_directory_flow_pagination() {
+    println!("\n\n[+] Running test: test_admin_user_directory_flow_pagination");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    for i in 0..5 {
+        client
+            .create_test_user(Some(format!("page-{}@example.com", i)))
+            .await
+            .expect("Failed creating a test user");
+    }
+
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let uri = match &cursor {
+            Some(c) => format!("/admin/users?limit=2&cursor={}", c),
+            None => "/admin/users?limit=2".to_string(),
+        };
+        println!("[>] Fetching {}", uri);
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let items = body["items"].as_array().unwrap();
+        assert!(items.len() <= 2);
+        for item in items {
+            assert!(item.get("auth_hash").is_none());
+            seen.push(item["email"].as_str().unwrap().to_string());
+        }
+
+        match body["next_cursor"].as_str() {
+            Some(c) => cursor = Some(c.to_string()),
+            None => break,
+        }
+    }
+
+    println!("[<] Saw {} users across pages.", seen.len());
+    assert_eq!(seen.len(), 5);
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen.len(), 5);
+    println!("[/] Test passed: Cursor pagination visits every user once.");
+}
+
+#[tokio::test]
+async fn test_admin_user_directory_flow_search_and_filters() {
+    println!("\n\n[+] Running test: test_admin_user_directory_flow_search_and_filters");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    client
+        .create_test_user(Some("alice@example.com".to_string()))
+        .await
+        .unwrap();
+    client
+        .create_test_user(Some("bob@example.com".to_string()))
+        .await
+        .unwrap();
+    client.create_pending_test_user().await.unwrap();
+    client.create_test_admin().await;
+
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let fetch = |uri: &'static str| {
+        test::TestRequest::get()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+            .to_request()
+    };
+
+    println!("[>] Searching by email prefix.");
+    let resp = test::call_service(&app, fetch("/admin/users?q=ALI")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let items = body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["email"], "alice@example.com");
+
+    println!("[>] Filtering by status.");
+    let resp = test::call_service(&app, fetch("/admin/users?status=pending")).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let items = body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["status"], "pending");
+
+    println!("[>] Filtering by role.");
+    let resp = test::call_service(&app, fetch("/admin/users?role=admin")).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let items = body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["role"], "admin");
+
+    println!("[>] Filtering by creation date in the future.");
+    let resp = test::call_service(
+        &app,
+        fetch("/admin/users?created_after=2999-01-01T00:00:00Z"),
+    )
+    .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["items"].as_array().unwrap().is_empty());
+    assert!(body["next_cursor"].is_null());
+
+    println!("[>] Sending a bogus cursor.");
+    let resp = test::call_service(&app, fetch("/admin/users?cursor=nope")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Directory search and filters work.");
+}
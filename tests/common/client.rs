@@ -1,4 +1,6 @@
-use actix_web::{web, App};
+use actix_web::{test, web, App};
+use chrono::Utc;
+use entity::user::UserRole;
 use ledger_auth::{
     db::postgres_service::PostgresService,
     types::{error::AppError, token::TokenType, user::DBUserCreate},
@@ -48,6 +50,8 @@ impl TestClient {
                 name: "Test Admin".to_string(),
                 email: email.clone(),
                 auth_hash: encrypted_token,
+                email_verified_at: Some(Utc::now()),
+                role: UserRole::Admin,
             })
             .await
             .expect("Failed to create admin");
@@ -77,6 +81,8 @@ impl TestClient {
                 name: "Test User".to_string(),
                 email: email.clone(),
                 auth_hash: encrypted_token,
+                email_verified_at: Some(Utc::now()),
+                role: UserRole::Member,
             })
             .await?;
         println!("[<] Created user with ID: {}", user_id);
@@ -86,4 +92,35 @@ impl TestClient {
 
         Ok((user_id, access_token))
     }
+
+    #[allow(dead_code)]
+    pub async fn create_pending_test_user(&self) -> Result<(Uuid, String, String), AppError> {
+        println!("[+] Creating pending test user");
+        let user_token = new_token(TokenType::User);
+        let encrypted_token = encrypt(&user_token).expect("Failed to encrypt token");
+        let email = format!("pending-{}@test.com", Uuid::new_v4());
+        println!("[>] Creating pending user with email: {}", email);
+
+        let user_id = self
+            .db
+            .create_user(DBUserCreate {
+                name: "Pending User".to_string(),
+                email: email.clone(),
+                auth_hash: encrypted_token,
+                email_verified_at: None,
+                role: UserRole::Member,
+            })
+            .await?;
+        println!("[<] Created pending user with ID: {}", user_id);
+
+        let access_token = construct_token(&user_id, &user_token);
+
+        Ok((user_id, email, access_token))
+    }
+}
+
+/// Adds the bearer token a request is sent with.
+#[allow(dead_code)]
+pub fn authed(req: test::TestRequest, token: &str) -> test::TestRequest {
+    req.insert_header(("Authorization", format!("Bearer {}", token)))
 }
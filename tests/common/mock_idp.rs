@@ -0,0 +1,96 @@
+//! A tiny OpenID provider for federated login tests.
+//!
+//! It skips the login page: the test builds the authorization code itself as
+//! base64url JSON of the claims it wants in the ID token (`sub`, `email`,
+//! `email_verified`, `name`, `nonce`), and the token endpoint signs them.
+#![allow(dead_code)]
+
+use super::MOCK_IDP_ISSUER;
+use actix_web::{get, post, web, App, HttpResponse, HttpServer};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ledger_auth::utils::jwt::{key_id, public_jwk, sign_rs256};
+use rand_core::OsRng;
+use rsa::RsaPrivateKey;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Once, OnceLock};
+
+static KEY: OnceLock<RsaPrivateKey> = OnceLock::new();
+static STARTED: Once = Once::new();
+
+fn key() -> &'static RsaPrivateKey {
+    KEY.get_or_init(|| RsaPrivateKey::new(&mut OsRng, 2048).expect("mock IdP key"))
+}
+
+/// Builds the code the mock token endpoint will accept.
+pub fn code_for(claims: serde_json::Value) -> String {
+    URL_SAFE_NO_PAD.encode(claims.to_string())
+}
+
+#[get("/.well-known/openid-configuration")]
+async fn discovery() -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "issuer": MOCK_IDP_ISSUER,
+        "authorization_endpoint": format!("{MOCK_IDP_ISSUER}/authorize"),
+        "token_endpoint": format!("{MOCK_IDP_ISSUER}/token"),
+        "jwks_uri": format!("{MOCK_IDP_ISSUER}/jwks"),
+    }))
+}
+
+#[get("/jwks")]
+async fn jwks() -> HttpResponse {
+    let public = key().to_public_key();
+    HttpResponse::Ok().json(json!({ "keys": [public_jwk(&public, &key_id(&public))] }))
+}
+
+#[post("/token")]
+async fn token(form: web::Form<HashMap<String, String>>) -> HttpResponse {
+    if form.get("client_secret").map(String::as_str) != Some("mock-secret")
+        || form.get("code_verifier").is_none_or(|v| v.len() < 43)
+    {
+        return HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" }));
+    }
+    let Some(mut claims) = form
+        .get("code")
+        .and_then(|c| URL_SAFE_NO_PAD.decode(c).ok())
+        .and_then(|c| serde_json::from_slice::<serde_json::Value>(&c).ok())
+    else {
+        return HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" }));
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    claims["iss"] = json!(MOCK_IDP_ISSUER);
+    claims["aud"] = json!(form.get("client_id"));
+    claims["iat"] = json!(now);
+    claims["exp"] = json!(now + 300);
+
+    let public = key().to_public_key();
+    let id_token = sign_rs256(&claims, key(), &key_id(&public)).unwrap();
+    HttpResponse::Ok().json(json!({
+        "access_token": "mock-access",
+        "token_type": "Bearer",
+        "id_token": id_token,
+    }))
+}
+
+/// Starts the mock on its own thread, once per test binary.
+pub fn start() {
+    STARTED.call_once(|| {
+        key();
+        let addr = MOCK_IDP_ISSUER.trim_start_matches("http://").to_string();
+        let bind = addr.clone();
+        std::thread::spawn(move || {
+            actix_web::rt::System::new().block_on(async move {
+                HttpServer::new(|| App::new().service(discovery).service(jwks).service(token))
+                    .workers(1)
+                    .bind(bind)
+                    .expect("mock IdP port taken")
+                    .run()
+                    .await
+            })
+        });
+        while std::net::TcpStream::connect(&addr).is_err() {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+    });
+}
@@ -4,6 +4,10 @@ use testcontainers::{runners::AsyncRunner, ContainerAsync};
 use testcontainers_modules::postgres::Postgres;
 
 pub mod client;
+pub mod mock_idp;
+
+/// Where [`mock_idp`] listens; see `federated_flow.rs`.
+pub const MOCK_IDP_ISSUER: &str = "http://127.0.0.1:18089";
 
 pub struct TestContext {
     pub db: Arc<PostgresService>,
@@ -56,10 +60,47 @@ pub fn get_test_config() -> EnvConfig {
         db_url: "test".to_string(), // Not used in tests
         admin_key: "test_admin_key".to_string(),
         resend_key: "test_resend_key".to_string(),
+        public_url: "http://localhost:8080".to_string(),
+        registration: ledger_auth::config::RegistrationConfig {
+            mode: ledger_auth::config::RegistrationMode::InviteOnly,
+            allowed_domains: vec![],
+            denied_domains: vec![],
+            block_disposable: true,
+        },
+        webauthn: ledger_auth::config::WebauthnConfig {
+            rp_id: "localhost".to_string(),
+            rp_origin: "http://localhost:8080".to_string(),
+        },
+        oidc: ledger_auth::config::OidcConfig {
+            signing_key_pem: None,
+        },
+        federation: vec![
+            ledger_auth::config::FederatedProviderConfig {
+                id: "mock".to_string(),
+                name: "Mock IdP".to_string(),
+                issuer: MOCK_IDP_ISSUER.to_string(),
+                client_id: "ledger-test".to_string(),
+                client_secret: "mock-secret".to_string(),
+                auto_create: true,
+            },
+            ledger_auth::config::FederatedProviderConfig {
+                id: "partner".to_string(),
+                name: "Partner IdP".to_string(),
+                issuer: MOCK_IDP_ISSUER.to_string(),
+                client_id: "ledger-test".to_string(),
+                client_secret: "mock-secret".to_string(),
+                auto_create: false,
+            },
+        ],
+        scim_token: Some("test_scim_token".to_string()),
         grpc: ledger_auth::config::GrpcConfig {
             port: 50051,
             auth_key: "test_grpc_auth".to_string(),
         },
+        kms: Some(ledger_auth::utils::kms::Keyring {
+            current: ledger_auth::utils::kms::MasterKey::new([7u8; 32]),
+            retired: vec![],
+        }),
     }
 }
 
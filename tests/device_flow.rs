@@ -0,0 +1,162 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+#[tokio::test]
+async fn test_device_flow_approve_issues_named_scoped_key() {
+    println!("\n\n[+] Running test: test_device_flow_approve_issues_named_scoped_key");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    println!("[>] CLI requesting a device code.");
+    let req = test::TestRequest::post()
+        .uri("/oauth/device_authorization")
+        .set_form([
+            ("client_id", "ledger-cli"),
+            ("scope", "files:read"),
+            ("device_name", "build-box"),
+        ])
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let device_code = body["device_code"].as_str().unwrap().to_string();
+    let user_code = body["user_code"].as_str().unwrap().to_string();
+    assert_eq!(user_code.len(), 9);
+    assert!(body["verification_uri_complete"]
+        .as_str()
+        .unwrap()
+        .contains("user_code="));
+
+    println!("[>] User reviewing and approving the code.");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/oauth/device?user_code={}",
+            user_code.to_lowercase()
+        ))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["device_name"], "build-box");
+    assert_eq!(body["scopes"], serde_json::json!(["files:read"]));
+
+    let req = test::TestRequest::post()
+        .uri("/oauth/device/approve")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({ "user_code": user_code }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    println!("[>] CLI polling for the token.");
+    let req = test::TestRequest::post()
+        .uri("/oauth/token")
+        .set_form([
+            ("grant_type", DEVICE_GRANT),
+            ("device_code", device_code.as_str()),
+            ("client_id", "ledger-cli"),
+        ])
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["token_type"], "Bearer");
+    assert_eq!(body["scope"], "files:read");
+    let api_key = body["access_token"].as_str().unwrap().to_string();
+
+    let keys = ctx.db.list_api_keys(&user_id).await.unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].name, "build-box");
+
+    println!("[>] Using the new key.");
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", api_key)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/user/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", api_key)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    println!("[/] Test passed: Device grant produced a named, scoped API key.");
+}
+
+#[tokio::test]
+async fn test_device_flow_pending_slow_down_and_denied() {
+    println!("\n\n[+] Running test: test_device_flow_pending_slow_down_and_denied");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (_user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    let req = test::TestRequest::post()
+        .uri("/oauth/device_authorization")
+        .set_form([("client_id", "ledger-cli")])
+        .to_request();
+    let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let device_code = body["device_code"].as_str().unwrap().to_string();
+    let user_code = body["user_code"].as_str().unwrap().to_string();
+
+    let poll = |code: String| {
+        test::TestRequest::post()
+            .uri("/oauth/token")
+            .set_form([
+                ("grant_type", DEVICE_GRANT.to_string()),
+                ("device_code", code),
+                ("client_id", "ledger-cli".to_string()),
+            ])
+            .to_request()
+    };
+
+    let mut errors = Vec::new();
+    for _ in 0..2 {
+        let resp = test::call_service(&app, poll(device_code.clone())).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        errors.push(body["error"].as_str().unwrap().to_string());
+    }
+    println!("[<] Poll errors: {:?}", errors);
+    assert_eq!(errors, ["authorization_pending", "slow_down"]);
+
+    println!("[>] Denying the code.");
+    let req = test::TestRequest::post()
+        .uri("/oauth/device/deny")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({ "user_code": user_code }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+
+    tokio::time::sleep(std::time::Duration::from_secs(11)).await;
+    let resp = test::call_service(&app, poll(device_code)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "access_denied");
+    println!("[/] Test passed: Polling follows RFC 8628 error codes.");
+}
@@ -0,0 +1,66 @@
+use entity::user::UserRole;
+use ledger_auth::utils::elevation::{effective_role, resolve_eligibility, Eligibility};
+
+fn eligible(role: UserRole, max_minutes: i32, requires_approval: bool) -> Eligibility {
+    Eligibility {
+        role,
+        max_minutes,
+        requires_approval,
+    }
+}
+
+#[test]
+fn test_elevation_without_eligibility_is_refused() {
+    assert_eq!(resolve_eligibility(&[], UserRole::Admin), None);
+    assert_eq!(
+        resolve_eligibility(&[eligible(UserRole::Member, 60, false)], UserRole::Admin),
+        None
+    );
+}
+
+#[test]
+fn test_elevation_eligibility_for_a_higher_role_covers_lower_ones() {
+    assert_eq!(
+        resolve_eligibility(&[eligible(UserRole::Admin, 60, true)], UserRole::Member),
+        Some(eligible(UserRole::Member, 60, true))
+    );
+}
+
+#[test]
+fn test_elevation_eligibilities_combine_to_the_most_permissive() {
+    let entries = [
+        eligible(UserRole::Admin, 60, true),
+        eligible(UserRole::Admin, 15, false),
+        eligible(UserRole::Member, 480, false),
+    ];
+    assert_eq!(
+        resolve_eligibility(&entries, UserRole::Admin),
+        Some(eligible(UserRole::Admin, 60, false))
+    );
+
+    let entries = [
+        eligible(UserRole::Admin, 60, true),
+        eligible(UserRole::Admin, 120, true),
+    ];
+    assert_eq!(
+        resolve_eligibility(&entries, UserRole::Admin),
+        Some(eligible(UserRole::Admin, 120, true))
+    );
+}
+
+#[test]
+fn test_elevation_raises_but_never_lowers_the_role() {
+    assert_eq!(effective_role(UserRole::Member, None), UserRole::Member);
+    assert_eq!(
+        effective_role(UserRole::Member, Some(UserRole::Admin)),
+        UserRole::Admin
+    );
+    assert_eq!(
+        effective_role(UserRole::Admin, Some(UserRole::Member)),
+        UserRole::Admin
+    );
+    assert_eq!(
+        effective_role(UserRole::ReadOnly, Some(UserRole::Member)),
+        UserRole::Member
+    );
+}
@@ -0,0 +1,304 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{
+    client::{authed, TestClient},
+    TestContext,
+};
+use entity::role_binding::SubjectType;
+use entity::user::UserRole;
+use ledger_auth::grpc::authentication::AuthenticationSvc;
+use ledger_auth::grpc::pb::{
+    authentication_server::Authentication, ValidationRequest, ValidationResponse,
+};
+use ledger_auth::types::audit::RAuditList;
+use tonic::Request;
+
+async fn validate(svc: &AuthenticationSvc, token: &str) -> ValidationResponse {
+    let mut request = Request::new(ValidationRequest {
+        token: token.to_string(),
+        ..Default::default()
+    });
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+    let response = svc
+        .validate_authentication(request)
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", response);
+    response
+}
+
+#[tokio::test]
+async fn test_elevation_flow_approved_admin_for_an_hour() {
+    println!("\n\n[+] Running test: test_elevation_flow_approved_admin_for_an_hour");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    let svc = AuthenticationSvc::new(ctx.db.clone());
+    println!("[+] Actix web app initialized.");
+
+    let (admin_id, admin_token) = client.create_test_admin().await;
+    let (oncall_id, oncall_token) = client.create_test_user(None).await.unwrap();
+    let list_users =
+        |token: &str| authed(test::TestRequest::get().uri("/admin/users"), token).to_request();
+
+    println!("[>] Without eligibility, asking for admin is refused.");
+    let request_admin = |token: &str| {
+        authed(
+            test::TestRequest::post().uri("/user/elevations/request"),
+            token,
+        )
+        .set_json(serde_json::json!({
+            "role": "admin",
+            "reason": "INC-42: restore a deleted workspace",
+            "duration_minutes": 60,
+        }))
+        .to_request()
+    };
+    let resp = test::call_service(&app, request_admin(&oncall_token)).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] An admin makes the on-call user eligible, with approval.");
+    let req = authed(
+        test::TestRequest::post().uri("/admin/elevations/eligibility"),
+        &admin_token,
+    )
+    .set_json(serde_json::json!({
+        "subject_type": "user",
+        "subject_id": oncall_id,
+        "role": "admin",
+        "max_minutes": 60,
+    }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    println!("[>] The request waits for approval.");
+    let resp = test::call_service(&app, request_admin(&oncall_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let elevation: serde_json::Value = test::read_body_json(resp).await;
+    println!("[<] Response body: {}", elevation);
+    assert_eq!(elevation["status"], "pending");
+    let elevation_id = elevation["id"].as_str().unwrap().to_string();
+    let resp = test::call_service(&app, list_users(&oncall_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, request_admin(&oncall_token)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    println!("[>] Another admin approves it.");
+    let req = authed(
+        test::TestRequest::post().uri(&format!("/admin/elevations/{}/approve", elevation_id)),
+        &admin_token,
+    )
+    .to_request();
+    let approved: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    println!("[<] Response body: {}", approved);
+    assert_eq!(approved["status"], "active");
+    assert_eq!(approved["decided_by"], admin_id.to_string());
+
+    println!("[>] The user now acts as an admin, and validation says until when.");
+    let resp = test::call_service(&app, list_users(&oncall_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let validation = validate(&svc, &oncall_token).await;
+    assert_eq!(validation.role, "admin");
+    assert_eq!(validation.elevation_id, elevation_id);
+    assert!(validation.elevated_until.is_some());
+
+    println!("[>] An elevated admin cannot make the role permanent.");
+    let req = authed(
+        test::TestRequest::put().uri(&format!("/admin/users/{}/role", oncall_id)),
+        &oncall_token,
+    )
+    .set_json(serde_json::json!({ "role": "admin" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Nor mint an admin invite, or bind a global role.");
+    let req = authed(
+        test::TestRequest::post().uri("/admin/invites"),
+        &oncall_token,
+    )
+    .set_json(serde_json::json!({ "default_role": "admin" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let req = authed(
+        test::TestRequest::post().uri("/admin/rbac/bindings"),
+        &oncall_token,
+    )
+    .set_json(serde_json::json!({
+        "subject_type": "user",
+        "subject_id": oncall_id,
+        "role_id": uuid::Uuid::new_v4(),
+        "scope": "*",
+    }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Nor change plans, entitlements or keys.");
+    let req = authed(test::TestRequest::post().uri("/admin/plans"), &oncall_token)
+        .set_json(serde_json::json!({ "name": "unlimited" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let req = authed(
+        test::TestRequest::put().uri(&format!("/admin/entitlements/user/{}", oncall_id)),
+        &oncall_token,
+    )
+    .set_json(serde_json::json!({ "plan_id": null }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let req = authed(
+        test::TestRequest::post().uri("/admin/kms/rotate"),
+        &oncall_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Reading is still allowed.");
+    for uri in ["/admin/invites", "/admin/plans", "/admin/audit"] {
+        let req = authed(test::TestRequest::get().uri(uri), &oncall_token).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK, "{uri}");
+    }
+
+    println!("[>] The user gives the rights back early.");
+    let req = authed(
+        test::TestRequest::delete().uri(&format!("/user/elevations/{}", elevation_id)),
+        &oncall_token,
+    )
+    .to_request();
+    let ended: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(ended["status"], "revoked");
+    let resp = test::call_service(&app, list_users(&oncall_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let validation = validate(&svc, &oncall_token).await;
+    assert_eq!(validation.role, "member");
+    assert_eq!(validation.elevated_until, None);
+
+    let (events, _) = ctx
+        .db
+        .list_audit_events(&RAuditList {
+            target_id: Some(elevation_id.parse().unwrap()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let actions: Vec<&str> = events.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            "elevation.revoked",
+            "elevation.approved",
+            "elevation.requested"
+        ]
+    );
+    println!("[/] Test passed: Elevation is approved, applied, visible and audited.");
+}
+
+#[tokio::test]
+async fn test_elevation_flow_team_eligibility_without_approval() {
+    println!("\n\n[+] Running test: test_elevation_flow_team_eligibility_without_approval");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (admin_id, _) = client.create_test_admin().await;
+    let (sre_id, sre_token) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&sre_id, "SRE").await.unwrap();
+    ctx.db
+        .create_elevation_eligibility(
+            SubjectType::Team,
+            &team.id,
+            UserRole::Admin,
+            30,
+            false,
+            Some(admin_id),
+        )
+        .await
+        .unwrap();
+
+    println!("[>] Longer than the eligibility allows is refused.");
+    let request = |minutes: i32| {
+        authed(
+            test::TestRequest::post().uri("/user/elevations/request"),
+            &sre_token,
+        )
+        .set_json(serde_json::json!({
+            "role": "admin",
+            "reason": "rotate the KMS master key",
+            "duration_minutes": minutes,
+        }))
+        .to_request()
+    };
+    let resp = test::call_service(&app, request(90)).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    println!("[>] Within it, the elevation starts right away.");
+    let elevation: serde_json::Value = test::call_and_read_body_json(&app, request(30)).await;
+    println!("[<] Response body: {}", elevation);
+    assert_eq!(elevation["status"], "active");
+    assert!(elevation["expires_at"].is_string());
+    let req = authed(test::TestRequest::get().uri("/admin/users"), &sre_token).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] An elevated admin cannot approve or grant further elevation.");
+    let req = authed(
+        test::TestRequest::post().uri("/admin/elevations/eligibility"),
+        &sre_token,
+    )
+    .set_json(serde_json::json!({
+        "subject_type": "user",
+        "subject_id": sre_id,
+        "role": "admin",
+        "max_minutes": 1440,
+        "requires_approval": false,
+    }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] An admin revokes it.");
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let req = authed(
+        test::TestRequest::post().uri(&format!(
+            "/admin/elevations/{}/revoke",
+            elevation["id"].as_str().unwrap()
+        )),
+        &admin_key,
+    )
+    .to_request();
+    let revoked: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(revoked["status"], "revoked");
+    let req = authed(test::TestRequest::get().uri("/admin/users"), &sre_token).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let (events, _) = ctx
+        .db
+        .list_audit_events(&RAuditList {
+            action: Some("elevation.activated".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].actor_id, Some(sre_id));
+    println!("[/] Test passed: Team members elevate themselves within the allowed time.");
+}
@@ -0,0 +1,219 @@
+mod common;
+
+use actix_web::{cookie::Cookie, dev::ServiceResponse, http::StatusCode, test};
+use common::{client::TestClient, mock_idp, TestContext, MOCK_IDP_ISSUER};
+use std::collections::HashMap;
+
+fn start(provider: &str) -> test::TestRequest {
+    test::TestRequest::get().uri(&format!("/auth/federated/{}/start", provider))
+}
+
+/// The `state` and `nonce` a started login sent to the IdP.
+fn login_params<B>(resp: &ServiceResponse<B>) -> (String, String) {
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    assert!(location.starts_with(&format!("{}/authorize", MOCK_IDP_ISSUER)));
+
+    let params: HashMap<String, String> = url::Url::parse(location)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+    assert_eq!(params["code_challenge_method"], "S256");
+    let cookie = resp
+        .response()
+        .cookies()
+        .find(|c| c.name() == "ledger_federated")
+        .expect("state cookie");
+    assert_eq!(cookie.value(), params["state"]);
+    (params["state"].clone(), params["nonce"].clone())
+}
+
+fn callback(provider: &str, state: &str, claims: serde_json::Value) -> test::TestRequest {
+    test::TestRequest::get()
+        .uri(&format!(
+            "/auth/federated/{}/callback?code={}&state={}",
+            provider,
+            mock_idp::code_for(claims),
+            state
+        ))
+        .cookie(Cookie::new("ledger_federated", state.to_string()))
+}
+
+#[tokio::test]
+async fn test_federated_flow_creates_then_reuses_account() {
+    println!("\n\n[+] Running test: test_federated_flow_creates_then_reuses_account");
+    mock_idp::start();
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let req = test::TestRequest::get().uri("/auth/federated").to_request();
+    let providers: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(providers[0]["id"], "mock");
+    assert_eq!(providers[1]["id"], "partner");
+
+    println!("[>] First login through the IdP creates the account.");
+    let (state, nonce) = login_params(&test::call_service(&app, start("mock").to_request()).await);
+    let claims = serde_json::json!({
+        "sub": "emp-1001",
+        "email": "Ada@Corp.example",
+        "email_verified": true,
+        "name": "Ada Lovelace",
+        "nonce": nonce,
+    });
+    let resp =
+        test::call_service(&app, callback("mock", &state, claims.clone()).to_request()).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp
+        .response()
+        .cookies()
+        .any(|c| c.name() == "ledger_session"));
+
+    let user = ctx.db.get_user_by_email("ada@corp.example").await.unwrap();
+    assert_eq!(user.name, "Ada Lovelace");
+    assert!(user.email_verified_at.is_some());
+
+    println!("[>] Replaying the same state is refused.");
+    let resp = test::call_service(&app, callback("mock", &state, claims).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    println!("[>] Second login finds the linked account by subject.");
+    let (state, nonce) = login_params(&test::call_service(&app, start("mock").to_request()).await);
+    let resp = test::call_service(
+        &app,
+        callback(
+            "mock",
+            &state,
+            serde_json::json!({ "sub": "emp-1001", "email": "ada@new.example", "nonce": nonce }),
+        )
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let sessions = ctx.db.list_sessions(&user.id).await.unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].auth_method, "federated:mock");
+
+    println!("[>] A wrong nonce is refused.");
+    let (state, _nonce) = login_params(&test::call_service(&app, start("mock").to_request()).await);
+    let resp = test::call_service(
+        &app,
+        callback(
+            "mock",
+            &state,
+            serde_json::json!({ "sub": "emp-1001", "nonce": "forged" }),
+        )
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    println!("[/] Test passed: Federated login provisions and reuses accounts.");
+}
+
+#[tokio::test]
+async fn test_federated_flow_links_only_verified_email() {
+    println!("\n\n[+] Running test: test_federated_flow_links_only_verified_email");
+    mock_idp::start();
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _token) = client
+        .create_test_user(Some("grace@corp.example".to_string()))
+        .await
+        .expect("Failed creating a test user");
+
+    println!("[>] Unverified email neither links nor creates.");
+    let (state, nonce) =
+        login_params(&test::call_service(&app, start("partner").to_request()).await);
+    let resp = test::call_service(
+        &app,
+        callback(
+            "partner",
+            &state,
+            serde_json::json!({
+                "sub": "p-7",
+                "email": "grace@corp.example",
+                "email_verified": false,
+                "nonce": nonce,
+            }),
+        )
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert!(ctx
+        .db
+        .find_federated_identity("partner", "p-7")
+        .await
+        .unwrap()
+        .is_none());
+
+    println!("[>] Verified email links to the existing account.");
+    let (state, nonce) =
+        login_params(&test::call_service(&app, start("partner").to_request()).await);
+    let resp = test::call_service(
+        &app,
+        callback(
+            "partner",
+            &state,
+            serde_json::json!({
+                "sub": "p-7",
+                "email": "grace@corp.example",
+                "email_verified": true,
+                "nonce": nonce,
+            }),
+        )
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let link = ctx
+        .db
+        .find_federated_identity("partner", "p-7")
+        .await
+        .unwrap()
+        .expect("identity linked");
+    assert_eq!(link.user_id, user_id);
+
+    println!("[>] Provider without auto-create refuses strangers.");
+    let (state, nonce) =
+        login_params(&test::call_service(&app, start("partner").to_request()).await);
+    let resp = test::call_service(
+        &app,
+        callback(
+            "partner",
+            &state,
+            serde_json::json!({
+                "sub": "p-8",
+                "email": "stranger@corp.example",
+                "email_verified": true,
+                "nonce": nonce,
+            }),
+        )
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Callback without the browser's state cookie is refused.");
+    let (state, nonce) =
+        login_params(&test::call_service(&app, start("partner").to_request()).await);
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/auth/federated/partner/callback?code={}&state={}",
+            mock_idp::code_for(serde_json::json!({ "sub": "p-7", "nonce": nonce })),
+            state
+        ))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    println!("[/] Test passed: Linking requires a verified email.");
+}
@@ -0,0 +1,61 @@
+use ledger_auth::utils::kms::{new_data_key, Keyring, MasterKey, DATA_KEY_LEN};
+use uuid::Uuid;
+
+#[test]
+fn test_kms_wrap_round_trip() {
+    let master = MasterKey::new([1u8; 32]);
+    let workspace = Uuid::new_v4();
+    let data_key = new_data_key();
+    assert_eq!(data_key.len(), DATA_KEY_LEN);
+
+    let wrapped = master.wrap(&workspace, &data_key);
+    assert_ne!(
+        &wrapped[wrapped.len() - DATA_KEY_LEN..],
+        data_key.as_slice()
+    );
+    assert_eq!(master.unwrap(&workspace, &wrapped), Some(data_key.clone()));
+
+    // Fresh nonce every time.
+    assert_ne!(master.wrap(&workspace, &data_key), wrapped);
+}
+
+#[test]
+fn test_kms_unwrap_rejects_wrong_key_workspace_or_tampering() {
+    let master = MasterKey::new([1u8; 32]);
+    let workspace = Uuid::new_v4();
+    let wrapped = master.wrap(&workspace, &new_data_key());
+
+    assert_eq!(MasterKey::new([2u8; 32]).unwrap(&workspace, &wrapped), None);
+    assert_eq!(master.unwrap(&Uuid::new_v4(), &wrapped), None);
+
+    let mut tampered = wrapped.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(master.unwrap(&workspace, &tampered), None);
+    assert_eq!(master.unwrap(&workspace, &wrapped[..12]), None);
+}
+
+#[test]
+fn test_kms_master_key_ids_and_parsing() {
+    let a = MasterKey::new([1u8; 32]);
+    assert_eq!(a.id(), MasterKey::new([1u8; 32]).id());
+    assert_ne!(a.id(), MasterKey::new([2u8; 32]).id());
+    assert!(!format!("{a:?}").contains("key:"));
+
+    let encoded = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=";
+    assert_eq!(MasterKey::from_base64(encoded).unwrap().id(), a.id());
+    assert!(MasterKey::from_base64("AQEB").is_err());
+    assert!(MasterKey::from_base64("not base64!").is_err());
+}
+
+#[test]
+fn test_kms_keyring_finds_current_and_retired_keys() {
+    let old = MasterKey::new([1u8; 32]);
+    let new = MasterKey::new([2u8; 32]);
+    let keyring = Keyring {
+        current: new.clone(),
+        retired: vec![old.clone()],
+    };
+    assert_eq!(keyring.get(new.id()).unwrap().id(), new.id());
+    assert_eq!(keyring.get(old.id()).unwrap().id(), old.id());
+    assert!(keyring.get(MasterKey::new([3u8; 32]).id()).is_none());
+}
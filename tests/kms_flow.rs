@@ -0,0 +1,192 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::grpc::authentication::AuthenticationSvc;
+use ledger_auth::grpc::pb::{
+    authentication_server::Authentication, WorkspaceKeyRequest, WorkspaceKeyResponse,
+};
+use ledger_auth::types::audit::RAuditList;
+use ledger_auth::utils::kms::{Keyring, MasterKey};
+use tonic::{Code, Request, Status};
+
+async fn workspace_key(
+    svc: &AuthenticationSvc,
+    subject: &str,
+    workspace_id: &str,
+    auth_key: &str,
+) -> Result<WorkspaceKeyResponse, Status> {
+    let mut request = Request::new(WorkspaceKeyRequest {
+        subject: subject.to_string(),
+        workspace_id: workspace_id.to_string(),
+    });
+    request
+        .metadata_mut()
+        .insert("authorization", auth_key.parse().unwrap());
+    svc.get_workspace_key(request)
+        .await
+        .map(|response| response.into_inner())
+}
+
+#[tokio::test]
+async fn test_kms_flow_only_members_receive_the_workspace_key() {
+    println!("\n\n[+] Running test: test_kms_flow_only_members_receive_the_workspace_key");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let svc = AuthenticationSvc::new(ctx.db.clone());
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+
+    let (owner_id, _) = client.create_test_user(None).await.unwrap();
+    let (member_id, _) = client.create_test_user(None).await.unwrap();
+    let (outsider_id, _) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&owner_id, "Finance").await.unwrap();
+    ctx.db
+        .add_team_member(
+            &team.id,
+            &member_id,
+            entity::team_membership::TeamRole::Member,
+        )
+        .await
+        .unwrap();
+    let workspace = team.id.to_string();
+
+    println!("[>] The owner asks for the key first, which creates it.");
+    let first = workspace_key(&svc, &owner_id.to_string(), &workspace, &grpc_auth_key)
+        .await
+        .unwrap();
+    assert_eq!(first.workspace_id, workspace);
+    assert_eq!(first.key.len(), 32);
+
+    println!("[>] Another member gets the same key.");
+    let second = workspace_key(&svc, &member_id.to_string(), &workspace, &grpc_auth_key)
+        .await
+        .unwrap();
+    assert_eq!(second.key, first.key);
+
+    println!("[>] Outsiders and callers without the service key get nothing.");
+    let err = workspace_key(&svc, &outsider_id.to_string(), &workspace, &grpc_auth_key)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+    let err = workspace_key(&svc, &owner_id.to_string(), &workspace, "wrong")
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    println!("[>] Deleting the team destroys its key.");
+    let (_, code) = ctx
+        .db
+        .create_team_deletion(&team.id, &owner_id)
+        .await
+        .unwrap();
+    ctx.db
+        .confirm_team_deletion(&team.id, &owner_id, &code)
+        .await
+        .unwrap();
+    let (events, _) = ctx
+        .db
+        .list_audit_events(&RAuditList {
+            action: Some("team.deleted".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(events[0].detail["revoked"]["workspace_keys_destroyed"], 1);
+    println!(
+        "[/] Test passed: Workspace key handed out to members only and destroyed with the team."
+    );
+}
+
+#[tokio::test]
+async fn test_kms_flow_rotation_rewraps_every_key() {
+    println!("\n\n[+] Running test: test_kms_flow_rotation_rewraps_every_key");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (owner_id, _) = client.create_test_user(None).await.unwrap();
+    let finance = ctx.db.create_team(&owner_id, "Finance").await.unwrap();
+    let legal = ctx.db.create_team(&owner_id, "Legal").await.unwrap();
+
+    let old = MasterKey::new([1u8; 32]);
+    let new = MasterKey::new([2u8; 32]);
+    let before = Keyring {
+        current: old.clone(),
+        retired: vec![],
+    };
+    let finance_key = ctx
+        .db
+        .workspace_data_key(&before, &finance.id)
+        .await
+        .unwrap();
+    let legal_key = ctx.db.workspace_data_key(&before, &legal.id).await.unwrap();
+
+    println!("[>] Rotating without the old key configured rewraps nothing.");
+    let new_only = Keyring {
+        current: new.clone(),
+        retired: vec![],
+    };
+    let rotation = ctx.db.rotate_workspace_keys(&new_only, None).await.unwrap();
+    assert_eq!(rotation.rewrapped, 0);
+    assert_eq!(rotation.failed.len(), 2);
+    assert!(ctx
+        .db
+        .workspace_data_key(&new_only, &finance.id)
+        .await
+        .is_err());
+
+    println!("[>] With the old key retired, everything moves to the new one.");
+    let during = Keyring {
+        current: new.clone(),
+        retired: vec![old],
+    };
+    let rotation = ctx
+        .db
+        .rotate_workspace_keys(&during, Some(owner_id))
+        .await
+        .unwrap();
+    assert_eq!(rotation.rewrapped, 2);
+    assert!(rotation.failed.is_empty());
+    assert_eq!(
+        ctx.db
+            .workspace_data_key(&new_only, &finance.id)
+            .await
+            .unwrap(),
+        finance_key
+    );
+    assert_eq!(
+        ctx.db
+            .workspace_data_key(&new_only, &legal.id)
+            .await
+            .unwrap(),
+        legal_key
+    );
+
+    println!("[>] The admin endpoint rotates onto the configured master key.");
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let req = test::TestRequest::post()
+        .uri("/admin/kms/rotate")
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    println!("[<] Response body: {}", body);
+    // The test config doesn't retire the key used above.
+    assert_eq!(body["rewrapped"], 0);
+    assert_eq!(body["failed"].as_array().unwrap().len(), 2);
+
+    let (events, _) = ctx
+        .db
+        .list_audit_events(&RAuditList {
+            action: Some("kms.master_key_rotated".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1].actor_id, Some(owner_id));
+    println!("[/] Test passed: Rotation rewraps every key without changing it.");
+}
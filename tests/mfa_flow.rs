@@ -0,0 +1,189 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use chrono::Utc;
+use common::{client::TestClient, TestContext};
+use ledger_auth::utils::totp::{base32_decode, totp_code, TOTP_DIGITS, TOTP_STEP_SECONDS};
+use ledger_auth::utils::webutils::OTP_HEADER;
+
+fn code_at_offset(secret: &str, steps: i64) -> String {
+    let secret = base32_decode(secret).unwrap();
+    let step = Utc::now().timestamp() / TOTP_STEP_SECONDS + steps;
+    totp_code(&secret, step, TOTP_DIGITS)
+}
+
+#[tokio::test]
+async fn test_totp_flow_guards_sensitive_routes() {
+    println!("\n\n[+] Running test: test_totp_flow_guards_sensitive_routes");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (_user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    println!("[>] Starting TOTP enrollment.");
+    let req = test::TestRequest::post()
+        .uri("/user/mfa/totp")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let secret = body["secret"].as_str().unwrap().to_string();
+    assert!(body["otpauth_uri"]
+        .as_str()
+        .unwrap()
+        .starts_with("otpauth://totp/"));
+
+    println!("[>] Confirming TOTP enrollment.");
+    let enrollment_code = code_at_offset(&secret, 0);
+    let req = test::TestRequest::post()
+        .uri("/user/mfa/totp/confirm")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({ "code": enrollment_code }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let recovery_codes: Vec<String> = body["recovery_codes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(recovery_codes.len(), 10);
+
+    println!("[>] Regenerating without a one-time code (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/regenerate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    println!("[>] Replaying the enrollment code (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/regenerate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .insert_header((OTP_HEADER, enrollment_code.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    println!("[>] Regenerating with a recovery code.");
+    let req = test::TestRequest::post()
+        .uri("/user/regenerate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .insert_header((OTP_HEADER, recovery_codes[0].clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    println!("[/] Test passed: TOTP guards token regeneration.");
+}
+
+#[tokio::test]
+async fn test_totp_flow_recovery_code_single_use() {
+    println!("\n\n[+] Running test: test_totp_flow_recovery_code_single_use");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+
+    let (user_id, _user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    let secret = ctx.db.begin_totp_enrollment(&user_id).await.unwrap();
+    let codes = ctx
+        .db
+        .confirm_totp(&user_id, &code_at_offset(&secret, 0))
+        .await
+        .unwrap();
+    assert!(ctx.db.totp_enabled(&user_id).await.unwrap());
+
+    assert!(ctx
+        .db
+        .verify_second_factor(&user_id, &codes[3])
+        .await
+        .unwrap());
+    assert!(!ctx
+        .db
+        .verify_second_factor(&user_id, &codes[3])
+        .await
+        .unwrap());
+    assert!(ctx
+        .db
+        .verify_second_factor(&user_id, &code_at_offset(&secret, 1))
+        .await
+        .unwrap());
+    println!("[/] Test passed: Second factors are single use.");
+}
+
+#[tokio::test]
+async fn test_totp_flow_users_without_totp_unaffected() {
+    println!("\n\n[+] Running test: test_totp_flow_users_without_totp_unaffected");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    println!("[>] Deleting account without TOTP.");
+    let req = test::TestRequest::delete()
+        .uri("/user/delete")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(ctx.db.get_user_by_id(&user_id).await.is_err());
+    println!("[/] Test passed: Users without TOTP only need their token.");
+}
+
+#[tokio::test]
+async fn test_totp_flow_locks_after_repeated_failures() {
+    println!("\n\n[+] Running test: test_totp_flow_locks_after_repeated_failures");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+
+    let (user_id, _user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    let secret = ctx.db.begin_totp_enrollment(&user_id).await.unwrap();
+    let codes = ctx
+        .db
+        .confirm_totp(&user_id, &code_at_offset(&secret, 0))
+        .await
+        .unwrap();
+
+    println!("[>] Guessing wrong codes until the lockout.");
+    for _ in 0..5 {
+        assert!(!ctx
+            .db
+            .verify_second_factor(&user_id, "not-a-code")
+            .await
+            .unwrap());
+    }
+    assert!(!ctx
+        .db
+        .verify_second_factor(&user_id, &codes[0])
+        .await
+        .unwrap());
+    assert!(!ctx
+        .db
+        .verify_second_factor(&user_id, &code_at_offset(&secret, 1))
+        .await
+        .unwrap());
+    println!("[/] Test passed: Valid codes are refused while locked out.");
+}
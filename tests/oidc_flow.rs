@@ -0,0 +1,309 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use common::{client::TestClient, TestContext};
+use ledger_auth::utils::{
+    jwt::{rsa_from_jwk, verify_rs256},
+    oidc::pkce_challenge,
+};
+use std::collections::HashMap;
+
+const REDIRECT_URI: &str = "https://app.example.com/callback";
+const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+fn query_of(location: &str) -> HashMap<String, String> {
+    url::Url::parse(location)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect()
+}
+
+fn authorize_query(client_id: &str) -> String {
+    format!(
+        "/oidc/authorize?response_type=code&client_id={}&redirect_uri={}&scope=openid%20email&state=xyz&nonce=n-0S6&code_challenge={}&code_challenge_method=S256",
+        client_id,
+        urlencoding::encode(REDIRECT_URI),
+        pkce_challenge(VERIFIER)
+    )
+}
+
+#[tokio::test]
+async fn test_oidc_flow_code_exchange_and_id_token() {
+    println!("\n\n[+] Running test: test_oidc_flow_code_exchange_and_id_token");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    println!("[>] Admin registering a confidential client.");
+    let req = test::TestRequest::post()
+        .uri("/admin/oauth-clients")
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .set_json(serde_json::json!({
+            "name": "Wiki",
+            "redirect_uris": [REDIRECT_URI],
+            "confidential": true
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let client_id = body["client_id"].as_str().unwrap().to_string();
+    let client_secret = body["client_secret"].as_str().unwrap().to_string();
+
+    println!("[>] Fetching discovery metadata and keys.");
+    let req = test::TestRequest::get()
+        .uri("/.well-known/openid-configuration")
+        .to_request();
+    let discovery: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let issuer = discovery["issuer"].as_str().unwrap().to_string();
+    assert_eq!(
+        discovery["code_challenge_methods_supported"],
+        serde_json::json!(["S256"])
+    );
+    let req = test::TestRequest::get().uri("/oidc/jwks").to_request();
+    let jwks: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let jwk = &jwks["keys"][0];
+
+    println!("[>] User starting the authorization request.");
+    let req = test::TestRequest::get()
+        .uri(&authorize_query(&client_id))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["client_name"], "Wiki");
+    assert_eq!(body["scopes"], serde_json::json!(["openid", "email"]));
+
+    println!("[>] User approving the consent prompt.");
+    let req = test::TestRequest::post()
+        .uri("/oidc/authorize")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({
+            "response_type": "code",
+            "client_id": client_id,
+            "redirect_uri": REDIRECT_URI,
+            "scope": "openid email",
+            "state": "xyz",
+            "nonce": "n-0S6",
+            "code_challenge": pkce_challenge(VERIFIER),
+            "code_challenge_method": "S256",
+            "approve": true
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let redirect = query_of(body["redirect_to"].as_str().unwrap());
+    assert_eq!(redirect["state"], "xyz");
+    assert_eq!(redirect["iss"], issuer);
+    let code = redirect["code"].clone();
+
+    let basic = format!(
+        "Basic {}",
+        STANDARD.encode(format!("{}:{}", client_id, client_secret))
+    );
+    let exchange = |verifier: &str| {
+        test::TestRequest::post()
+            .uri("/oauth/token")
+            .insert_header(("Authorization", basic.clone()))
+            .set_form([
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("redirect_uri", REDIRECT_URI),
+                ("code_verifier", verifier),
+            ])
+            .to_request()
+    };
+
+    println!("[>] Client exchanging the code with the wrong verifier.");
+    let resp = test::call_service(&app, exchange(&"x".repeat(43))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "invalid_grant");
+
+    println!("[>] Client exchanging the code with the right verifier.");
+    let resp = test::call_service(&app, exchange(VERIFIER)).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let access_token = body["access_token"].as_str().unwrap().to_string();
+    let id_token = body["id_token"].as_str().unwrap();
+
+    let key = rsa_from_jwk(jwk["n"].as_str().unwrap(), jwk["e"].as_str().unwrap()).unwrap();
+    let claims: serde_json::Value = verify_rs256(id_token, &key).expect("ID token must verify");
+    assert_eq!(claims["iss"], issuer);
+    assert_eq!(claims["aud"], client_id);
+    assert_eq!(claims["sub"], user_id.to_string());
+    assert_eq!(claims["nonce"], "n-0S6");
+    assert!(claims["email"].is_string());
+    assert!(claims.get("name").is_none());
+
+    println!("[>] Client calling userinfo.");
+    let userinfo = || {
+        test::TestRequest::get()
+            .uri("/oidc/userinfo")
+            .insert_header(("Authorization", format!("Bearer {}", access_token)))
+            .to_request()
+    };
+    let resp = test::call_service(&app, userinfo()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["sub"], user_id.to_string());
+
+    println!("[>] Replaying the code revokes what it issued.");
+    let resp = test::call_service(&app, exchange(VERIFIER)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        test::call_service(&app, userinfo()).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    println!("[>] Second login skips the prompt.");
+    let req = test::TestRequest::get()
+        .uri(&authorize_query(&client_id))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    assert!(location.starts_with(REDIRECT_URI));
+    assert!(query_of(location).contains_key("code"));
+    println!("[/] Test passed: Code exchange yielded a verifiable ID token.");
+}
+
+#[tokio::test]
+async fn test_oidc_flow_rejects_bad_requests_and_revoked_consent() {
+    println!("\n\n[+] Running test: test_oidc_flow_rejects_bad_requests_and_revoked_consent");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let (oauth_client, secret) = ctx
+        .db
+        .create_oauth_client("Desktop", &[REDIRECT_URI.to_string()], false)
+        .await
+        .unwrap();
+    assert!(secret.is_none());
+    let client_id = oauth_client.client_id.clone();
+
+    println!("[>] Unregistered redirect URI is shown, not followed.");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/oidc/authorize?response_type=code&client_id={}&redirect_uri=https%3A%2F%2Fevil.example%2F&scope=openid",
+            client_id
+        ))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    println!("[>] Missing PKCE is sent back to the client.");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/oidc/authorize?response_type=code&client_id={}&redirect_uri={}&scope=openid&state=s1",
+            client_id,
+            urlencoding::encode(REDIRECT_URI)
+        ))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    let redirect = query_of(location);
+    assert_eq!(redirect["error"], "invalid_request");
+    assert_eq!(redirect["state"], "s1");
+
+    println!("[>] Public client completes the flow without a secret.");
+    ctx.db
+        .grant_consent(&user_id, &oauth_client.id, &["openid".to_string()])
+        .await
+        .unwrap();
+    let req = test::TestRequest::get()
+        .uri(&authorize_query(&client_id).replace("openid%20email", "openid"))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    let code = query_of(location)["code"].clone();
+
+    let req = test::TestRequest::post()
+        .uri("/oauth/token")
+        .set_form([
+            ("grant_type", "authorization_code"),
+            ("client_id", client_id.as_str()),
+            ("client_secret", "guess"),
+            ("code", code.as_str()),
+            ("redirect_uri", REDIRECT_URI),
+            ("code_verifier", VERIFIER),
+        ])
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/oauth/token")
+        .set_form([
+            ("grant_type", "authorization_code"),
+            ("client_id", client_id.as_str()),
+            ("code", code.as_str()),
+            ("redirect_uri", REDIRECT_URI),
+            ("code_verifier", VERIFIER),
+        ])
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let access_token = body["access_token"].as_str().unwrap().to_string();
+
+    println!("[>] User withdrawing consent.");
+    let req = test::TestRequest::get()
+        .uri("/user/consents")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let consents: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(consents[0]["client_name"], "Desktop");
+    let consent_id = consents[0]["id"].as_str().unwrap();
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/user/consents/{}", consent_id))
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/oidc/userinfo")
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    println!("[/] Test passed: Bad requests refused and consent revocation enforced.");
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::grpc::pb::authentication_server::Authentication;
+use tonic::Request;
+use webauthn_authenticator_rs::{softpasskey::SoftPasskey, WebauthnAuthenticator};
+use webauthn_rs::prelude::{CreationChallengeResponse, RequestChallengeResponse, Url};
+
+fn origin() -> Url {
+    Url::parse(&ledger_auth::config::config().webauthn.rp_origin).unwrap()
+}
+
+#[tokio::test]
+async fn test_passkey_flow_register_and_login() {
+    println!("\n\n[+] Running test: test_passkey_flow_register_and_login");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, user_token) = client
+        .create_test_user(Some("passkey@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+    let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+
+    println!("[>] Starting passkey registration.");
+    let req = test::TestRequest::post()
+        .uri("/user/passkeys/register/start")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let options: CreationChallengeResponse =
+        serde_json::from_value(body["options"].clone()).unwrap();
+    let credential = authenticator
+        .do_registration(origin(), options)
+        .expect("Software authenticator failed to register");
+
+    println!("[>] Finishing passkey registration.");
+    let req = test::TestRequest::post()
+        .uri("/user/passkeys/register/finish")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({
+            "ceremony_id": body["ceremony_id"],
+            "name": "Laptop",
+            "credential": credential,
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let req = test::TestRequest::get()
+        .uri("/user/passkeys")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["name"], "Laptop");
+
+    println!("[>] Logging in with the passkey.");
+    let req = test::TestRequest::post()
+        .uri("/auth/passkey/start")
+        .set_json(serde_json::json!({ "email": "passkey@example.com" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let options: RequestChallengeResponse =
+        serde_json::from_value(body["options"].clone()).unwrap();
+    let assertion = authenticator
+        .do_authentication(origin(), options)
+        .expect("Software authenticator failed to sign in");
+
+    let req = test::TestRequest::post()
+        .uri("/auth/passkey/finish")
+        .set_json(serde_json::json!({
+            "ceremony_id": body["ceremony_id"],
+            "credential": assertion,
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let session_token = body["token"].as_str().unwrap().to_string();
+
+    println!("[>] Validating the session token.");
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", session_token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let auth_svc = ledger_auth::grpc::authentication::AuthenticationSvc::new(ctx.db.clone());
+    let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
+        token: session_token,
+        ..Default::default()
+    });
+    request.metadata_mut().insert(
+        "authorization",
+        ledger_auth::config::config().grpc.auth_key.parse().unwrap(),
+    );
+    let validation_response = auth_svc
+        .validate_authentication(request)
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", validation_response);
+    assert!(validation_response.is_valid);
+    assert_eq!(validation_response.user_id, user_id.to_string());
+    println!("[/] Test passed: Passkey registered and used to open a session.");
+}
+
+#[tokio::test]
+async fn test_passkey_flow_login_unavailable_without_passkey() {
+    println!("\n\n[+] Running test: test_passkey_flow_login_unavailable_without_passkey");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    client
+        .create_test_user(Some("nopasskey@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+
+    let mut bodies = Vec::new();
+    for email in ["nopasskey@example.com", "nobody@example.com"] {
+        let req = test::TestRequest::post()
+            .uri("/auth/passkey/start")
+            .set_json(serde_json::json!({ "email": email }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        println!("[<] Received response with status: {}", resp.status());
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        bodies.push(body);
+    }
+
+    assert_eq!(bodies[0], bodies[1]);
+    println!("[/] Test passed: Passkey login refused the same way for both.");
+}
+
+#[tokio::test]
+async fn test_passkey_flow_ceremony_is_single_use() {
+    println!("\n\n[+] Running test: test_passkey_flow_ceremony_is_single_use");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (_user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+
+    let req = test::TestRequest::post()
+        .uri("/user/passkeys/register/start")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let options: CreationChallengeResponse =
+        serde_json::from_value(body["options"].clone()).unwrap();
+    let credential = authenticator.do_registration(origin(), options).unwrap();
+    let finish = serde_json::json!({
+        "ceremony_id": body["ceremony_id"],
+        "credential": credential,
+    });
+
+    let req = test::TestRequest::post()
+        .uri("/user/passkeys/register/finish")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(&finish)
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::CREATED
+    );
+
+    println!("[>] Replaying the registration answer (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/passkeys/register/finish")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(&finish)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Ceremonies can't be replayed.");
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+
+#[tokio::test]
+async fn test_password_flow_set_and_login() {
+    println!("\n\n[+] Running test: test_password_flow_set_and_login");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, _user_token) = client
+        .create_test_user(Some("password@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+    let token = ctx
+        .db
+        .create_password_token(&user_id)
+        .await
+        .expect("Failed to create password token");
+
+    println!("[>] Setting a weak password (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/password")
+        .set_json(serde_json::json!({ "token": token, "password": "short" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    println!("[>] Setting a good password with the same token.");
+    let req = test::TestRequest::post()
+        .uri("/user/password")
+        .set_json(serde_json::json!({ "token": token, "password": "correct horse battery" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] Logging in.");
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(serde_json::json!({
+            "email": "password@example.com",
+            "password": "correct horse battery",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let session_token = body["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", session_token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    println!("[>] Reusing the spent token (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/password")
+        .set_json(serde_json::json!({ "token": token, "password": "another long passphrase" }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    println!("[/] Test passed: Password set by mailed token and used to log in.");
+}
+
+#[tokio::test]
+async fn test_password_flow_failures_look_the_same() {
+    println!("\n\n[+] Running test: test_password_flow_failures_look_the_same");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _user_token) = client
+        .create_test_user(Some("haspassword@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+    client
+        .create_test_user(Some("nopassword@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+    let token = ctx.db.create_password_token(&user_id).await.unwrap();
+    ctx.db
+        .set_password_with_token(&token, "correct horse battery")
+        .await
+        .unwrap();
+
+    let mut bodies = Vec::new();
+    for (email, password) in [
+        ("haspassword@example.com", "wrong horse battery"),
+        ("nopassword@example.com", "correct horse battery"),
+        ("nobody@example.com", "correct horse battery"),
+    ] {
+        println!("[>] Logging in as {} (expecting failure).", email);
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(serde_json::json!({ "email": email, "password": password }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        println!("[<] Received response with status: {}", resp.status());
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        bodies.push(body);
+    }
+
+    assert_eq!(bodies[0], bodies[1]);
+    assert_eq!(bodies[1], bodies[2]);
+    println!("[/] Test passed: Login failures are indistinguishable.");
+}
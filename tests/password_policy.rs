@@ -0,0 +1,27 @@
+use ledger_auth::types::error::AppError;
+use ledger_auth::utils::password::{check_password_policy, hash_password, password_matches};
+
+#[test]
+fn test_password_policy_length() {
+    assert!(matches!(
+        check_password_policy("short", "a@corp.com"),
+        Err(AppError::Validation(_))
+    ));
+    assert!(check_password_policy(&"x1".repeat(65), "a@corp.com").is_err());
+    assert!(check_password_policy("correct horse battery", "a@corp.com").is_ok());
+}
+
+#[test]
+fn test_password_policy_refuses_weak_passwords() {
+    assert!(check_password_policy("PasswordPassword", "a@corp.com").is_err());
+    assert!(check_password_policy("aaaaaaaaaaaaaaaa", "a@corp.com").is_err());
+    assert!(check_password_policy("my-jane.doe-secret", "jane.doe@corp.com").is_err());
+}
+
+#[test]
+fn test_password_matches_without_stored_hash() {
+    let hash = hash_password("correct horse battery").unwrap();
+    assert!(password_matches("correct horse battery", Some(&hash)));
+    assert!(!password_matches("wrong horse battery", Some(&hash)));
+    assert!(!password_matches("correct horse battery", None));
+}
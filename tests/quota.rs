@@ -0,0 +1,75 @@
+use ledger_auth::utils::quota::{is_valid_feature, Exceeded, Limits, Usage};
+
+fn usage(bytes: i64, files: i64, share_links: i64) -> Usage {
+    Usage {
+        bytes,
+        files,
+        share_links,
+    }
+}
+
+#[test]
+fn test_quota_no_limits_fits_anything() {
+    let limits = Limits::default();
+    assert_eq!(
+        limits.exceeded(&usage(i64::MAX, 0, 0), &usage(1, 1, 1)),
+        None
+    );
+}
+
+#[test]
+fn test_quota_reports_the_first_limit_exceeded() {
+    let limits = Limits {
+        max_bytes: Some(100),
+        max_files: Some(2),
+        max_share_links: Some(0),
+    };
+    let used = usage(60, 1, 0);
+    assert_eq!(limits.exceeded(&used, &usage(40, 1, 0)), None);
+    assert_eq!(
+        limits.exceeded(&used, &usage(41, 1, 0)),
+        Some(Exceeded::Bytes)
+    );
+    assert_eq!(
+        limits.exceeded(&used, &usage(0, 2, 0)),
+        Some(Exceeded::Files)
+    );
+    assert_eq!(
+        limits.exceeded(&used, &usage(0, 0, 1)),
+        Some(Exceeded::ShareLinks)
+    );
+}
+
+#[test]
+fn test_quota_only_growing_counters_are_checked() {
+    // A plan was lowered below what the subject already stores.
+    let limits = Limits {
+        max_bytes: Some(100),
+        max_files: Some(1),
+        max_share_links: None,
+    };
+    let used = usage(50, 5, 0);
+    assert_eq!(limits.exceeded(&used, &usage(10, 0, 0)), None);
+    assert_eq!(
+        limits.exceeded(&used, &usage(10, 1, 0)),
+        Some(Exceeded::Files)
+    );
+}
+
+#[test]
+fn test_quota_release_stops_at_zero() {
+    let used = usage(10, 2, 1);
+    assert_eq!(used.minus(&usage(4, 1, 1)), usage(6, 1, 0));
+    assert_eq!(used.minus(&usage(40, 5, 5)), usage(0, 0, 0));
+    assert_eq!(used.plus(&usage(1, 1, 1)), usage(11, 3, 2));
+    assert!(usage(0, -1, 0).is_negative());
+}
+
+#[test]
+fn test_quota_feature_names() {
+    assert!(is_valid_feature("public_links"));
+    assert!(is_valid_feature("sharing.password"));
+    assert!(!is_valid_feature(""));
+    assert!(!is_valid_feature("Public Links"));
+    assert!(!is_valid_feature("sharing."));
+}
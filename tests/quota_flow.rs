@@ -0,0 +1,190 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::grpc::authentication::AuthenticationSvc;
+use ledger_auth::grpc::pb::{
+    authentication_server::Authentication, EntitlementsRequest, QuotaUsage, UsageRequest,
+    UsageResponse,
+};
+use tonic::Request;
+
+fn admin(req: test::TestRequest) -> test::TestRequest {
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    req.insert_header(("Authorization", format!("Bearer {}", admin_key)))
+}
+
+fn with_auth_key<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+    request
+}
+
+fn usage_request(team_id: &str, bytes: i64, files: i64) -> Request<UsageRequest> {
+    with_auth_key(UsageRequest {
+        subject_type: "team".into(),
+        subject_id: team_id.to_string(),
+        delta: Some(QuotaUsage {
+            bytes,
+            files,
+            share_links: 0,
+        }),
+    })
+}
+
+async fn reserve(svc: &AuthenticationSvc, team_id: &str, bytes: i64, files: i64) -> UsageResponse {
+    let response = svc
+        .reserve_usage(usage_request(team_id, bytes, files))
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", response);
+    response
+}
+
+#[tokio::test]
+async fn test_quota_flow_plan_caps_team_storage() {
+    println!("\n\n[+] Running test: test_quota_flow_plan_caps_team_storage");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    let svc = AuthenticationSvc::new(ctx.db.clone());
+    println!("[+] Actix web app initialized.");
+
+    let (owner_id, _) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&owner_id, "Finance").await.unwrap();
+    let team_id = team.id.to_string();
+
+    println!("[>] An admin creates a plan and puts the team on it.");
+    let req = admin(test::TestRequest::post().uri("/admin/plans"))
+        .set_json(serde_json::json!({
+            "name": "team-small",
+            "max_bytes": 1000,
+            "max_files": 3,
+            "features": ["public_links", "public_links"],
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let plan: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(plan["features"], serde_json::json!(["public_links"]));
+    assert!(plan["max_share_links"].is_null());
+
+    let req = admin(test::TestRequest::put().uri(&format!("/admin/entitlements/team/{}", team_id)))
+        .set_json(serde_json::json!({ "plan_id": plan["id"] }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    println!("[<] Response body: {}", body);
+    assert_eq!(body["plan"]["name"], "team-small");
+
+    println!("[>] The file service reads the team's entitlements.");
+    let entitlements = svc
+        .get_entitlements(with_auth_key(EntitlementsRequest {
+            subject_type: "team".into(),
+            subject_id: team_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", entitlements);
+    assert_eq!(entitlements.plan, "team-small");
+    assert_eq!(entitlements.max_bytes, Some(1000));
+    assert_eq!(entitlements.max_share_links, None);
+    assert_eq!(entitlements.features, vec!["public_links".to_string()]);
+
+    println!("[>] Uploads are reserved until the quota runs out.");
+    assert!(reserve(&svc, &team_id, 600, 1).await.granted);
+    let denied = reserve(&svc, &team_id, 500, 1).await;
+    assert!(!denied.granted);
+    assert_eq!(denied.message, "storage quota exceeded");
+    assert_eq!(denied.usage.unwrap().bytes, 600);
+
+    println!("[>] Deleting a file gives its room back.");
+    let released = svc
+        .release_usage(usage_request(&team_id, 600, 1))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(released.usage.unwrap(), QuotaUsage::default());
+    assert!(reserve(&svc, &team_id, 500, 1).await.granted);
+
+    println!("[>] Negative amounts are refused.");
+    let err = svc
+        .reserve_usage(usage_request(&team_id, -10, 0))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    println!("[>] A plan in use cannot be deleted.");
+    let req = admin(
+        test::TestRequest::delete().uri(&format!("/admin/plans/{}", plan["id"].as_str().unwrap())),
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    println!("[/] Test passed: The plan caps storage and usage is tracked across calls.");
+}
+
+#[tokio::test]
+async fn test_quota_flow_concurrent_reservations_never_overshoot() {
+    println!("\n\n[+] Running test: test_quota_flow_concurrent_reservations_never_overshoot");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let svc = AuthenticationSvc::new(ctx.db.clone());
+
+    let (owner_id, _) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&owner_id, "Finance").await.unwrap();
+    let plan = ctx
+        .db
+        .create_plan(
+            &ledger_auth::types::plan::RPlan {
+                name: "five-files".into(),
+                max_bytes: None,
+                max_files: Some(5),
+                max_share_links: None,
+                features: vec![],
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    ctx.db
+        .assign_plan(
+            entity::role_binding::SubjectType::Team,
+            &team.id,
+            Some(plan.id),
+            None,
+        )
+        .await
+        .unwrap();
+
+    println!("[>] Twelve uploads race for five slots.");
+    let tasks: Vec<_> = (0..12)
+        .map(|_| {
+            let svc = svc.clone();
+            let team_id = team.id.to_string();
+            tokio::spawn(async move { reserve(&svc, &team_id, 10, 1).await.granted })
+        })
+        .collect();
+    let mut granted = 0;
+    for task in tasks {
+        if task.await.unwrap() {
+            granted += 1;
+        }
+    }
+    assert_eq!(granted, 5);
+
+    let (_, usage) = ctx
+        .db
+        .get_entitlements(entity::role_binding::SubjectType::Team, &team.id)
+        .await
+        .unwrap();
+    assert_eq!(usage.files, 5);
+    assert_eq!(usage.bytes, 50);
+    println!("[/] Test passed: Concurrent reservations stop exactly at the limit.");
+}
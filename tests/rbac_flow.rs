@@ -0,0 +1,111 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::grpc::authentication::AuthenticationSvc;
+use ledger_auth::grpc::pb::{authentication_server::Authentication, AuthorizeRequest};
+use tonic::Request;
+
+fn admin(req: test::TestRequest) -> test::TestRequest {
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    req.insert_header(("Authorization", format!("Bearer {}", admin_key)))
+}
+
+async fn authorize(svc: &AuthenticationSvc, subject: &str, action: &str, resource: &str) -> bool {
+    let mut request = Request::new(AuthorizeRequest {
+        subject: subject.to_string(),
+        action: action.to_string(),
+        resource: resource.to_string(),
+        context: None,
+        token: String::new(),
+    });
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+    let response = svc.authorize(request).await.unwrap().into_inner();
+    println!("[<] gRPC response body: {:?}", response);
+    response.allowed
+}
+
+#[tokio::test]
+async fn test_rbac_flow_team_binding_authorizes_members() {
+    println!("\n\n[+] Running test: test_rbac_flow_team_binding_authorizes_members");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (owner_id, _) = client.create_test_user(None).await.unwrap();
+    let (outsider_id, _) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&owner_id, "Finance").await.unwrap();
+    let workspace = format!("workspace:{}", team.id);
+
+    println!("[>] Admin defines permissions and an editor role.");
+    for name in ["file.read", "file.write", "file.delete"] {
+        let req = admin(test::TestRequest::post().uri("/admin/rbac/permissions"))
+            .set_json(serde_json::json!({ "name": name }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+    let req = admin(test::TestRequest::post().uri("/admin/rbac/roles"))
+        .set_json(serde_json::json!({ "name": "editor", "permissions": ["file.nope"] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = admin(test::TestRequest::post().uri("/admin/rbac/roles"))
+        .set_json(serde_json::json!({
+            "name": "editor",
+            "permissions": ["file.read", "file.write", "file.delete"]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let role: serde_json::Value = test::read_body_json(resp).await;
+
+    println!("[>] The role is bound to the team on its workspace.");
+    let req = admin(test::TestRequest::post().uri("/admin/rbac/bindings"))
+        .set_json(serde_json::json!({
+            "subject_type": "team",
+            "subject_id": team.id,
+            "role_id": role["id"],
+            "scope": workspace,
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let binding: serde_json::Value = test::read_body_json(resp).await;
+
+    let svc = AuthenticationSvc::new(ctx.db.clone());
+    let file = format!("{}/file:report.pdf", workspace);
+    println!("[>] Can the owner delete a file in the workspace?");
+    assert!(authorize(&svc, &owner_id.to_string(), "file.delete", &file).await);
+    println!("[>] Not in another workspace, and not an unknown action.");
+    assert!(
+        !authorize(
+            &svc,
+            &owner_id.to_string(),
+            "file.delete",
+            "workspace:other/file:x"
+        )
+        .await
+    );
+    assert!(!authorize(&svc, &owner_id.to_string(), "workspace.manage", &workspace).await);
+    println!("[>] Non-members get nothing.");
+    assert!(!authorize(&svc, &outsider_id.to_string(), "file.read", &file).await);
+
+    println!("[>] Deleting the binding revokes access.");
+    let req = admin(test::TestRequest::delete().uri(&format!(
+        "/admin/rbac/bindings/{}",
+        binding["id"].as_str().unwrap()
+    )))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(!authorize(&svc, &owner_id.to_string(), "file.read", &file).await);
+    println!("[/] Test passed: Role bindings drive gRPC authorization.");
+}
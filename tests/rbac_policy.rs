@@ -0,0 +1,77 @@
+use chrono::Utc;
+use ledger_auth::utils::policy::{
+    is_valid_permission, is_valid_resource, permission_covers, scope_covers, Access, Decision,
+    Grant, Policy, RbacPolicy, RequestContext,
+};
+use uuid::Uuid;
+
+fn grant(scope: &str, permissions: &[&str]) -> Grant {
+    Grant {
+        binding_id: Uuid::new_v4(),
+        scope: scope.to_string(),
+        permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        conditions: vec![],
+    }
+}
+
+#[test]
+fn test_rbac_scope_covers_descendants_only() {
+    assert!(scope_covers("*", "workspace:a/file:b"));
+    assert!(scope_covers("workspace:a", "workspace:a"));
+    assert!(scope_covers("workspace:a", "workspace:a/file:b"));
+    assert!(!scope_covers("workspace:a", "workspace:ab"));
+    assert!(!scope_covers("workspace:a/file:b", "workspace:a"));
+}
+
+#[test]
+fn test_rbac_permission_wildcards() {
+    assert!(permission_covers("file.delete", "file.delete"));
+    assert!(permission_covers("file.*", "file.delete"));
+    assert!(permission_covers("*", "workspace.manage"));
+    assert!(!permission_covers("file.*", "filesystem.read"));
+    assert!(!permission_covers("file.read", "file.delete"));
+}
+
+#[test]
+fn test_rbac_policy_needs_scope_and_permission() {
+    let editor = grant("workspace:z", &["file.read", "file.write"]);
+    let access = Access {
+        grants: vec![grant("workspace:y", &["file.*"]), editor.clone()],
+        acl: vec![],
+    };
+    let ctx = RequestContext::at(Utc::now());
+
+    assert_eq!(
+        RbacPolicy.authorize(&access, "file.write", "workspace:z/file:1", &ctx),
+        Decision::Allow {
+            binding_id: editor.binding_id
+        }
+    );
+    assert_eq!(
+        RbacPolicy.authorize(&access, "file.delete", "workspace:z/file:1", &ctx),
+        Decision::Deny
+    );
+    assert!(RbacPolicy
+        .authorize(&access, "file.delete", "workspace:y/file:1", &ctx)
+        .is_allowed());
+    assert_eq!(
+        RbacPolicy.authorize(&Access::default(), "file.read", "*", &ctx),
+        Decision::Deny
+    );
+}
+
+#[test]
+fn test_rbac_name_validation() {
+    assert!(is_valid_permission("file.delete"));
+    assert!(is_valid_permission("file.*"));
+    assert!(is_valid_permission("*"));
+    assert!(!is_valid_permission("File.Delete"));
+    assert!(!is_valid_permission("file..delete"));
+    assert!(!is_valid_permission("file*"));
+
+    assert!(is_valid_resource("workspace:z/file:y"));
+    assert!(is_valid_resource("*"));
+    assert!(!is_valid_resource(""));
+    assert!(!is_valid_resource("workspace:z//file"));
+    assert!(!is_valid_resource("workspace: z"));
+}
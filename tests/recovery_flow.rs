@@ -0,0 +1,219 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use chrono::Utc;
+use common::{client::TestClient, TestContext};
+use entity::user::UserStatus;
+use ledger_auth::utils::totp::{base32_decode, totp_code, TOTP_DIGITS, TOTP_STEP_SECONDS};
+use ledger_auth::utils::webutils::OTP_HEADER;
+
+#[tokio::test]
+async fn test_recovery_flow_same_response_for_unknown_email() {
+    println!("\n\n[+] Running test: test_recovery_flow_same_response_for_unknown_email");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (_user_id, _user_token) = client
+        .create_test_user(Some("known@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+
+    let mut bodies = Vec::new();
+    for email in ["known@example.com", "unknown@example.com"] {
+        println!("[>] Requesting recovery for {}", email);
+        let req = test::TestRequest::post()
+            .uri("/user/recover")
+            .set_json(serde_json::json!({ "email": email }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        println!("[<] Received response with status: {}", resp.status());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        bodies.push(body);
+    }
+
+    assert_eq!(bodies[0], bodies[1]);
+    println!("[/] Test passed: Recovery responses are indistinguishable.");
+}
+
+#[tokio::test]
+async fn test_recovery_flow_redeem_issues_new_token_once() {
+    println!("\n\n[+] Running test: test_recovery_flow_redeem_issues_new_token_once");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, old_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let recovery_token = ctx
+        .db
+        .create_recovery_token(&user_id)
+        .await
+        .expect("Failed to create recovery token");
+
+    println!("[>] Opening the recovery link (must not spend it).");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/user/recover/redeem?token={}",
+            urlencoding::encode(&recovery_token)
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body.get("token").is_none());
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", old_token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    println!("[>] Redeeming recovery token.");
+    let req = test::TestRequest::post()
+        .uri("/user/recover/redeem")
+        .set_json(serde_json::json!({ "token": recovery_token }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let new_token = body["token"].as_str().unwrap().to_string();
+
+    println!("[>] Checking old and new tokens.");
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", old_token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", new_token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    println!("[>] Redeeming the same recovery token again (expecting failure).");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/user/recover/redeem?token={}",
+            urlencoding::encode(&recovery_token)
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Recovery token works exactly once.");
+}
+
+#[tokio::test]
+async fn test_recovery_flow_new_request_supersedes_old_link() {
+    println!("\n\n[+] Running test: test_recovery_flow_new_request_supersedes_old_link");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let first = ctx.db.create_recovery_token(&user_id).await.unwrap();
+    let _second = ctx.db.create_recovery_token(&user_id).await.unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/user/recover/redeem")
+        .set_json(serde_json::json!({ "token": first }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Older recovery links are invalidated.");
+}
+
+#[tokio::test]
+async fn test_recovery_flow_blocked_account_keeps_link() {
+    println!("\n\n[+] Running test: test_recovery_flow_blocked_account_keeps_link");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let recovery_token = ctx.db.create_recovery_token(&user_id).await.unwrap();
+    ctx.db
+        .set_user_status(&user_id, UserStatus::Locked, None, "test".to_string())
+        .await
+        .unwrap();
+    let redeem = || {
+        test::TestRequest::post()
+            .uri("/user/recover/redeem")
+            .set_json(serde_json::json!({ "token": recovery_token }))
+            .to_request()
+    };
+
+    println!("[>] Redeeming while the account is locked.");
+    let resp = test::call_service(&app, redeem()).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Redeeming after the lock is lifted.");
+    ctx.db
+        .set_user_status(&user_id, UserStatus::Active, None, "test".to_string())
+        .await
+        .unwrap();
+    let resp = test::call_service(&app, redeem()).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    println!("[/] Test passed: A locked account doesn't burn its recovery link.");
+}
+
+#[tokio::test]
+async fn test_recovery_flow_totp_users_need_a_second_factor() {
+    println!("\n\n[+] Running test: test_recovery_flow_totp_users_need_a_second_factor");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let secret = ctx.db.begin_totp_enrollment(&user_id).await.unwrap();
+    let secret = base32_decode(&secret).unwrap();
+    let step = Utc::now().timestamp() / TOTP_STEP_SECONDS;
+    let codes = ctx
+        .db
+        .confirm_totp(&user_id, &totp_code(&secret, step, TOTP_DIGITS))
+        .await
+        .unwrap();
+    let recovery_token = ctx.db.create_recovery_token(&user_id).await.unwrap();
+    let redeem = |otp: Option<&str>| {
+        let mut req = test::TestRequest::post()
+            .uri("/user/recover/redeem")
+            .set_json(serde_json::json!({ "token": recovery_token }));
+        if let Some(otp) = otp {
+            req = req.insert_header((OTP_HEADER, otp.to_string()));
+        }
+        req.to_request()
+    };
+
+    println!("[>] Redeeming with the link alone.");
+    let resp = test::call_service(&app, redeem(None)).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    println!("[>] Redeeming with a TOTP recovery code.");
+    let resp = test::call_service(&app, redeem(Some(&codes[0]))).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    println!("[/] Test passed: Email recovery doesn't skip the second factor.");
+}
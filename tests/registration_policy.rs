@@ -0,0 +1,76 @@
+use ledger_auth::config::{RegistrationConfig, RegistrationMode};
+use ledger_auth::types::error::AppError;
+use ledger_auth::utils::registration::{check_registration, email_domain, SignupChannel};
+
+fn policy(mode: RegistrationMode, allowed: &[&str], denied: &[&str]) -> RegistrationConfig {
+    RegistrationConfig {
+        mode,
+        allowed_domains: allowed.iter().map(|d| d.to_string()).collect(),
+        denied_domains: denied.iter().map(|d| d.to_string()).collect(),
+        block_disposable: true,
+    }
+}
+
+#[test]
+fn test_registration_policy_closed_refuses_public_signup() {
+    let p = policy(RegistrationMode::Closed, &[], &[]);
+    assert!(matches!(
+        check_registration(&p, "a@corp.com", SignupChannel::Invite),
+        Err(AppError::Forbidden)
+    ));
+    assert!(check_registration(&p, "a@corp.com", SignupChannel::Admin).is_ok());
+}
+
+#[test]
+fn test_registration_policy_invite_only_needs_code() {
+    let p = policy(RegistrationMode::InviteOnly, &[], &[]);
+    assert!(matches!(
+        check_registration(&p, "a@corp.com", SignupChannel::SelfService),
+        Err(AppError::BadRequest(_))
+    ));
+    assert!(check_registration(&p, "a@corp.com", SignupChannel::Invite).is_ok());
+}
+
+#[test]
+fn test_registration_policy_domain_restricted() {
+    let p = policy(RegistrationMode::DomainRestricted, &["ourcompany.com"], &[]);
+    assert!(check_registration(&p, "a@ourcompany.com", SignupChannel::SelfService).is_ok());
+    assert!(check_registration(&p, "a@eu.ourcompany.com", SignupChannel::SelfService).is_ok());
+    assert!(check_registration(&p, "a@notourcompany.com", SignupChannel::SelfService).is_err());
+    assert!(check_registration(&p, "a@gmail.com", SignupChannel::Invite).is_err());
+}
+
+#[test]
+fn test_registration_policy_open_still_blocks_denied_and_disposable() {
+    let p = policy(RegistrationMode::Open, &[], &["competitor.io"]);
+    assert!(check_registration(&p, "a@gmail.com", SignupChannel::SelfService).is_ok());
+    assert!(check_registration(&p, "a@competitor.io", SignupChannel::Admin).is_err());
+    assert!(check_registration(&p, "a@mailinator.com", SignupChannel::SelfService).is_err());
+    assert!(check_registration(&p, "a@x.yopmail.com", SignupChannel::Admin).is_err());
+
+    let mut lenient = p.clone();
+    lenient.block_disposable = false;
+    assert!(check_registration(&lenient, "a@mailinator.com", SignupChannel::SelfService).is_ok());
+}
+
+#[test]
+fn test_registration_policy_rejects_malformed_email() {
+    let p = policy(RegistrationMode::Open, &[], &[]);
+    assert!(matches!(
+        check_registration(&p, "not-an-email", SignupChannel::SelfService),
+        Err(AppError::Validation(_))
+    ));
+    assert_eq!(email_domain(" A@Example.COM "), Some("example.com".to_string()));
+    assert_eq!(email_domain("@example.com"), None);
+}
+
+#[test]
+fn test_registration_policy_federated_ignores_mode_but_not_lists() {
+    let p = policy(RegistrationMode::Closed, &["corp.example"], &[]);
+    assert!(check_registration(&p, "a@corp.example", SignupChannel::Federated).is_ok());
+    assert!(matches!(
+        check_registration(&p, "a@other.example", SignupChannel::Federated),
+        Err(AppError::Forbidden)
+    ));
+    assert!(check_registration(&p, "a@mailinator.com", SignupChannel::Federated).is_err());
+}
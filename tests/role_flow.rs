@@ -0,0 +1,113 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{
+    client::{authed, TestClient},
+    TestContext,
+};
+use entity::user::UserRole;
+use ledger_auth::grpc::pb::authentication_server::Authentication;
+use tonic::Request;
+
+#[tokio::test]
+async fn test_role_flow_read_only_users_cannot_write() {
+    println!("\n\n[+] Running test: test_role_flow_read_only_users_cannot_write");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, user_token) = client.create_test_user(None).await.unwrap();
+    ctx.db
+        .set_user_role(&user_id, UserRole::ReadOnly, None, None)
+        .await
+        .unwrap();
+
+    println!("[>] A read-only user can still list their teams.");
+    let req = authed(test::TestRequest::get().uri("/teams"), &user_token).to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] But cannot create one.");
+    let req = authed(test::TestRequest::post().uri("/teams"), &user_token)
+        .set_json(serde_json::json!({ "name": "Nope" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert!(ctx.db.list_user_teams(&user_id).await.unwrap().is_empty());
+    println!("[/] Test passed: Read-only role enforced on write routes.");
+}
+
+#[tokio::test]
+async fn test_role_flow_only_admins_change_roles() {
+    println!("\n\n[+] Running test: test_role_flow_only_admins_change_roles");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (admin_id, admin_token) = client.create_test_admin().await;
+    let (user_id, user_token) = client.create_test_user(None).await.unwrap();
+
+    println!("[>] A member cannot reach the admin API.");
+    let req = authed(
+        test::TestRequest::put().uri(&format!("/admin/users/{}/role", user_id)),
+        &user_token,
+    )
+    .set_json(serde_json::json!({ "role": "admin" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    println!("[>] An admin account makes the user read-only.");
+    let req = authed(
+        test::TestRequest::put().uri(&format!("/admin/users/{}/role", user_id)),
+        &admin_token,
+    )
+    .set_json(serde_json::json!({ "role": "read_only", "reason": "contractor" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["role"], "read_only");
+
+    println!("[>] The change is audited with the acting admin.");
+    let req = authed(
+        test::TestRequest::get().uri(&format!(
+            "/admin/audit?action=user.role_changed&target_id={}",
+            user_id
+        )),
+        &admin_token,
+    )
+    .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let events = body["items"].as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["actor_id"], admin_id.to_string());
+    assert_eq!(events[0]["detail"]["from"], "member");
+    assert_eq!(events[0]["detail"]["to"], "read_only");
+
+    println!("[>] gRPC validation reports the new role.");
+    let auth_svc = ledger_auth::grpc::authentication::AuthenticationSvc::new(ctx.db.clone());
+    let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
+        token: user_token,
+        ..Default::default()
+    });
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+    let validation_response = auth_svc
+        .validate_authentication(request)
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", validation_response);
+    assert!(validation_response.is_valid);
+    assert_eq!(validation_response.role, "read_only");
+    println!("[/] Test passed: Role changes are admin-only, audited and visible.");
+}
@@ -0,0 +1,21 @@
+use entity::user::UserRole;
+use ledger_auth::utils::role::{role_name, role_satisfies};
+
+#[test]
+fn test_roles_are_ordered() {
+    assert!(role_satisfies(UserRole::Admin, UserRole::Admin));
+    assert!(role_satisfies(UserRole::Admin, UserRole::Member));
+    assert!(role_satisfies(UserRole::Member, UserRole::ReadOnly));
+    assert!(!role_satisfies(UserRole::Member, UserRole::Admin));
+    assert!(!role_satisfies(UserRole::ReadOnly, UserRole::Member));
+}
+
+#[test]
+fn test_role_names_match_serde() {
+    for role in [UserRole::Admin, UserRole::Member, UserRole::ReadOnly] {
+        assert_eq!(
+            serde_json::to_value(role).unwrap(),
+            serde_json::Value::from(role_name(role))
+        );
+    }
+}
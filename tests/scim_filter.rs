@@ -0,0 +1,91 @@
+use ledger_auth::types::scim::{ScimError, ScimPatchOperation};
+use ledger_auth::utils::scim::{
+    parse_filter, patch_changes, ScimAttr, ScimFilter, ScimOp, ScimUserChanges, ScimValue,
+};
+use serde_json::json;
+
+fn op(op: &str, path: Option<&str>, value: serde_json::Value) -> ScimPatchOperation {
+    ScimPatchOperation {
+        op: op.to_string(),
+        path: path.map(str::to_string),
+        value: Some(value),
+    }
+}
+
+#[test]
+fn test_scim_filter_parses_and_chains() {
+    let filters = parse_filter(r#"userName Eq "a b@corp.com" and active eq true"#).unwrap();
+    assert_eq!(
+        filters,
+        vec![
+            ScimFilter {
+                attr: ScimAttr::UserName,
+                op: ScimOp::Eq,
+                value: ScimValue::Str("a b@corp.com".into()),
+            },
+            ScimFilter {
+                attr: ScimAttr::Active,
+                op: ScimOp::Eq,
+                value: ScimValue::Bool(true),
+            },
+        ]
+    );
+
+    let filters =
+        parse_filter(r#"urn:ietf:params:scim:schemas:core:2.0:User:emails.value sw "a\"b""#)
+            .unwrap();
+    assert_eq!(filters[0].attr, ScimAttr::UserName);
+    assert_eq!(filters[0].value, ScimValue::Str("a\"b".into()));
+}
+
+#[test]
+fn test_scim_filter_rejects_unsupported() {
+    for raw in [
+        r#"title eq "x""#,
+        r#"userName gt "a""#,
+        r#"userName eq "a" or active eq true"#,
+        r#"active co "t""#,
+        r#"userName eq "unterminated"#,
+        "userName eq",
+    ] {
+        assert!(
+            matches!(parse_filter(raw), Err(ScimError::InvalidFilter(_))),
+            "{raw}"
+        );
+    }
+}
+
+#[test]
+fn test_scim_patch_reads_both_shapes() {
+    let changes = patch_changes(&[
+        op(
+            "replace",
+            None,
+            json!({ "active": "False", "displayName": "Ada" }),
+        ),
+        op(
+            "add",
+            Some("emails"),
+            json!([{ "value": "old@corp.com" }, { "value": "ada@corp.com", "primary": true }]),
+        ),
+        op("replace", Some("title"), json!("Countess")),
+    ])
+    .unwrap();
+    assert_eq!(
+        changes,
+        ScimUserChanges {
+            name: Some("Ada".into()),
+            email: Some("ada@corp.com".into()),
+            active: Some(false),
+        }
+    );
+
+    assert!(matches!(
+        patch_changes(&[op("remove", Some("displayName"), json!(null))]),
+        Err(ScimError::Mutability(_))
+    ));
+    assert!(matches!(
+        patch_changes(&[op("replace", Some("active"), json!("maybe"))]),
+        Err(ScimError::InvalidValue(_))
+    ));
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::{db::session::SessionOrigin, utils::scope::SCOPE_ACCOUNT};
+
+fn scim(req: test::TestRequest) -> test::TestRequest {
+    let token = ledger_auth::config::config().scim_token.clone().unwrap();
+    req.insert_header(("Authorization", format!("Bearer {}", token)))
+        .insert_header(("Content-Type", "application/scim+json"))
+}
+
+fn validate(token: &str) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+}
+
+#[tokio::test]
+async fn test_scim_flow_provision_filter_and_deactivate() {
+    println!("\n\n[+] Running test: test_scim_flow_provision_filter_and_deactivate");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    println!("[>] Provisioning a user over SCIM.");
+    let req = scim(test::TestRequest::post().uri("/scim/v2/Users"))
+        .set_payload(
+            serde_json::json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                "userName": "grace@corp.example",
+                "name": { "givenName": "Grace", "familyName": "Hopper" },
+                "emails": [{ "value": "grace@corp.example", "primary": true }],
+                "active": true,
+            })
+            .to_string(),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert!(resp.headers().contains_key("Location"));
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["displayName"], "Grace Hopper");
+    assert_eq!(body["active"], true);
+    let grace_id = body["id"].as_str().unwrap().to_string();
+
+    println!("[>] Provisioning the same user again (expecting a conflict).");
+    let req = scim(test::TestRequest::post().uri("/scim/v2/Users"))
+        .set_payload(serde_json::json!({ "userName": "GRACE@corp.example" }).to_string())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["scimType"], "uniqueness");
+
+    let (user_id, user_token) = client
+        .create_test_user(Some("ada@corp.example".into()))
+        .await
+        .expect("Failed creating a test user");
+    let session = ctx
+        .db
+        .create_session(&user_id, "password", SessionOrigin::default())
+        .await
+        .unwrap();
+    let (_, api_key) = ctx
+        .db
+        .create_api_key(&user_id, "ci", &[SCOPE_ACCOUNT.to_string()])
+        .await
+        .unwrap();
+
+    println!("[>] Looking Ada up the way an IdP does.");
+    let req = scim(test::TestRequest::get().uri(
+        "/scim/v2/Users?filter=userName%20eq%20%22Ada%40corp.example%22&startIndex=1&count=10",
+    ))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["totalResults"], 1);
+    assert_eq!(body["Resources"][0]["id"], user_id.to_string());
+
+    let req = scim(test::TestRequest::get().uri("/scim/v2/Users?filter=title%20eq%20%22x%22"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["scimType"], "invalidFilter");
+
+    println!("[>] Offboarding Ada with a PATCH.");
+    let req = scim(test::TestRequest::patch().uri(&format!("/scim/v2/Users/{}", user_id)))
+        .set_payload(
+            serde_json::json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                "Operations": [{ "op": "replace", "value": { "active": "False" } }],
+            })
+            .to_string(),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["active"], false);
+
+    println!("[>] Every credential Ada held should now fail.");
+    for token in [&user_token, &session.token, &api_key] {
+        let resp = test::call_service(&app, validate(token).to_request()).await;
+        println!("[<] Received response with status: {}", resp.status());
+        assert!(resp.status().is_client_error());
+    }
+
+    let req = scim(test::TestRequest::get().uri("/scim/v2/Users?filter=active%20eq%20false"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["totalResults"], 1);
+
+    println!("[>] Rehiring Ada reactivates the account but not the old token.");
+    let req = scim(test::TestRequest::patch().uri(&format!("/scim/v2/Users/{}", user_id)))
+        .set_payload(
+            serde_json::json!({
+                "Operations": [{ "op": "replace", "path": "active", "value": true }],
+            })
+            .to_string(),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let user = ctx.db.get_user_by_id(&user_id).await.unwrap();
+    assert_eq!(user.status, entity::user::UserStatus::Active);
+    let resp = test::call_service(&app, validate(&user_token).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    println!("[>] Deleting Grace.");
+    let req =
+        scim(test::TestRequest::delete().uri(&format!("/scim/v2/Users/{}", grace_id))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let req =
+        scim(test::TestRequest::get().uri(&format!("/scim/v2/Users/{}", grace_id))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    println!("[/] Test passed: SCIM provisioning and offboarding work end to end.");
+}
+
+#[tokio::test]
+async fn test_scim_flow_requires_scim_token() {
+    println!("\n\n[+] Running test: test_scim_flow_requires_scim_token");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let req = test::TestRequest::get()
+        .uri("/scim/v2/Users")
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = scim(test::TestRequest::get().uri("/scim/v2/ServiceProviderConfig")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] Discovery offers users and nothing else.");
+    let req = scim(test::TestRequest::get().uri("/scim/v2/ResourceTypes")).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    println!("[<] Response body: {}", body);
+    assert_eq!(body["totalResults"], 1);
+    assert_eq!(body["Resources"][0]["endpoint"], "/Users");
+    let req = scim(test::TestRequest::get().uri("/scim/v2/Groups")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    println!("[/] Test passed: Only the SCIM token opens /scim/v2.");
+}
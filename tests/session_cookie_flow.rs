@@ -0,0 +1,122 @@
+mod common;
+
+use actix_web::{cookie::SameSite, http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::utils::csrf::CSRF_HEADER;
+use ledger_auth::utils::webutils::{CSRF_COOKIE, SESSION_COOKIE};
+
+#[tokio::test]
+async fn test_cookie_session_flow_with_csrf() {
+    println!("\n\n[+] Running test: test_cookie_session_flow_with_csrf");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, _user_token) = client
+        .create_test_user(Some("browser@example.com".to_string()))
+        .await
+        .expect("Failed creating a test user");
+    let token = ctx.db.create_password_token(&user_id).await.unwrap();
+    ctx.db
+        .set_password_with_token(&token, "correct horse battery")
+        .await
+        .unwrap();
+
+    println!("[>] Logging in for a cookie session.");
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(serde_json::json!({
+            "email": "browser@example.com",
+            "password": "correct horse battery",
+            "cookie": true,
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let session_cookie = resp
+        .response()
+        .cookies()
+        .find(|c| c.name() == SESSION_COOKIE)
+        .expect("No session cookie")
+        .into_owned();
+    assert_eq!(session_cookie.http_only(), Some(true));
+    assert_eq!(session_cookie.secure(), Some(true));
+    assert_eq!(session_cookie.same_site(), Some(SameSite::Lax));
+    assert!(resp.response().cookies().any(|c| c.name() == CSRF_COOKIE));
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body.get("token").is_none());
+    let csrf_token = body["csrf_token"].as_str().unwrap().to_string();
+
+    println!("[>] Reading with the cookie alone.");
+    let req = test::TestRequest::get()
+        .uri("/user/passkeys")
+        .cookie(session_cookie.clone())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    println!("[>] Mutating without a CSRF token (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/mfa/totp")
+        .cookie(session_cookie.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Mutating with the CSRF token.");
+    let req = test::TestRequest::post()
+        .uri("/user/mfa/totp")
+        .cookie(session_cookie.clone())
+        .insert_header((CSRF_HEADER, csrf_token.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] Logging out.");
+    let req = test::TestRequest::post()
+        .uri("/auth/logout")
+        .cookie(session_cookie.clone())
+        .insert_header((CSRF_HEADER, csrf_token))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let req = test::TestRequest::get()
+        .uri("/user/passkeys")
+        .cookie(session_cookie)
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    println!("[/] Test passed: Cookie session honoured CSRF and ended on logout.");
+}
+
+#[tokio::test]
+async fn test_cookie_session_flow_rejects_api_token_cookie() {
+    println!("\n\n[+] Running test: test_cookie_session_flow_rejects_api_token_cookie");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (_user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    let req = test::TestRequest::get()
+        .uri("/user/passkeys")
+        .cookie(actix_web::cookie::Cookie::new(SESSION_COOKIE, user_token))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    println!("[/] Test passed: Only session tokens are accepted as cookies.");
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use common::{client::TestClient, TestContext};
+use ledger_auth::db::session::SessionOrigin;
+use ledger_auth::utils::webutils::describe_device;
+
+const FIREFOX_LINUX: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.6 Mobile/15E148 Safari/604.1";
+
+#[test]
+fn test_describe_device() {
+    assert_eq!(describe_device(Some(FIREFOX_LINUX)), "Firefox on Linux");
+    assert_eq!(describe_device(Some(SAFARI_IPHONE)), "Safari on iOS");
+    assert_eq!(describe_device(None), "Unknown device");
+}
+
+#[tokio::test]
+async fn test_sessions_flow_list_and_revoke() {
+    println!("\n\n[+] Running test: test_sessions_flow_list_and_revoke");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = actix_web::test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let laptop = ctx
+        .db
+        .create_session(
+            &user_id,
+            "password",
+            SessionOrigin {
+                ip_address: Some("203.0.113.7".into()),
+                user_agent: Some(FIREFOX_LINUX.into()),
+            },
+        )
+        .await
+        .unwrap();
+    let phone = ctx
+        .db
+        .create_session(
+            &user_id,
+            "passkey",
+            SessionOrigin {
+                ip_address: Some("198.51.100.2".into()),
+                user_agent: Some(SAFARI_IPHONE.into()),
+            },
+        )
+        .await
+        .unwrap();
+
+    println!("[>] Listing sessions from the laptop.");
+    let req = actix_web::test::TestRequest::get()
+        .uri("/user/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", laptop.token)))
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = actix_web::test::read_body_json(resp).await;
+    let sessions = body.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    let current = sessions.iter().find(|s| s["current"] == true).unwrap();
+    assert_eq!(current["device"], "Firefox on Linux");
+    assert_eq!(current["ip_address"], "203.0.113.7");
+    let phone_id = sessions.iter().find(|s| s["current"] == false).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    println!("[>] Revoking the phone session.");
+    let req = actix_web::test::TestRequest::delete()
+        .uri(&format!("/user/sessions/{}", phone_id))
+        .insert_header(("Authorization", format!("Bearer {}", laptop.token)))
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", phone.token)))
+        .to_request();
+    assert_eq!(
+        actix_web::test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    println!("[>] Signing out everywhere.");
+    let req = actix_web::test::TestRequest::delete()
+        .uri("/user/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", laptop.token)))
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = actix_web::test::read_body_json(resp).await;
+    assert_eq!(body["revoked"], 1);
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", laptop.token)))
+        .to_request();
+    assert_eq!(
+        actix_web::test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let req = actix_web::test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    assert_eq!(
+        actix_web::test::call_service(&app, req).await.status(),
+        StatusCode::OK
+    );
+    println!("[/] Test passed: Sessions listed and revoked immediately.");
+}
+
+#[tokio::test]
+async fn test_sessions_flow_cannot_revoke_foreign_session() {
+    println!("\n\n[+] Running test: test_sessions_flow_cannot_revoke_foreign_session");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = actix_web::test::init_service(client.create_app()).await;
+
+    let (_alice_id, alice_token) = client.create_test_user(None).await.unwrap();
+    let (bob_id, _bob_token) = client.create_test_user(None).await.unwrap();
+    ctx.db
+        .create_session(&bob_id, "password", SessionOrigin::default())
+        .await
+        .unwrap();
+    let bob_session = ctx.db.list_sessions(&bob_id).await.unwrap()[0].id;
+
+    let req = actix_web::test::TestRequest::delete()
+        .uri(&format!("/user/sessions/{}", bob_session))
+        .insert_header(("Authorization", format!("Bearer {}", alice_token)))
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(ctx.db.list_sessions(&bob_id).await.unwrap().len(), 1);
+    println!("[/] Test passed: Other users' sessions are out of reach.");
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use entity::user::UserRole;
+
+#[tokio::test]
+async fn test_signup_flow_with_invite_success() {
+    println!("\n\n[+] Running test: test_signup_flow_with_invite_success");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+
+    println!("[>] Creating invite as admin.");
+    let req = test::TestRequest::post()
+        .uri("/admin/invites")
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .set_json(serde_json::json!({ "max_uses": 1, "default_role": "read_only" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let code = body["code"].as_str().unwrap().to_string();
+
+    println!("[>] Redeeming invite.");
+    let req = test::TestRequest::post()
+        .uri("/signup")
+        .set_json(serde_json::json!({
+            "code": code,
+            "name": "Invited User",
+            "email": "invited@example.com",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let user = ctx.db.get_user_by_email("invited@example.com").await.unwrap();
+    assert_eq!(user.role, UserRole::ReadOnly);
+    assert!(user.email_verified_at.is_none());
+
+    println!("[>] Redeeming used-up invite (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/signup")
+        .set_json(serde_json::json!({
+            "code": code,
+            "name": "Second User",
+            "email": "second@example.com",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Invite redeemed once and then refused.");
+}
+
+#[tokio::test]
+async fn test_signup_flow_revoked_invite() {
+    println!("\n\n[+] Running test: test_signup_flow_revoked_invite");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+    let (invite_id, code) = ctx
+        .db
+        .create_signup_invite(5, None, UserRole::Member)
+        .await
+        .expect("Failed to create invite");
+
+    println!("[>] Revoking invite.");
+    let req = test::TestRequest::delete()
+        .uri(&format!("/admin/invites/{}", invite_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    println!("[>] Listing invites.");
+    let req = test::TestRequest::get()
+        .uri("/admin/invites")
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let listed = &body.as_array().unwrap()[0];
+    assert!(!listed["revoked_at"].is_null());
+    assert!(listed.get("code").is_none());
+
+    println!("[>] Redeeming revoked invite (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/signup")
+        .set_json(serde_json::json!({
+            "code": code,
+            "name": "Late User",
+            "email": "late@example.com",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Revoked invite refused.");
+}
+
+#[tokio::test]
+async fn test_signup_flow_invites_require_admin() {
+    println!("\n\n[+] Running test: test_signup_flow_invites_require_admin");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (_user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+
+    let req = test::TestRequest::post()
+        .uri("/admin/invites")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    println!("[/] Test passed: Regular users can't create invites.");
+}
+
+#[tokio::test]
+async fn test_signup_flow_invite_only_requires_code() {
+    println!("\n\n[+] Running test: test_signup_flow_invite_only_requires_code");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let req = test::TestRequest::post()
+        .uri("/signup")
+        .set_json(serde_json::json!({
+            "name": "Walk-in User",
+            "email": "walkin@example.com",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert!(ctx.db.get_user_by_email("walkin@example.com").await.is_err());
+    println!("[/] Test passed: Signup without a code refused in invite-only mode.");
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, TestContext};
+use ledger_auth::grpc::pb::authentication_server::Authentication;
+use ledger_auth::types::audit::RAuditList;
+use tonic::Request;
+
+#[tokio::test]
+async fn test_account_status_flow_suspend_and_reactivate() {
+    println!("\n\n[+] Running test: test_account_status_flow_suspend_and_reactivate");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+
+    println!("[>] Suspending user {}.", user_id);
+    let req = test::TestRequest::put()
+        .uri(&format!("/admin/users/{}/status", user_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .set_json(serde_json::json!({ "status": "suspended", "reason": "abuse report" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "suspended");
+    assert_eq!(body["status_reason"], "abuse report");
+
+    println!("[>] Validating suspended user's token.");
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "ACCOUNT_SUSPENDED");
+
+    println!("[>] Regenerating token while suspended (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/user/regenerate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    println!("[>] Reactivating user.");
+    let req = test::TestRequest::put()
+        .uri(&format!("/admin/users/{}/status", user_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .set_json(serde_json::json!({ "status": "active", "reason": "appeal granted" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let (events, _) = ctx
+        .db
+        .list_audit_events(&RAuditList {
+            action: Some("user.status_changed".into()),
+            target_id: Some(user_id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].detail["to"], "active");
+    assert_eq!(events[1].detail["reason"], "abuse report");
+    println!("[/] Test passed: Suspension applied and lifted immediately.");
+}
+
+#[tokio::test]
+async fn test_account_status_flow_requires_reason() {
+    println!("\n\n[+] Running test: test_account_status_flow_requires_reason");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/admin/users/{}/status", user_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .set_json(serde_json::json!({ "status": "locked", "reason": "  " }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Status change without a reason refused.");
+}
+
+#[tokio::test]
+async fn test_account_status_flow_unverified_stays_pending() {
+    println!("\n\n[+] Running test: test_account_status_flow_unverified_stays_pending");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, _email, user_token) = client
+        .create_pending_test_user()
+        .await
+        .expect("Failed creating a pending test user");
+    let admin_key = ledger_auth::config::config().admin_key.clone();
+
+    println!("[>] Activating a user that never verified their email.");
+    let req = test::TestRequest::put()
+        .uri(&format!("/admin/users/{}/status", user_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin_key)))
+        .set_json(serde_json::json!({ "status": "active", "reason": "support ticket" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "pending");
+
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    println!("[/] Test passed: Admins can't skip email verification.");
+}
+
+#[tokio::test]
+async fn test_grpc_token_validation_flow_suspended_user() {
+    println!("\n\n[+] Running test: test_grpc_token_validation_flow_suspended_user");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+
+    let (user_id, user_token) = client
+        .create_test_user(None)
+        .await
+        .expect("Failed creating a test user");
+    ctx.db
+        .set_user_status(
+            &user_id,
+            entity::user::UserStatus::Suspended,
+            None,
+            "test".to_string(),
+        )
+        .await
+        .unwrap();
+
+    let auth_svc = ledger_auth::grpc::authentication::AuthenticationSvc::new(ctx.db.clone());
+    let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
+        token: user_token,
+        ..Default::default()
+    });
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+
+    let validation_response = auth_svc
+        .validate_authentication(request)
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", validation_response);
+
+    assert!(!validation_response.is_valid);
+    assert_eq!(validation_response.message, "suspended");
+    println!("[/] Test passed: Suspended user refused over gRPC.");
+}
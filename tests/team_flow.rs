@@ -0,0 +1,202 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{
+    client::{authed, TestClient},
+    TestContext,
+};
+use entity::team_membership::TeamRole;
+use ledger_auth::grpc::pb::authentication_server::Authentication;
+use tonic::Request;
+
+#[tokio::test]
+async fn test_team_flow_create_add_and_remove_members() {
+    println!("\n\n[+] Running test: test_team_flow_create_add_and_remove_members");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (alice_id, alice_token) = client.create_test_user(None).await.unwrap();
+    let (bob_id, bob_token) = client.create_test_user(None).await.unwrap();
+
+    println!("[>] Alice creates a team.");
+    let req = authed(test::TestRequest::post().uri("/teams"), &alice_token)
+        .set_json(serde_json::json!({ "name": "  Design  " }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["name"], "Design");
+    assert_eq!(body["role"], "owner");
+    let team_id = body["id"].as_str().unwrap().to_string();
+
+    println!("[>] Bob cannot see the team yet.");
+    let req = authed(
+        test::TestRequest::get().uri(&format!("/teams/{}", team_id)),
+        &bob_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    println!("[>] Alice adds Bob.");
+    let req = authed(
+        test::TestRequest::post().uri(&format!("/teams/{}/members", team_id)),
+        &alice_token,
+    )
+    .set_json(serde_json::json!({ "user_id": bob_id }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let req = authed(
+        test::TestRequest::post().uri(&format!("/teams/{}/members", team_id)),
+        &alice_token,
+    )
+    .set_json(serde_json::json!({ "user_id": bob_id }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    println!("[>] Bob, a member, cannot rename the team or add people.");
+    let req = authed(
+        test::TestRequest::put().uri(&format!("/teams/{}", team_id)),
+        &bob_token,
+    )
+    .set_json(serde_json::json!({ "name": "Bob's" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let req = authed(test::TestRequest::get().uri("/teams"), &bob_token).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body[0]["id"], team_id);
+    assert_eq!(body[0]["role"], "member");
+
+    let req = authed(
+        test::TestRequest::get().uri(&format!("/teams/{}/members", team_id)),
+        &bob_token,
+    )
+    .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    println!("[>] The only owner cannot leave.");
+    let req = authed(
+        test::TestRequest::delete().uri(&format!("/teams/{}/members/{}", team_id, alice_id)),
+        &alice_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    println!("[>] Bob leaves.");
+    let req = authed(
+        test::TestRequest::delete().uri(&format!("/teams/{}/members/{}", team_id, bob_id)),
+        &bob_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(ctx.db.list_user_teams(&bob_id).await.unwrap().is_empty());
+    println!("[/] Test passed: Team membership managed by its owner.");
+}
+
+#[tokio::test]
+async fn test_team_flow_last_owner_is_kept() {
+    println!("\n\n[+] Running test: test_team_flow_last_owner_is_kept");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (alice_id, alice_token) = client.create_test_user(None).await.unwrap();
+    let (bob_id, _) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&alice_id, "Design").await.unwrap();
+
+    println!("[>] Alice, the only owner, cannot delete her account.");
+    let delete_account = || {
+        authed(
+            test::TestRequest::delete().uri("/user/delete"),
+            &alice_token,
+        )
+        .to_request()
+    };
+    let resp = test::call_service(&app, delete_account()).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    assert!(ctx.db.get_user_by_id(&alice_id).await.is_ok());
+
+    println!("[>] Two owners removing each other at once: one of them stays.");
+    ctx.db
+        .add_team_member(&team.id, &bob_id, TeamRole::Owner)
+        .await
+        .unwrap();
+    let (a, b) = tokio::join!(
+        ctx.db.remove_team_member(&team.id, &alice_id),
+        ctx.db.remove_team_member(&team.id, &bob_id),
+    );
+    println!("[<] Results: {:?}, {:?}", a.is_ok(), b.is_ok());
+    assert!(a.is_ok() != b.is_ok());
+    let remaining = ctx.db.list_team_members(&team.id).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+
+    println!("[>] With another owner in place, the account can go.");
+    if a.is_err() {
+        ctx.db
+            .add_team_member(&team.id, &bob_id, TeamRole::Owner)
+            .await
+            .unwrap();
+    } else {
+        ctx.db
+            .add_team_member(&team.id, &alice_id, TeamRole::Owner)
+            .await
+            .unwrap();
+    }
+    let resp = test::call_service(&app, delete_account()).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let members = ctx.db.list_team_members(&team.id).await.unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].1.id, bob_id);
+    println!("[/] Test passed: A team always keeps an owner.");
+}
+
+#[tokio::test]
+async fn test_grpc_token_validation_flow_includes_teams() {
+    println!("\n\n[+] Running test: test_grpc_token_validation_flow_includes_teams");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+
+    let (user_id, user_token) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&user_id, "Storage").await.unwrap();
+
+    let auth_svc = ledger_auth::grpc::authentication::AuthenticationSvc::new(ctx.db.clone());
+    let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
+        token: user_token,
+        ..Default::default()
+    });
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+
+    let validation_response = auth_svc
+        .validate_authentication(request)
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", validation_response);
+
+    assert!(validation_response.is_valid);
+    assert_eq!(validation_response.teams.len(), 1);
+    assert_eq!(validation_response.teams[0].team_id, team.id.to_string());
+    assert_eq!(validation_response.teams[0].team_name, "Storage");
+    assert_eq!(validation_response.teams[0].role, "owner");
+    println!("[/] Test passed: Validation carries team memberships.");
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{
+    client::{authed, TestClient},
+    TestContext,
+};
+use entity::team_membership::TeamRole;
+
+fn accept(token: &str) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/teams/invites/accept")
+        .set_json(serde_json::json!({ "token": token, "name": "New Person" }))
+}
+
+#[tokio::test]
+async fn test_team_invite_flow_creates_account_on_accept() {
+    println!("\n\n[+] Running test: test_team_invite_flow_creates_account_on_accept");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (alice_id, alice_token) = client.create_test_user(None).await.unwrap();
+    let team = ctx.db.create_team(&alice_id, "Research").await.unwrap();
+
+    println!("[>] Alice invites someone without an account.");
+    let req = authed(
+        test::TestRequest::post().uri(&format!("/teams/{}/invites", team.id)),
+        &alice_token,
+    )
+    .set_json(serde_json::json!({ "email": "New.Person@corp.example" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["email"], "new.person@corp.example");
+    assert!(body.get("token").is_none());
+    let invite_id: uuid::Uuid = body["id"].as_str().unwrap().parse().unwrap();
+
+    let req = authed(
+        test::TestRequest::post().uri(&format!("/teams/{}/invites", team.id)),
+        &alice_token,
+    )
+    .set_json(serde_json::json!({ "email": "new.person@corp.example" }))
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // The mail is faked, so take a fresh token the way a resend would.
+    let (_, token) = ctx
+        .db
+        .resend_team_invite(&team.id, &invite_id)
+        .await
+        .unwrap();
+
+    println!("[>] Accepting the invite.");
+    let resp = test::call_service(&app, accept(&token).to_request()).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["account_created"], true);
+
+    let user = ctx
+        .db
+        .get_user_by_email("new.person@corp.example")
+        .await
+        .unwrap();
+    assert_eq!(user.name, "New Person");
+    assert!(user.email_verified_at.is_some());
+    let membership = ctx
+        .db
+        .get_team_membership(&team.id, &user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(membership.role, TeamRole::Member);
+
+    println!("[>] Accepting again (expecting failure).");
+    let resp = test::call_service(&app, accept(&token).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert!(ctx.db.list_team_invites(&team.id).await.unwrap().is_empty());
+    println!("[/] Test passed: Invite created the account and the membership once.");
+}
+
+#[tokio::test]
+async fn test_team_invite_flow_resend_and_cancel() {
+    println!("\n\n[+] Running test: test_team_invite_flow_resend_and_cancel");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (alice_id, alice_token) = client.create_test_user(None).await.unwrap();
+    let (_, bob_token) = client
+        .create_test_user(Some("bob@corp.example".into()))
+        .await
+        .unwrap();
+    let team = ctx.db.create_team(&alice_id, "Ops").await.unwrap();
+    let (invite, old_token) = ctx
+        .db
+        .create_team_invite(&team.id, "bob@corp.example", TeamRole::Owner, &alice_id)
+        .await
+        .unwrap();
+
+    println!("[>] Bob is not an owner and cannot see the invites.");
+    let req = authed(
+        test::TestRequest::get().uri(&format!("/teams/{}/invites", team.id)),
+        &bob_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    println!("[>] Resending replaces the token.");
+    let req = authed(
+        test::TestRequest::post().uri(&format!("/teams/{}/invites/{}/resend", team.id, invite.id)),
+        &alice_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, accept(&old_token).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    println!("[>] Cancelling the invite.");
+    let req = authed(
+        test::TestRequest::get().uri(&format!("/teams/{}/invites", team.id)),
+        &alice_token,
+    )
+    .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["role"], "owner");
+
+    let req = authed(
+        test::TestRequest::delete().uri(&format!("/teams/{}/invites/{}", team.id, invite.id)),
+        &alice_token,
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(ctx.db.list_team_invites(&team.id).await.unwrap().is_empty());
+    assert!(ctx
+        .db
+        .resend_team_invite(&team.id, &invite.id)
+        .await
+        .is_err());
+    println!("[/] Test passed: Owners can resend and cancel pending invites.");
+}
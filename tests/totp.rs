@@ -0,0 +1,44 @@
+use ledger_auth::utils::totp::{base32_decode, base32_encode, totp_code, verify_totp};
+
+// RFC 6238 appendix B, SHA1 column.
+const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+#[test]
+fn test_totp_rfc6238_vectors() {
+    let cases = [
+        (59, "94287082"),
+        (1111111109, "07081804"),
+        (1111111111, "14050471"),
+        (1234567890, "89005924"),
+        (2000000000, "69279037"),
+        (20000000000, "65353130"),
+    ];
+    for (time, expected) in cases {
+        assert_eq!(totp_code(RFC_SECRET, time / 30, 8), expected, "t={time}");
+    }
+}
+
+#[test]
+fn test_totp_verify_window_and_format() {
+    let secret = base32_encode(RFC_SECRET);
+    let now = 1111111111;
+    let code = totp_code(RFC_SECRET, now / 30, 6);
+
+    assert_eq!(verify_totp(&secret, &code, now), Some(now / 30));
+    assert_eq!(verify_totp(&secret, &code, now + 30), Some(now / 30));
+    assert_eq!(verify_totp(&secret, &code, now + 90), None);
+    assert_eq!(verify_totp(&secret, "12345", now), None);
+    assert_eq!(verify_totp(&secret, "abcdef", now), None);
+}
+
+#[test]
+fn test_totp_base32_roundtrip() {
+    let encoded = base32_encode(RFC_SECRET);
+    assert_eq!(encoded, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    assert_eq!(base32_decode(&encoded).unwrap(), RFC_SECRET);
+    assert_eq!(
+        base32_decode("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap(),
+        RFC_SECRET
+    );
+    assert!(base32_decode("not base32!").is_none());
+}
@@ -129,6 +129,7 @@ async fn test_grpc_token_validation_flow_success() {
     println!("[>] Creating gRPC request with valid token.");
     let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
         token: user_token.clone(),
+        ..Default::default()
     });
 
     let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
@@ -162,6 +163,7 @@ async fn test_grpc_token_validation_flow_invalid_token() {
     println!("[>] Creating gRPC request with invalid token.");
     let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
         token: "invalid_token".to_string(),
+        ..Default::default()
     });
 
     request
@@ -194,6 +196,7 @@ async fn test_grpc_token_validation_flow_missing_auth_header() {
     println!("[>] Creating gRPC request with missing auth header.");
     let request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
         token: "some_token".to_string(),
+        ..Default::default()
     });
 
     println!("[>] Sending gRPC request to validate_authentication.");
@@ -221,6 +224,7 @@ async fn test_grpc_token_validation_flow_malformed_auth_header() {
     println!("[>] Creating gRPC request with malformed auth header.");
     let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
         token: "some_token".to_string(),
+        ..Default::default()
     });
 
     // Malformed auth header (not "Bearer token" format)
@@ -261,6 +265,7 @@ async fn test_grpc_token_validation_flow_admin_token() {
     println!("[>] Creating gRPC request with admin token.");
     let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
         token: admin_token.clone(),
+        ..Default::default()
     });
 
     let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
@@ -306,6 +311,7 @@ async fn test_grpc_token_validation_flow_token_mismatch() {
     println!("[>] Creating gRPC request with token mismatch.");
     let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
         token: "different_token".to_string(),
+        ..Default::default()
     });
 
     request.metadata_mut().insert(
@@ -0,0 +1,237 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use common::{client::TestClient, test_data, TestContext};
+use ledger_auth::grpc::pb::authentication_server::Authentication;
+use tonic::Request;
+
+#[tokio::test]
+async fn test_email_verification_flow_success() {
+    println!("\n\n[+] Running test: test_email_verification_flow_success");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    println!("[+] Test client and context created.");
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let (user_id, email, user_token) = client
+        .create_pending_test_user()
+        .await
+        .expect("Failed creating a pending test user");
+
+    println!("[>] Validating token before verification (expecting failure).");
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let code = ctx
+        .db
+        .create_email_verification(&user_id)
+        .await
+        .expect("Failed to create verification code");
+
+    println!("[>] Sending verification code.");
+    let req = test::TestRequest::post()
+        .uri("/user/verify")
+        .set_json(serde_json::json!({ "email": email, "code": code }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let user = ctx.db.get_user_by_id(&user_id).await.unwrap();
+    assert!(user.email_verified_at.is_some());
+
+    println!("[>] Validating token after verification.");
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] Sending the code again (fails like an unknown email).");
+    let req = test::TestRequest::post()
+        .uri("/user/verify")
+        .set_json(serde_json::json!({ "email": email, "code": code }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    println!("[/] Test passed: Email verification activated the token.");
+}
+
+#[tokio::test]
+async fn test_email_verification_flow_link() {
+    println!("\n\n[+] Running test: test_email_verification_flow_link");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, email, _user_token) = client
+        .create_pending_test_user()
+        .await
+        .expect("Failed creating a pending test user");
+    let code = ctx.db.create_email_verification(&user_id).await.unwrap();
+
+    println!("[>] Following verification link.");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/user/verify?email={}&code={}",
+            urlencoding::encode(&email),
+            code
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+    println!("[/] Test passed: Verification link accepted.");
+}
+
+#[tokio::test]
+async fn test_email_verification_flow_wrong_code() {
+    println!("\n\n[+] Running test: test_email_verification_flow_wrong_code");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+
+    let (user_id, email, _user_token) = client
+        .create_pending_test_user()
+        .await
+        .expect("Failed creating a pending test user");
+    ctx.db.create_email_verification(&user_id).await.unwrap();
+
+    println!("[>] Sending wrong verification code.");
+    let req = test::TestRequest::post()
+        .uri("/user/verify")
+        .set_json(serde_json::json!({ "email": email, "code": "not-the-code" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let user = ctx.db.get_user_by_id(&user_id).await.unwrap();
+    assert!(user.email_verified_at.is_none());
+    println!("[/] Test passed: Wrong code rejected.");
+}
+
+#[tokio::test]
+async fn test_email_verification_flow_created_user_is_pending() {
+    println!("\n\n[+] Running test: test_email_verification_flow_created_user_is_pending");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    let (_admin_id, admin_token) = client.create_test_admin().await;
+
+    let user_data = test_data::sample_user_with_email("pending@example.com");
+    let req = test::TestRequest::post()
+        .uri("/user/create")
+        .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+        .set_json(&user_data)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let user = ctx.db.get_user_by_email(&user_data.email).await.unwrap();
+    assert!(user.email_verified_at.is_none());
+    println!("[/] Test passed: Admin-created users start pending.");
+}
+
+#[tokio::test]
+async fn test_grpc_token_validation_flow_pending_user() {
+    println!("\n\n[+] Running test: test_grpc_token_validation_flow_pending_user");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+
+    let (_user_id, _email, user_token) = client
+        .create_pending_test_user()
+        .await
+        .expect("Failed creating a pending test user");
+
+    let auth_svc = ledger_auth::grpc::authentication::AuthenticationSvc::new(ctx.db.clone());
+    let mut request = Request::new(ledger_auth::grpc::pb::ValidationRequest {
+        token: user_token,
+        ..Default::default()
+    });
+    let grpc_auth_key = ledger_auth::config::config().grpc.auth_key.clone();
+    request
+        .metadata_mut()
+        .insert("authorization", grpc_auth_key.parse().unwrap());
+
+    let validation_response = auth_svc
+        .validate_authentication(request)
+        .await
+        .unwrap()
+        .into_inner();
+    println!("[<] gRPC response body: {:?}", validation_response);
+
+    assert!(!validation_response.is_valid);
+    assert_eq!(validation_response.message, "pending email verification");
+    println!("[/] Test passed: Pending user refused over gRPC.");
+}
+
+#[tokio::test]
+async fn test_email_verification_flow_email_change() {
+    println!("\n\n[+] Running test: test_email_verification_flow_email_change");
+    let ctx = TestContext::new().await;
+    let client = TestClient::new(ctx.db.clone());
+    let app = test::init_service(client.create_app()).await;
+    println!("[+] Actix web app initialized.");
+
+    let old_email = format!("old-{}@test.com", uuid::Uuid::new_v4());
+    let new_email = format!("new-{}@test.com", uuid::Uuid::new_v4());
+    let (user_id, user_token) = client
+        .create_test_user(Some(old_email.clone()))
+        .await
+        .expect("Failed creating a test user");
+
+    println!("[>] Asking to move to {}.", new_email);
+    let req = test::TestRequest::put()
+        .uri("/user/email")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .set_json(serde_json::json!({ "email": new_email }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    println!("[>] The account keeps its address and token until verification.");
+    let user = ctx.db.get_user_by_id(&user_id).await.unwrap();
+    assert_eq!(user.email, old_email);
+    assert_eq!(user.pending_email.as_deref(), Some(new_email.as_str()));
+    assert!(user.email_verified_at.is_some());
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(("Authorization", format!("Bearer {}", user_token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let code = ctx.db.create_email_verification(&user_id).await.unwrap();
+
+    println!("[>] The code only works for the new address.");
+    let req = test::TestRequest::post()
+        .uri("/user/verify")
+        .set_json(serde_json::json!({ "email": old_email, "code": code }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::post()
+        .uri("/user/verify")
+        .set_json(serde_json::json!({ "email": new_email, "code": code }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    println!("[<] Received response with status: {}", resp.status());
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let user = ctx.db.get_user_by_id(&user_id).await.unwrap();
+    assert_eq!(user.email, new_email);
+    assert_eq!(user.pending_email, None);
+    println!("[/] Test passed: The new email replaced the old one only once verified.");
+}